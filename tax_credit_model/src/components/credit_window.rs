@@ -0,0 +1,27 @@
+use askama::Template;
+
+use crate::schema::simulation::CreditYearSummary;
+
+#[derive(Template, Default, Debug, Clone)]
+#[template(path = "components/credit_window.html")]
+pub struct CreditWindowPanel {
+    pub credit_years: Vec<CreditYearSummary>,
+    pub kg_hydrogen: f64,
+    pub tax_credit_usd: f64,
+}
+
+impl CreditWindowPanel {
+    pub fn render(credit_years: Vec<CreditYearSummary>) -> Self {
+        Self {
+            kg_hydrogen: credit_years
+                .iter()
+                .map(|credit_year| credit_year.kg_hydrogen)
+                .sum(),
+            tax_credit_usd: credit_years
+                .iter()
+                .map(|credit_year| credit_year.tax_credit_usd)
+                .sum(),
+            credit_years,
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     schema::{
         electrolyzer::{Electrolyzer, ElectrolyzerDetailsState, ElectrolyzerId},
         endpoints::Endpoint,
+        ercot::SettlementPointLocation,
     },
 };
 
@@ -14,6 +15,7 @@ use super::{
     event::EventListener,
     icon::{Icon, IconColor, IconKind, IconSize},
     input::Input,
+    select::Select,
 };
 
 #[derive(Template, Default, Debug)]
@@ -22,12 +24,15 @@ pub struct CreateElectrolyzerForm {
     endpoint: Endpoint,
     create_electrolyzer_button: Button,
     conversion_rate_badge: Badge,
+    efficiency_curve_badge: Badge,
+    minimum_stable_load_badge: Badge,
     opex_badge: Badge,
     capex_badge: Badge,
     capacity_badge: Badge,
     degradation_rate_badge: Badge,
     replacement_threshold_badge: Badge,
     replacement_cost_badge: Badge,
+    settlement_point_select: Select,
     left_arrow_icon: Icon,
 }
 
@@ -42,6 +47,16 @@ impl CreateElectrolyzerForm {
             replacement_threshold_badge: Badge::render("%"),
             replacement_cost_badge: Badge::render("$ / replacement"),
             conversion_rate_badge: Badge::render("kg / MW"),
+            efficiency_curve_badge: Badge::render("load : kg / MW"),
+            minimum_stable_load_badge: Badge::render("load"),
+            settlement_point_select: Select::render(
+                "settlement_point",
+                &SettlementPointLocation::default().to_string(),
+                SettlementPointLocation::all()
+                    .iter()
+                    .map(|settlement_point| settlement_point.to_string())
+                    .collect(),
+            ),
             create_electrolyzer_button: Button::render(
                 "Create Electrolyzer",
                 Endpoint::CreateElectrolyzer,
@@ -66,6 +81,8 @@ pub struct ElectrolyzerDetails {
     pub state_icon: Icon,
     pub select_electrolyzer_button: Button,
     pub conversion_rate_badge: Badge,
+    pub efficiency_curve_badge: Badge,
+    pub minimum_stable_load_badge: Badge,
     pub opex_badge: Badge,
     pub capex_badge: Badge,
     pub capacity_badge: Badge,
@@ -131,6 +148,8 @@ impl ElectrolyzerDetails {
             replacement_threshold_badge: Badge::render("%"),
             replacement_cost_badge: Badge::render("$ / replacement"),
             conversion_rate_badge: Badge::render("kg / MW"),
+            efficiency_curve_badge: Badge::render("load : kg / MW"),
+            minimum_stable_load_badge: Badge::render("load"),
             left_arrow_icon: Icon::render_filled(
                 IconKind::LeftArrow,
                 IconSize::Small,
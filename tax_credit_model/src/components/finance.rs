@@ -0,0 +1,49 @@
+use askama::Template;
+
+use crate::schema::finance::{CashFlowYear, FinancialConfiguration, FinancialReport};
+
+const NOT_APPLICABLE: &str = "N/A";
+
+#[derive(Template, Default, Debug, Clone)]
+#[template(path = "components/financial_report.html")]
+pub struct FinancialReportPanel {
+    pub levelized_cost: String,
+    pub levelized_cost_net_of_credit: String,
+    pub net_present_value: String,
+    pub internal_rate_of_return: String,
+    pub payback: String,
+    pub discount_rate: f64,
+    pub project_life_years: u32,
+    pub cash_flows: Vec<CashFlowYear>,
+}
+
+impl FinancialReportPanel {
+    pub fn render(report: FinancialReport, configuration: &FinancialConfiguration) -> Self {
+        Self {
+            levelized_cost: report
+                .levelized_cost_usd_per_kg
+                .map_or(String::from(NOT_APPLICABLE), |cost| {
+                    format!("${:.2} / kg", cost)
+                }),
+            levelized_cost_net_of_credit: report
+                .levelized_cost_net_of_credit_usd_per_kg
+                .map_or(String::from(NOT_APPLICABLE), |cost| {
+                    format!("${:.2} / kg", cost)
+                }),
+            net_present_value: format!("${:.2}", report.net_present_value_usd),
+            internal_rate_of_return: report
+                .internal_rate_of_return
+                .map_or(String::from(NOT_APPLICABLE), |rate| {
+                    format!("{:.2}%", rate * 100.0)
+                }),
+            payback: report
+                .payback_years
+                .map_or(String::from(NOT_APPLICABLE), |years| {
+                    format!("{:.1} years", years)
+                }),
+            discount_rate: configuration.discount_rate,
+            project_life_years: configuration.project_life_years,
+            cash_flows: report.cash_flows,
+        }
+    }
+}
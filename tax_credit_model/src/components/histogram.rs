@@ -7,7 +7,7 @@ use crate::schema::{endpoints::Endpoint, histogram::Histogram};
 
 use super::input::Input;
 
-#[derive(Template, Default, Debug)]
+#[derive(Template, Default, Debug, Clone)]
 #[template(path = "components/histogram.html")]
 pub struct HistogramResponse {
     pub id: String,
@@ -2,14 +2,14 @@ use askama::Template;
 
 use crate::schema::endpoints::Endpoint;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub enum InputVariant {
     #[default]
     Default,
     Hidden,
 }
 
-#[derive(Template, Debug, Default)]
+#[derive(Template, Debug, Default, Clone)]
 #[template(path = "components/input.html")]
 pub struct Input {
     variant: InputVariant,
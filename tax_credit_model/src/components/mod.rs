@@ -4,10 +4,13 @@ pub mod component;
 pub mod electrolyzer;
 pub mod error;
 pub mod event;
+pub mod credit_window;
+pub mod finance;
 pub mod form_field;
 pub mod histogram;
 pub mod icon;
 pub mod input;
+pub mod plant;
 pub mod select;
 pub mod simulation;
 pub mod time_series;
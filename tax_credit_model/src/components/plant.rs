@@ -0,0 +1,27 @@
+use askama::Template;
+
+use crate::schema::plant::PlantPurchaseSummary;
+
+#[derive(Template, Default, Debug, Clone)]
+#[template(path = "components/plant_purchases.html")]
+pub struct PlantPurchasePanel {
+    pub plant_purchases: Vec<PlantPurchaseSummary>,
+    pub purchased_mwh: f64,
+    pub energy_usd: f64,
+}
+
+impl PlantPurchasePanel {
+    pub fn render(plant_purchases: Vec<PlantPurchaseSummary>) -> Self {
+        Self {
+            purchased_mwh: plant_purchases
+                .iter()
+                .map(|plant_purchase| plant_purchase.purchased_mwh)
+                .sum(),
+            energy_usd: plant_purchases
+                .iter()
+                .map(|plant_purchase| plant_purchase.energy_usd)
+                .sum(),
+            plant_purchases,
+        }
+    }
+}
@@ -2,16 +2,25 @@ use askama::Template;
 
 use crate::{
     client::{events::ClientEvent, htmx::HtmxSwap},
+    jobs::simulation_runner::SimulationJobStatus,
     logic::simulation::SimulationState,
     schema::{
+        emissions::{EmissionFactorSet, DEFAULT_EMISSION_FACTOR_SET_ID},
         endpoints::Endpoint,
-        simulation::{SimulationId, SimulationResult},
-        time::DateTimeRange,
+        grid::GridRegion,
+        policy::TaxCredit45VRulesetVersion,
+        simulation::{
+            AccountingMode, CertificateMatching, DispatchObjective, DispatchStrategyKind,
+            GapFillPolicy, MarginalEmissionsSource, MissingYearSource, ProcurementMode,
+            SimulationId, SimulationResult, SimulationStatus, SimulationStep,
+        },
+        time::{DateTimeRange, DisplayTimeZone},
     },
 };
 
 use super::{
     button::Button, electrolyzer::ElectrolyzerSelector, event::EventListener, input::Input,
+    select::Select,
 };
 
 #[derive(Debug, Default, Template)]
@@ -25,6 +34,7 @@ impl SimulationList {
     pub fn render(simulations: Vec<SimulationState>) -> SimulationList {
         let list_items = simulations.iter().map(|simulation| SimulationListItem {
             id: simulation.id,
+            status: simulation.status.to_string(),
             id_input: Input::render_hidden(&simulation.id.to_string(), "simulation_id"),
             endpoint: Endpoint::SelectSimulation,
         });
@@ -46,6 +56,7 @@ impl SimulationList {
 pub struct SimulationListItem {
     endpoint: Endpoint,
     id: SimulationId,
+    status: String,
     id_input: Input,
 }
 
@@ -54,6 +65,19 @@ pub struct SimulationListItem {
 pub struct SimulationView {
     generation_range: DateTimeRange,
     electrolyzer_selector: ElectrolyzerSelector,
+    dispatch_strategy_select: Select,
+    dispatch_objective_select: Select,
+    accounting_mode_select: Select,
+    emission_factor_set_select: Select,
+    tax_credit_ruleset_select: Select,
+    missing_year_source_select: Select,
+    simulation_step_select: Select,
+    display_time_zone_select: Select,
+    procurement_mode_select: Select,
+    gap_fill_policy_select: Select,
+    grid_region_select: Select,
+    marginal_emissions_source_select: Select,
+    certificate_matching_select: Select,
     create_electrolyzer_listener: EventListener,
     list_simulation_button: Button,
     simulate_button: Button,
@@ -63,6 +87,8 @@ impl SimulationView {
     pub fn render(
         generation_range: DateTimeRange,
         electrolyzer_selector: ElectrolyzerSelector,
+        emission_factor_sets: Vec<EmissionFactorSet>,
+        grid_regions: Vec<GridRegion>,
     ) -> Self {
         SimulationView {
             create_electrolyzer_listener: EventListener::render(
@@ -73,6 +99,110 @@ impl SimulationView {
             ),
             generation_range,
             electrolyzer_selector,
+            dispatch_strategy_select: Select::render(
+                "dispatch_strategy.kind",
+                &DispatchStrategyKind::default().to_string(),
+                DispatchStrategyKind::all()
+                    .iter()
+                    .map(|kind| kind.to_string())
+                    .collect(),
+            ),
+            dispatch_objective_select: Select::render(
+                "dispatch_strategy.objective",
+                &DispatchObjective::default().to_string(),
+                vec![
+                    DispatchObjective::MinimizeCost.to_string(),
+                    DispatchObjective::MinimizeNetCost.to_string(),
+                ],
+            ),
+            accounting_mode_select: Select::render(
+                "accounting_mode",
+                &AccountingMode::default().to_string(),
+                AccountingMode::all()
+                    .iter()
+                    .map(|mode| mode.to_string())
+                    .collect(),
+            ),
+            emission_factor_set_select: Select::render(
+                "emission_factor_set_id",
+                DEFAULT_EMISSION_FACTOR_SET_ID,
+                emission_factor_sets
+                    .iter()
+                    .map(|emission_factor_set| emission_factor_set.id())
+                    .collect(),
+            ),
+            tax_credit_ruleset_select: Select::render(
+                "tax_credit_policy.version",
+                &TaxCredit45VRulesetVersion::default().to_string(),
+                TaxCredit45VRulesetVersion::all()
+                    .iter()
+                    .map(|version| version.to_string())
+                    .collect(),
+            ),
+            missing_year_source_select: Select::render(
+                "credit_window.missing_year_source",
+                &MissingYearSource::default().to_string(),
+                MissingYearSource::all()
+                    .iter()
+                    .map(|source| source.to_string())
+                    .collect(),
+            ),
+            simulation_step_select: Select::render(
+                "simulation_step",
+                &SimulationStep::default().to_string(),
+                SimulationStep::all()
+                    .iter()
+                    .map(|step| step.to_string())
+                    .collect(),
+            ),
+            display_time_zone_select: Select::render(
+                "display_time_zone",
+                &DisplayTimeZone::default().to_string(),
+                DisplayTimeZone::all()
+                    .iter()
+                    .map(|time_zone| time_zone.to_string())
+                    .collect(),
+            ),
+            procurement_mode_select: Select::render(
+                "procurement_mode",
+                &ProcurementMode::default().to_string(),
+                ProcurementMode::all()
+                    .iter()
+                    .map(|mode| mode.to_string())
+                    .collect(),
+            ),
+            grid_region_select: Select::render(
+                "grid_region",
+                &GridRegion::default().to_string(),
+                grid_regions
+                    .iter()
+                    .map(|region| region.to_string())
+                    .collect(),
+            ),
+            marginal_emissions_source_select: Select::render(
+                "marginal_emissions_source",
+                &MarginalEmissionsSource::default().to_string(),
+                MarginalEmissionsSource::all()
+                    .iter()
+                    .map(|source| source.to_string())
+                    .collect(),
+            ),
+            certificate_matching_select: Select::render(
+                "certificate_matching",
+                &CertificateMatching::default().to_string(),
+                CertificateMatching::all()
+                    .iter()
+                    .map(|matching| matching.to_string())
+                    .collect(),
+            ),
+            gap_fill_policy_select: Select::render(
+                "gap_fill_policy",
+                &GapFillPolicy::default().to_string(),
+                GapFillPolicy::all()
+                    .iter()
+                    .map(|policy| policy.to_string())
+                    .collect(),
+            ),
             list_simulation_button: Button::render_outline(
                 "View Runs",
                 Endpoint::ListSimulations,
@@ -91,14 +221,58 @@ impl SimulationView {
 #[template(path = "components/simulation_result.html")]
 pub struct SimulationResultView {
     pub simulation_result: SimulationResult,
-    pub simulation_view: SimulationView,
 }
 
 impl SimulationResultView {
-    pub fn render(simulation_view: SimulationView, simulation_result: SimulationResult) -> Self {
+    pub fn render(simulation_result: SimulationResult) -> Self {
+        Self { simulation_result }
+    }
+}
+
+#[derive(Template, Default, Debug)]
+#[template(path = "components/simulation_progress.html")]
+pub struct SimulationProgressView {
+    simulation_id: SimulationId,
+    status: String,
+    message: String,
+    progress_percent: f64,
+    polling: bool,
+    progress_endpoint: Endpoint,
+    cancel_button: Button,
+    result: Option<SimulationResultView>,
+}
+
+impl SimulationProgressView {
+    pub fn render(simulation_id: SimulationId, job_status: SimulationJobStatus) -> Self {
+        let progress_percent = match job_status.status {
+            SimulationStatus::Running { progress } => progress * 100.0,
+            SimulationStatus::Complete => 100.0,
+            _ => 0.0,
+        };
+
+        Self {
+            simulation_id,
+            status: job_status.status.to_string(),
+            message: job_status.message,
+            progress_percent,
+            polling: !job_status.status.is_finished(),
+            progress_endpoint: Endpoint::FetchSimulationProgress,
+            cancel_button: Button::render_outline(
+                "Cancel",
+                Endpoint::CancelSimulation,
+                "#simulation-result",
+            ),
+            result: None,
+        }
+    }
+
+    pub fn render_result(simulation_id: SimulationId, result: SimulationResult) -> Self {
         Self {
-            simulation_view,
-            simulation_result,
+            simulation_id,
+            status: SimulationStatus::Complete.to_string(),
+            progress_percent: 100.0,
+            result: Some(SimulationResultView::render(result)),
+            ..Default::default()
         }
     }
 }
@@ -7,7 +7,7 @@ use crate::schema::{endpoints::Endpoint, time_series::TimeSeriesChart};
 
 use super::input::Input;
 
-#[derive(Template, Default, Debug)]
+#[derive(Template, Default, Debug, Clone)]
 #[template(path = "components/time_series_chart.html")]
 pub struct TimeSeriesChartResponse {
     pub id: String,
@@ -1,14 +1,33 @@
 use std::process::{self, exit};
 
+use crate::jobs::data_quality::DataQualityJob;
 use crate::jobs::ercot_data_retriever::ErcotDataRetrieverJob;
+use crate::jobs::grid_data_source::{grid_data_sources, GridDataMonth};
+use crate::jobs::marginal_emissions::MarginalEmissionsJob;
+use crate::jobs::plant_generation::PlantGenerationJob;
+use crate::schema::data_quality::{DataQualityPolicy, DataQualityReport, MonthlyDataQualityReport};
+use crate::schema::grid::GridRegion;
+use crate::schema::plant::PowerPlantDetails;
+use crate::schema::simulation::GenerationMetric;
+use crate::schema::time::TimeRange;
 
 use crate::server::{Dependencies, ServerConfiguration};
 
-const MONTHS: [&'static str; 12] = [
-    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
-];
-
 pub fn fill_generations(configuration: ServerConfiguration, dependencies: &Dependencies) {
+    let plants = dependencies
+        .plant_registry_client
+        .list_plants()
+        .unwrap_or_else(|err| {
+            eprintln!("{err}");
+            exit(1)
+        });
+    dependencies
+        .grid_client
+        .register_power_plants(plants.clone())
+        .unwrap_or_else(|err| {
+            eprintln!("{err}");
+            exit(1)
+        });
     let generations = dependencies
         .generation_client
         .list_generations()
@@ -25,34 +44,165 @@ pub fn fill_generations(configuration: ServerConfiguration, dependencies: &Depen
         return;
     }
 
-    for month in MONTHS {
-        let input = ErcotDataRetrieverJob::extract(&configuration.data_directory, month)
-            .unwrap_or_else(|err| {
-                eprintln!("{err}");
-                process::exit(1);
-            });
-        let generations = ErcotDataRetrieverJob::transform(input).unwrap_or_else(|err| {
-            eprintln!("{err}");
+    let policy = configuration.data_quality_policy;
+    let mut report = DataQualityReport {
+        policy,
+        ..Default::default()
+    };
+    let mut generations = vec![];
+
+    let sources = grid_data_sources(&configuration.data_directory).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(1)
+    });
+
+    for source in sources {
+        let months = source.months(&TimeRange::all()).unwrap_or_else(|err| {
+            eprintln!("{}: {err}", source.region());
             process::exit(1);
         });
+        let skipped_files = source.skipped_files().unwrap_or_else(|err| {
+            eprintln!("{}: {err}", source.region());
+            process::exit(1);
+        });
+        report.skipped_files.extend(skipped_files);
 
-        if generations.is_empty() {
-            continue;
+        for month in months {
+            let (month_generations, month_report) = inspect_month(&source.region(), month, policy);
+            generations.extend(month_generations);
+            report.months.push(month_report);
         }
+    }
 
-        for generation in &generations {
-            dependencies
-                .generation_client
-                .create_generation(generation)
-                .unwrap_or_else(|err| {
-                    eprintln!("{err}");
-                    exit(1)
-                });
-        }
+    let plant_generations = plants
+        .iter()
+        .flat_map(|plant| {
+            read_plant_generations(&configuration.data_directory, plant, &generations)
+        })
+        .collect::<Vec<GenerationMetric>>();
+    generations.extend(plant_generations);
 
-        ErcotDataRetrieverJob::load(generations, &dependencies.grid_client).unwrap_or_else(|err| {
+    for region in list_regions(&generations) {
+        apply_marginal_emission_rates(&configuration.data_directory, &region, &mut generations);
+    }
+
+    dependencies
+        .data_quality_report_client
+        .save_report(&report)
+        .unwrap_or_else(|err| {
             eprintln!("{err}");
             exit(1)
         });
+
+    if policy == DataQualityPolicy::Fail && report.has_issues() {
+        for month in report
+            .months
+            .iter()
+            .filter(|month| !month.issues.is_empty())
+        {
+            eprintln!(
+                "{}: {} data quality issues",
+                month.month,
+                month.issues.len()
+            );
+        }
+
+        eprintln!("Ingestion stopped by the Fail data quality policy");
+        exit(1);
     }
+
+    for generation in &generations {
+        dependencies
+            .generation_client
+            .create_generation(generation)
+            .unwrap_or_else(|err| {
+                eprintln!("{err}");
+                exit(1)
+            });
+    }
+
+    ErcotDataRetrieverJob::load(generations, &dependencies.grid_client).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(1)
+    });
+}
+
+fn list_regions(generations: &[GenerationMetric]) -> Vec<GridRegion> {
+    let mut regions = vec![];
+
+    for generation in generations {
+        if !regions.contains(&generation.region) {
+            regions.push(generation.region.clone());
+        }
+    }
+
+    regions
+}
+
+fn apply_marginal_emission_rates(
+    data_directory: &str,
+    region: &GridRegion,
+    generations: &mut [GenerationMetric],
+) {
+    let mut issues = vec![];
+    let rates = MarginalEmissionsJob::extract(data_directory, region)
+        .and_then(|sheet| MarginalEmissionsJob::transform(region, &sheet, &mut issues))
+        .unwrap_or_else(|err| {
+            eprintln!("{region} marginal emissions: {err}");
+            exit(1)
+        });
+
+    if !issues.is_empty() {
+        eprintln!(
+            "{region} marginal emissions: {} unparseable rows",
+            issues.len()
+        );
+    }
+
+    MarginalEmissionsJob::apply(generations, region, &rates);
+}
+
+fn read_plant_generations(
+    data_directory: &str,
+    plant: &PowerPlantDetails,
+    grid_generations: &[GenerationMetric],
+) -> Vec<GenerationMetric> {
+    let mut issues = vec![];
+    let generations =
+        PlantGenerationJob::extract(&format!("{}/{}", data_directory, "plants"), plant)
+            .and_then(|sheet| {
+                PlantGenerationJob::transform(plant, &sheet, grid_generations, &mut issues)
+            })
+            .unwrap_or_else(|err| {
+                eprintln!("{}: {err}", plant.name);
+                exit(1)
+            });
+
+    if !issues.is_empty() {
+        eprintln!("{}: {} unparseable rows", plant.name, issues.len());
+    }
+
+    generations
+}
+
+fn inspect_month(
+    region: &GridRegion,
+    month: GridDataMonth,
+    policy: DataQualityPolicy,
+) -> (Vec<GenerationMetric>, MonthlyDataQualityReport) {
+    let month_name = format!("{} {}", region, month.month);
+    let (generations, mut report) = DataQualityJob::inspect(
+        &month_name,
+        month.generations,
+        month.unparseable_rows,
+        policy,
+    );
+
+    report
+        .issues
+        .extend(ErcotDataRetrieverJob::initial_settlement_issue(
+            &generations,
+        ));
+
+    (generations, report)
 }
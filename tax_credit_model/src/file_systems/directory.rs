@@ -1,7 +1,9 @@
-use std::fs::create_dir_all;
-
-use crate::schema::errors::{Result, Error};
+use std::{
+    fs::{create_dir_all, read_dir},
+    path::Path,
+};
 
+use crate::schema::errors::{Error, Result};
 
 #[derive(Default, Debug, Eq, PartialEq)]
 pub struct Directory {
@@ -24,5 +26,30 @@ impl Directory {
 
         Ok(Directory::new(path))
     }
-}
 
+    pub fn list_files(&self) -> Result<Vec<String>> {
+        self.list_paths(|path| path.is_file())
+    }
+
+    pub fn list_directories(&self) -> Result<Vec<String>> {
+        self.list_paths(|path| path.is_dir())
+    }
+
+    fn list_paths(&self, is_listed: fn(&Path) -> bool) -> Result<Vec<String>> {
+        let mut paths = read_dir(&self.path)
+            .map_err(|err| Error::invalid_argument(&err.to_string()))?
+            .map(|entry| {
+                entry
+                    .map(|entry| entry.path())
+                    .map_err(|err| Error::invalid_argument(&err.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|path| is_listed(path))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        paths.sort();
+
+        Ok(paths)
+    }
+}
@@ -0,0 +1,27 @@
+use rocket::{form::Form, post, State};
+
+use crate::{
+    components::{
+        component::{Component, ComponentResponse},
+        error::BannerError,
+        simulation::SimulationProgressView,
+    },
+    jobs::simulation_runner::SimulationJobRunner,
+    responders::htmx_responder::HtmxHeadersBuilder,
+    schema::{simulation::SimulationJobRequest, user::User},
+};
+
+#[post("/cancel_simulation", data = "<request>")]
+pub fn cancel_simulation_handler(
+    request: Form<SimulationJobRequest>,
+    simulation_runner: &State<SimulationJobRunner>,
+    user: User,
+) -> ComponentResponse<SimulationProgressView, BannerError> {
+    Component::component(
+        HtmxHeadersBuilder::new().build(),
+        SimulationProgressView::render(
+            request.simulation_id,
+            simulation_runner.cancel(&user.id, &request.simulation_id)?,
+        ),
+    )
+}
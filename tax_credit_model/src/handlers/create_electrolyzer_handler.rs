@@ -9,8 +9,12 @@ use crate::{
     },
     persistance::electrolyzer::ElectrolyzerClient,
     responders::htmx_responder::HtmxHeadersBuilder,
-    schema::electrolyzer::{
-        ConstantProduction, CreateElectrolyzerRequest, Electrolyzer, ProductionType,
+    schema::{
+        electrolyzer::{
+            ConstantProduction, CreateElectrolyzerRequest, CreateProductionRequest, Electrolyzer,
+            ProductionType, VariableProduction,
+        },
+        errors::{Error, Result},
     },
 };
 
@@ -20,6 +24,7 @@ pub fn create_electrolyzer_handler(
     electrolyzer_client: &State<Box<dyn ElectrolyzerClient>>,
 ) -> ComponentResponse<ElectrolyzerDetails, BannerError> {
     let electrolyzers = electrolyzer_client.list_electrolyzers()?;
+    let (production, variable_production) = parse_production(&request.production_method)?;
     let electrolyzer = electrolyzer_client.create_electrolyzer(&Electrolyzer {
         id: 0,
         name: String::from(&request.name),
@@ -28,18 +33,11 @@ pub fn create_electrolyzer_handler(
         capacity_mw: request.capacity_mw,
         opex: request.opex,
         capex: request.capex,
-        production: ConstantProduction {
-            conversion_rate: request
-                .production_method
-                .conversion_rate_constant
-                .ok_or_else(|| {
-                    BannerError::create_from_message("Only constant production is allowed")
-                })?,
-        },
-        production_type: ProductionType::Constant,
+        production,
+        variable_production,
+        production_type: request.production_method.production_type,
         replacement_cost: request.replacement_cost,
-        city: String::from("Huston"),
-        state: String::from("TX"),
+        settlement_point: request.settlement_point,
     })?;
 
     if electrolyzers.is_empty() {
@@ -58,3 +56,35 @@ pub fn create_electrolyzer_handler(
         ElectrolyzerDetails::render_unselected(electrolyzer),
     )
 }
+
+// Variable electrolyzers still carry a constant conversion rate, which is
+// their full load rate from the efficiency curve
+fn parse_production(
+    request: &CreateProductionRequest,
+) -> Result<(ConstantProduction, VariableProduction)> {
+    match request.production_type {
+        ProductionType::Constant => Ok((
+            ConstantProduction {
+                conversion_rate: request.conversion_rate_constant.ok_or_else(|| {
+                    Error::invalid_argument("Constant production requires a production rate")
+                })?,
+            },
+            VariableProduction::default(),
+        )),
+        ProductionType::Variable => {
+            let variable_production = VariableProduction::new(
+                request.efficiency_curve.as_ref().ok_or_else(|| {
+                    Error::invalid_argument("Variable production requires an efficiency curve")
+                })?,
+                request.minimum_stable_load.unwrap_or_default(),
+            )?;
+
+            Ok((
+                ConstantProduction {
+                    conversion_rate: variable_production.conversion_rate_at(1.0),
+                },
+                variable_production,
+            ))
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use rocket::{get, State};
+
+use crate::{
+    pages::{
+        data_quality::DataQualityPage,
+        page::{Page, PageResponse},
+    },
+    persistance::data_quality::DataQualityReportClient,
+};
+
+#[get("/admin/data_quality")]
+pub fn data_quality_handler(
+    data_quality_report_client: &State<Box<dyn DataQualityReportClient>>,
+) -> PageResponse<DataQualityPage> {
+    Page::basic_page(DataQualityPage::render(
+        data_quality_report_client.get_report()?,
+    ))
+}
@@ -3,14 +3,15 @@ use rocket::{form::Form, post, State};
 use crate::{
     components::{
         component::{Component, ComponentResponse},
-        electrolyzer::ElectrolyzerSelector,
         error::BannerError,
-        simulation::{SimulationResultView, SimulationView},
+        simulation::SimulationProgressView,
     },
-    logic::simulation::{simulate, SimulationState},
+    jobs::simulation_runner::{SimulationJob, SimulationJobRunner},
+    logic::simulation::SimulationState,
     persistance::{
-        electrolyzer::ElectrolyzerClient, generation::GenerationClient, grid::GridClient,
-        simulation::SimulationClient, simulation_selection::SimulationSelectionClient,
+        certificates::CertificateClient, electrolyzer::ElectrolyzerClient,
+        emission_factors::EmissionFactorClient, grid::GridClient, simulation::SimulationClient,
+        simulation_selection::SimulationSelectionClient,
     },
     responders::{client_context::ClientContext, htmx_responder::HtmxHeadersBuilder},
     schema::{simulation::ExecuteSimulationRequest, user::User},
@@ -25,13 +26,26 @@ pub fn execute_simulation(
     electrolyzer_client: &State<Box<dyn ElectrolyzerClient>>,
     simulation_client: &State<Box<dyn SimulationClient>>,
     simulation_selection_client: &State<Box<dyn SimulationSelectionClient>>,
-    generation_client: &State<Box<dyn GenerationClient>>,
-) -> ComponentResponse<SimulationResultView, BannerError> {
+    emission_factor_client: &State<Box<dyn EmissionFactorClient>>,
+    certificate_client: &State<Box<dyn CertificateClient>>,
+    simulation_runner: &State<SimulationJobRunner>,
+) -> ComponentResponse<SimulationProgressView, BannerError> {
     let mut client_context = client_context;
-    let generation_range = generation_client.get_generation_range()?;
     let electrolyzer = electrolyzer_client.get_electrolyzer(request.electrolyzer_id)?;
-    let power_grid = power_grid_fetcher.get_power_grid()?;
+    let power_grid = power_grid_fetcher.get_power_grid(&request.grid_region)?;
+    let emission_factors =
+        emission_factor_client.get_emission_factor_set(&request.emission_factor_set_id)?;
+    let certificates = certificate_client.list_certificates(&request.grid_region)?;
     let current_simulation_id = simulation_selection_client.expect_current_selection(&user.id)?;
+    simulation_runner.submit(SimulationJob {
+        simulation_id: current_simulation_id,
+        user_id: user.id,
+        power_grid,
+        electrolyzer: electrolyzer.clone(),
+        emission_factors,
+        certificates,
+        request: request.into_inner(),
+    })?;
     let mut next_simulation = SimulationState::default();
     next_simulation.electrolyzer_id = electrolyzer.id;
     let next_simulation = simulation_client.create_simulation_state(&next_simulation)?;
@@ -44,21 +58,9 @@ pub fn execute_simulation(
         HtmxHeadersBuilder::new()
             .replace_url(&location.build_url())
             .build(),
-        SimulationResultView::render(
-            SimulationView::render(
-                generation_range,
-                ElectrolyzerSelector::render(
-                    electrolyzer.id,
-                    electrolyzer_client.list_electrolyzers()?,
-                ),
-            ),
-            simulate(
-                current_simulation_id,
-                &power_grid,
-                &electrolyzer,
-                &request.simulation_time_range,
-                simulation_client.inner(),
-            )?,
+        SimulationProgressView::render(
+            current_simulation_id,
+            simulation_runner.status(&user.id, &current_simulation_id)?,
         ),
     )
 }
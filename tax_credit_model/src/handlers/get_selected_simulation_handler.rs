@@ -9,11 +9,12 @@ use crate::{
     },
     logic::simulation::SimulationState,
     persistance::{
-        electrolyzer::ElectrolyzerClient, simulation::SimulationClient,
+        electrolyzer::ElectrolyzerClient, emission_factors::EmissionFactorClient,
+        generation::GenerationClient, grid::GridClient, simulation::SimulationClient,
         simulation_selection::SimulationSelectionClient,
     },
     responders::htmx_responder::HtmxHeadersBuilder,
-    schema::{time::DateTimeRange, user::User},
+    schema::user::User,
 };
 
 #[post("/get_selected_simulation")]
@@ -22,6 +23,9 @@ pub fn get_selected_simulation_handler(
     electrolyzer_client: &State<Box<dyn ElectrolyzerClient>>,
     simulation_client: &State<Box<dyn SimulationClient>>,
     simulation_selection_client: &State<Box<dyn SimulationSelectionClient>>,
+    emission_factor_client: &State<Box<dyn EmissionFactorClient>>,
+    generation_client: &State<Box<dyn GenerationClient>>,
+    grid_client: &State<Box<dyn GridClient>>,
 ) -> ComponentResponse<SimulationView, BannerError> {
     let electrolyzers = electrolyzer_client.list_electrolyzers()?;
 
@@ -37,11 +41,10 @@ pub fn get_selected_simulation_handler(
     Component::component(
         HtmxHeadersBuilder::new().build(),
         SimulationView::render(
-            DateTimeRange {
-                start: String::from("2023-01-01T00:00"),
-                end: String::from("2023-07-31T23:59"),
-            },
+            generation_client.get_generation_range()?,
             ElectrolyzerSelector::render(simulation_state.electrolyzer_id, electrolyzers),
+            emission_factor_client.list_emission_factor_sets()?,
+            grid_client.list_regions()?,
         ),
     )
 }
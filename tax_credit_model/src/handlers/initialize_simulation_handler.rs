@@ -4,7 +4,8 @@ use crate::{
     components::{component::ComponentResponse, error::BannerError, simulation::SimulationView},
     logic::simulation::SimulationState,
     persistance::{
-        electrolyzer::ElectrolyzerClient, simulation::SimulationClient,
+        electrolyzer::ElectrolyzerClient, emission_factors::EmissionFactorClient,
+        generation::GenerationClient, grid::GridClient, simulation::SimulationClient,
         simulation_selection::SimulationSelectionClient,
     },
     responders::client_context::ClientContext,
@@ -20,6 +21,9 @@ pub fn initialize_simulation_handler(
     electrolyzer_client: &State<Box<dyn ElectrolyzerClient>>,
     simulation_client: &State<Box<dyn SimulationClient>>,
     simulation_selection_client: &State<Box<dyn SimulationSelectionClient>>,
+    emission_factor_client: &State<Box<dyn EmissionFactorClient>>,
+    generation_client: &State<Box<dyn GenerationClient>>,
+    grid_client: &State<Box<dyn GridClient>>,
 ) -> ComponentResponse<SimulationView, BannerError> {
     let electrolyzers = electrolyzer_client.list_electrolyzers()?;
 
@@ -38,5 +42,8 @@ pub fn initialize_simulation_handler(
         simulation_client,
         electrolyzer_client,
         simulation_selection_client,
+        emission_factor_client,
+        generation_client,
+        grid_client,
     )
 }
@@ -1,6 +1,8 @@
+pub mod cancel_simulation_handler;
 pub mod close_error_handler;
 pub mod create_electrolyzer_form_handler;
 pub mod create_electrolyzer_handler;
+pub mod data_quality_handler;
 pub mod electrolyzer_selector_handler;
 pub mod execute_simulation_handler;
 pub mod get_electrolyzer_handler;
@@ -14,3 +16,4 @@ pub mod search_electrolyzers_handler;
 pub mod select_electrolyzer_handler;
 pub mod select_simulation_handler;
 pub mod simulation_handler;
+pub mod simulation_progress_handler;
@@ -9,11 +9,12 @@ use crate::{
         simulation::SimulationView,
     },
     persistance::{
-        electrolyzer::ElectrolyzerClient, simulation::SimulationClient,
+        electrolyzer::ElectrolyzerClient, emission_factors::EmissionFactorClient,
+        generation::GenerationClient, grid::GridClient, simulation::SimulationClient,
         simulation_selection::SimulationSelectionClient,
     },
     responders::{client_context::ClientContext, htmx_responder::HtmxHeadersBuilder},
-    schema::{simulation::SimulationId, time::DateTimeRange, user::User},
+    schema::{simulation::SimulationId, user::User},
 };
 
 #[derive(Debug, FromForm)]
@@ -29,6 +30,9 @@ pub fn select_simulation_handler(
     simulation_client: &State<Box<dyn SimulationClient>>,
     electrolyzer_client: &State<Box<dyn ElectrolyzerClient>>,
     simulation_selection: &State<Box<dyn SimulationSelectionClient>>,
+    emission_factor_client: &State<Box<dyn EmissionFactorClient>>,
+    generation_client: &State<Box<dyn GenerationClient>>,
+    grid_client: &State<Box<dyn GridClient>>,
 ) -> ComponentResponse<SimulationView, BannerError> {
     let mut client_context = client_context;
     let simulation = simulation_client.get_simulation_state(&request.simulation_id)?;
@@ -44,11 +48,10 @@ pub fn select_simulation_handler(
             .trigger(ClientEvent::SelectSimulation)
             .build(),
         SimulationView::render(
-            DateTimeRange {
-                start: String::from("2023-01-01T00:00"),
-                end: String::from("2023-07-31T23:59"),
-            },
+            generation_client.get_generation_range()?,
             ElectrolyzerSelector::render(simulation.electrolyzer_id, electrolyzers),
+            emission_factor_client.list_emission_factor_sets()?,
+            grid_client.list_regions()?,
         ),
     )
 }
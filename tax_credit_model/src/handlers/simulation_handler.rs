@@ -10,11 +10,12 @@ use crate::{
         simulation::SimulationPage,
     },
     persistance::{
-        electrolyzer::ElectrolyzerClient, simulation::SimulationClient,
+        electrolyzer::ElectrolyzerClient, emission_factors::EmissionFactorClient,
+        generation::GenerationClient, grid::GridClient, simulation::SimulationClient,
         simulation_selection::SimulationSelectionClient, user::UserClient,
     },
     responders::{htmx_responder::HtmxHeadersBuilder, user_context::UserContext},
-    schema::{simulation::SimulationId, time::DateTimeRange, user::User},
+    schema::{simulation::SimulationId, user::User},
 };
 
 #[get("/simulation/<simulation_id>")]
@@ -25,6 +26,9 @@ pub fn simulation_handler(
     simulation_client: &State<Box<dyn SimulationClient>>,
     user_client: &State<Box<dyn UserClient>>,
     simulation_selection_client: &State<Box<dyn SimulationSelectionClient>>,
+    emission_factor_client: &State<Box<dyn EmissionFactorClient>>,
+    generation_client: &State<Box<dyn GenerationClient>>,
+    grid_client: &State<Box<dyn GridClient>>,
 ) -> PageResponse<SimulationPage> {
     let mut cookie = None;
     let mut user_context = user_context;
@@ -51,11 +55,10 @@ pub fn simulation_handler(
         SimulationPage {
             electrolyzer_details: ElectrolyzerDetails::render_selected(electrolyzer),
             simulation_view: SimulationView::render(
-                DateTimeRange {
-                    start: String::from("2023-01-01T00:00"),
-                    end: String::from("2023-07-31T23:59"),
-                },
+                generation_client.get_generation_range()?,
                 ElectrolyzerSelector::render(electrolyzer_id, electrolyzers),
+                emission_factor_client.list_emission_factor_sets()?,
+                grid_client.list_regions()?,
             ),
         },
     )
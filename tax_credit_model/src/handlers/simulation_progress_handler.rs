@@ -0,0 +1,39 @@
+use rocket::{form::Form, post, State};
+
+use crate::{
+    components::{
+        component::{Component, ComponentResponse},
+        error::BannerError,
+        simulation::SimulationProgressView,
+    },
+    jobs::simulation_runner::SimulationJobRunner,
+    responders::htmx_responder::HtmxHeadersBuilder,
+    schema::{
+        simulation::{SimulationJobRequest, SimulationStatus},
+        user::User,
+    },
+};
+
+#[post("/simulation_progress", data = "<request>")]
+pub fn simulation_progress_handler(
+    request: Form<SimulationJobRequest>,
+    simulation_runner: &State<SimulationJobRunner>,
+    user: User,
+) -> ComponentResponse<SimulationProgressView, BannerError> {
+    let job_status = simulation_runner.status(&user.id, &request.simulation_id)?;
+
+    if job_status.status == SimulationStatus::Complete {
+        return Component::component(
+            HtmxHeadersBuilder::new().build(),
+            SimulationProgressView::render_result(
+                request.simulation_id,
+                simulation_runner.result(&user.id, &request.simulation_id)?,
+            ),
+        );
+    }
+
+    Component::component(
+        HtmxHeadersBuilder::new().build(),
+        SimulationProgressView::render(request.simulation_id, job_status),
+    )
+}
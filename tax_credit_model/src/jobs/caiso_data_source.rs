@@ -0,0 +1,149 @@
+use crate::schema::{errors::Result, grid::GridRegion, simulation::EnergySource, time::TimeRange};
+
+use super::grid_data_source::{
+    form_months, read_fuel_columns, read_node_prices, FuelMixLayout, GridDataMonth, GridDataSource,
+    NodePriceLayout, RegionFiles,
+};
+
+// CAISO reports its fuel mix and real-time LMPs every five minutes
+const INTERVAL_SECONDS: i64 = 300;
+const FUEL_MIX_TIME_HEADERS: [&str; 2] = ["Interval Start GMT", "INTERVALSTARTTIME_GMT"];
+const FUELS: [(&str, EnergySource); 13] = [
+    ("Solar", EnergySource::Solar),
+    ("Wind", EnergySource::Wind),
+    ("Geothermal", EnergySource::Geothermal),
+    ("Biomass", EnergySource::Biomass),
+    ("Biogas", EnergySource::Biomass),
+    ("Small hydro", EnergySource::Hydropower),
+    ("Large hydro", EnergySource::Hydropower),
+    ("Coal", EnergySource::Coal),
+    ("Nuclear", EnergySource::Nuclear),
+    ("Natural gas", EnergySource::NaturalGas),
+    ("Batteries", EnergySource::WholesaleStorageLoad),
+    ("Imports", EnergySource::Unknown),
+    ("Other", EnergySource::Unknown),
+];
+const LMP_TIME_HEADERS: [&str; 1] = ["INTERVALSTARTTIME_GMT"];
+const NODE_HEADERS: [&str; 2] = ["NODE", "NODE_ID"];
+const PRICE_HEADERS: [&str; 2] = ["MW", "VALUE"];
+// OASIS files list each LMP component, only the total is read
+const LMP_TYPE_FILTER: [(&str, &str); 1] = [("LMP_TYPE", "LMP")];
+const REFERENCE_NODE: &str = "TH_NP15_GEN-APND";
+const FUEL_MIX_LAYOUT: FuelMixLayout = FuelMixLayout {
+    time_headers: &FUEL_MIX_TIME_HEADERS,
+    fuel_headers: &[],
+    generation_headers: &[],
+    fuels: &FUELS,
+    filters: &[],
+    start_offset_seconds: 0,
+};
+const LMP_LAYOUT: NodePriceLayout = NodePriceLayout {
+    time_headers: &LMP_TIME_HEADERS,
+    node_headers: &NODE_HEADERS,
+    price_headers: &PRICE_HEADERS,
+    filters: &LMP_TYPE_FILTER,
+    reference_node: REFERENCE_NODE,
+    start_offset_seconds: 0,
+};
+
+pub struct CaisoDataSource {
+    data_directory: String,
+}
+
+impl CaisoDataSource {
+    pub fn new(data_directory: &str) -> Self {
+        Self {
+            data_directory: String::from(data_directory),
+        }
+    }
+
+    pub fn transform(files: &RegionFiles, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        let mut issues = vec![];
+        let fuel_mixes = read_fuel_columns(&files.fuel_mix, &FUEL_MIX_LAYOUT, &mut issues)?;
+        let prices = read_node_prices(&files.prices, &LMP_LAYOUT, &mut issues)?;
+
+        form_months(
+            GridRegion::Caiso,
+            INTERVAL_SECONDS,
+            fuel_mixes,
+            prices,
+            issues,
+            time_range,
+        )
+    }
+}
+
+impl GridDataSource for CaisoDataSource {
+    fn region(&self) -> GridRegion {
+        GridRegion::Caiso
+    }
+
+    fn months(&self, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        CaisoDataSource::transform(
+            &RegionFiles::open(&self.data_directory, &self.region())?,
+            time_range,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        jobs::grid_data_source::{create_sheet, RegionFiles},
+        schema::{data_quality::DataQualityIssueKind, grid::GridRegion, time::TimeRange},
+    };
+
+    use super::CaisoDataSource;
+
+    #[test]
+    fn should_read_fuel_mix_and_hub_prices() {
+        let files = RegionFiles {
+            fuel_mix: create_sheet(
+                "Interval Start GMT,Solar,Wind,Large hydro,Natural gas,Imports\n\
+                 2023-06-01T00:00:00Z,1200,600,300,600,120\n\
+                 2023-06-01T00:05:00Z,1200,600,300,600,120\n\
+                 2023-06-01T00:10:00Z,1200,600,300,600,120\n\
+                 2023-06-01T00:15:00Z,1200,600,300,,120\n\
+                 2023-06-01T00:20:00Z,1200,600,300,600,120\n\
+                 not a time,1,1,1,1,1\n",
+            ),
+            prices: create_sheet(
+                "INTERVALSTARTTIME_GMT,NODE,LMP_TYPE,MW\n\
+                 2023-06-01T00:00:00-00:00,TH_NP15_GEN-APND,LMP,30\n\
+                 2023-06-01T00:00:00-00:00,TH_NP15_GEN-APND,MCC,5\n\
+                 2023-06-01T00:00:00-00:00,TH_SP15_GEN-APND,LMP,90\n\
+                 2023-06-01T00:05:00-00:00,TH_NP15_GEN-APND,LMP,60\n\
+                 2023-06-01T00:10:00-00:00,TH_NP15_GEN-APND,LMP,90\n\
+                 2023-06-01T00:15:00-00:00,TH_NP15_GEN-APND,LMP,45\n\
+                 2023-06-01T00:20:00-00:00,TH_SP15_GEN-APND,LMP,90\n",
+            ),
+        };
+
+        let months = CaisoDataSource::transform(&files, &TimeRange::all()).unwrap();
+
+        assert_eq!(months.len(), 1);
+        assert_eq!(months[0].month, "Jun 2023");
+        assert_eq!(
+            months[0]
+                .unparseable_rows
+                .iter()
+                .map(|issue| issue.kind)
+                .collect::<Vec<DataQualityIssueKind>>(),
+            vec![
+                DataQualityIssueKind::BlankCell,
+                DataQualityIssueKind::UnparseableRow,
+                DataQualityIssueKind::MissingPrice,
+            ]
+        );
+        assert_eq!(months[0].generations.len(), 1);
+        let generation = &months[0].generations[0];
+        assert_eq!(generation.region, GridRegion::Caiso);
+        assert_eq!(generation.time_generated.seconds, 1685578500);
+        assert_eq!(generation.sale_price_usd_per_mwh, 60.0);
+        assert_eq!(generation.portfolio.solar_mwh, 300.0);
+        assert_eq!(generation.portfolio.natural_gas_mwh, 150.0);
+        assert_eq!(generation.portfolio.unknown_mwh, 30.0);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+
+use crate::schema::{
+    data_quality::{
+        DataQualityIssue, DataQualityIssueKind, DataQualityPolicy, MonthlyDataQualityReport,
+    },
+    simulation::GenerationMetric,
+    time::Timestamp,
+};
+
+const INTERVAL_SECONDS: i64 = 900;
+const PRICE_SPIKE_MEDIAN_MULTIPLE: f64 = 10.0;
+
+pub struct DataQualityJob {}
+
+impl DataQualityJob {
+    // Reports a month of generations' duplicates, gaps and outliers alongside
+    // the rows that could not be parsed, and applies the policy to them. Under
+    // the fail policy the generations are returned as skipped and it is up to
+    // the caller to stop when the report has issues
+    pub fn inspect(
+        month: &str,
+        generations: Vec<GenerationMetric>,
+        unparseable_rows: Vec<DataQualityIssue>,
+        policy: DataQualityPolicy,
+    ) -> (Vec<GenerationMetric>, MonthlyDataQualityReport) {
+        let mut report = MonthlyDataQualityReport {
+            month: String::from(month),
+            issues: unparseable_rows,
+            ..Default::default()
+        };
+        let mut generations = generations;
+        generations.sort_by_key(|generation| generation.time_generated);
+        let mut intervals: Vec<GenerationMetric> = vec![];
+
+        for generation in generations {
+            if intervals
+                .last()
+                .is_some_and(|last| last.time_generated == generation.time_generated)
+            {
+                report.issues.push(DataQualityIssue::new(
+                    DataQualityIssueKind::Duplicate,
+                    Some(generation.time_generated),
+                    "Interval appears more than once, keeping the first",
+                ));
+                continue;
+            }
+
+            intervals.push(generation);
+        }
+
+        for pair in intervals.windows(2) {
+            let missing_intervals = (pair[1].time_generated.seconds
+                - pair[0].time_generated.seconds)
+                / INTERVAL_SECONDS
+                - 1;
+
+            if missing_intervals > 0 {
+                report.issues.push(DataQualityIssue::new(
+                    DataQualityIssueKind::Gap,
+                    Some(Timestamp::new(
+                        pair[0].time_generated.seconds + INTERVAL_SECONDS,
+                        0,
+                    )),
+                    &format!("{missing_intervals} intervals missing"),
+                ));
+            }
+        }
+
+        let median_price = DataQualityJob::median_price(&intervals);
+        let mut outliers = HashSet::new();
+
+        for generation in &intervals {
+            let issues = DataQualityJob::find_outliers(generation, median_price);
+
+            if !issues.is_empty() {
+                outliers.insert(generation.time_generated);
+            }
+
+            report.issues.extend(issues);
+        }
+
+        report.intervals = intervals.len();
+        let first = intervals
+            .first()
+            .map(|generation| generation.time_generated);
+        let last = intervals.last().map(|generation| generation.time_generated);
+        let valid = intervals
+            .into_iter()
+            .filter(|generation| !outliers.contains(&generation.time_generated))
+            .collect::<Vec<GenerationMetric>>();
+        report.skipped_intervals = report.intervals - valid.len();
+
+        match (policy, first, last) {
+            (DataQualityPolicy::Interpolate, Some(first), Some(last)) => {
+                let filled = DataQualityJob::interpolate(valid, &first, &last);
+                report.skipped_intervals = 0;
+                report.interpolated_intervals = filled.len() - (report.intervals - outliers.len());
+
+                (filled, report)
+            }
+            _ => (valid, report),
+        }
+    }
+
+    fn median_price(generations: &[GenerationMetric]) -> Option<f64> {
+        let mut prices = generations
+            .iter()
+            .map(|generation| generation.sale_price_usd_per_mwh)
+            .collect::<Vec<f64>>();
+        prices.sort_by(|a, b| a.total_cmp(b));
+
+        prices.get(prices.len() / 2).copied()
+    }
+
+    fn find_outliers(
+        generation: &GenerationMetric,
+        median_price: Option<f64>,
+    ) -> Vec<DataQualityIssue> {
+        let mut issues = vec![];
+        let price = generation.sale_price_usd_per_mwh;
+
+        if price < 0.0 {
+            issues.push(DataQualityIssue::new(
+                DataQualityIssueKind::NegativePrice,
+                Some(generation.time_generated),
+                &format!("Price of {price:.2} USD/MWh"),
+            ));
+        }
+
+        if let Some(median_price) = median_price.filter(|median_price| *median_price > 0.0) {
+            if price > median_price * PRICE_SPIKE_MEDIAN_MULTIPLE {
+                issues.push(DataQualityIssue::new(
+                    DataQualityIssueKind::PriceSpike,
+                    Some(generation.time_generated),
+                    &format!("Price of {price:.2} USD/MWh against a median of {median_price:.2}"),
+                ));
+            }
+        }
+
+        if generation.portfolio.total_electricity_mwh <= 0.0 {
+            issues.push(DataQualityIssue::new(
+                DataQualityIssueKind::ZeroPortfolio,
+                Some(generation.time_generated),
+                "No electricity generated",
+            ));
+        }
+
+        issues
+    }
+
+    // Fills every interval from the first to the last between the valid
+    // generations either side, or copies the nearest one at the edges
+    fn interpolate(
+        valid: Vec<GenerationMetric>,
+        first: &Timestamp,
+        last: &Timestamp,
+    ) -> Vec<GenerationMetric> {
+        if valid.is_empty() {
+            return valid;
+        }
+
+        let mut filled = vec![];
+        let mut next = 0;
+        let mut seconds = first.seconds;
+
+        while seconds <= last.seconds {
+            let timestamp = Timestamp::new(seconds, 0);
+
+            while next < valid.len() && valid[next].time_generated.seconds < seconds {
+                next += 1;
+            }
+
+            let generation = match (
+                next.checked_sub(1).map(|index| &valid[index]),
+                valid.get(next),
+            ) {
+                (_, Some(after)) if after.time_generated.seconds == seconds => after.clone(),
+                (Some(before), Some(after)) => {
+                    GenerationMetric::interpolate(before, after, &timestamp)
+                }
+                (Some(nearest), None) | (None, Some(nearest)) => GenerationMetric {
+                    time_generated: timestamp,
+                    filled: true,
+                    ..nearest.clone()
+                },
+                (None, None) => break,
+            };
+
+            filled.push(generation);
+            seconds += INTERVAL_SECONDS;
+        }
+
+        filled
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        data_quality::{DataQualityIssue, DataQualityIssueKind, DataQualityPolicy},
+        simulation::{EnergySourcePortfolio, GenerationMetric},
+        time::Timestamp,
+    };
+
+    use super::DataQualityJob;
+
+    fn create_generation(seconds: i64, price: f64, wind_mwh: f64) -> GenerationMetric {
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.wind_mwh = wind_mwh;
+        portfolio.total_electricity_mwh = wind_mwh;
+
+        GenerationMetric::new(0, &Timestamp::new(seconds, 0), price, portfolio)
+    }
+
+    fn create_month() -> Vec<GenerationMetric> {
+        vec![
+            create_generation(0, 20.0, 10.0),
+            create_generation(900, 22.0, 10.0),
+            create_generation(900, 25.0, 10.0),
+            create_generation(1800, -5.0, 10.0),
+            create_generation(2700, 500.0, 10.0),
+            create_generation(5400, 24.0, 0.0),
+            create_generation(6300, 30.0, 20.0),
+        ]
+    }
+
+    #[test]
+    fn should_report_issues_and_skip_outliers() {
+        let (generations, report) = DataQualityJob::inspect(
+            "Jan 2023",
+            create_month(),
+            vec![DataQualityIssue::new(
+                DataQualityIssueKind::UnparseableRow,
+                None,
+                "Fuel mix row 3: Storage",
+            )],
+            DataQualityPolicy::Skip,
+        );
+
+        assert_eq!(
+            generations
+                .iter()
+                .map(|generation| generation.time_generated.seconds)
+                .collect::<Vec<i64>>(),
+            vec![0, 900, 6300]
+        );
+        assert_eq!(report.intervals, 6);
+        assert_eq!(report.skipped_intervals, 3);
+        assert_eq!(
+            DataQualityIssueKind::all()
+                .into_iter()
+                .map(|kind| report.count(kind))
+                .collect::<Vec<usize>>(),
+            vec![1, 1, 1, 1, 1, 1, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn should_interpolate_gaps_and_outliers() {
+        let (generations, report) = DataQualityJob::inspect(
+            "Jan 2023",
+            create_month(),
+            vec![],
+            DataQualityPolicy::Interpolate,
+        );
+
+        assert_eq!(generations.len(), 8);
+        assert_eq!(report.interpolated_intervals, 5);
+        assert_eq!(generations[4].time_generated.seconds, 3600);
+        assert_eq!(generations[4].sale_price_usd_per_mwh, 26.0);
+        assert_eq!(generations[4].portfolio.total_electricity_mwh, 15.0);
+    }
+}
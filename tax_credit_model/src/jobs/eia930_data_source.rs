@@ -0,0 +1,251 @@
+use crate::schema::{errors::Result, grid::GridRegion, simulation::EnergySource, time::TimeRange};
+
+use super::grid_data_source::{
+    form_months, read_fuel_columns, read_fuel_rows, read_node_prices, FuelMixLayout, GridDataMonth,
+    GridDataSource, NodePriceLayout, RegionFiles,
+};
+
+// EIA-930 reports hourly net generation stamped at the end of the hour, either
+// with a row per fuel code as the API exports it or with a column per fuel as
+// the six month balance files publish it
+const INTERVAL_SECONDS: i64 = 3600;
+const TIME_HEADERS: [&str; 2] = ["period", "UTC Time at End of Hour"];
+const BALANCING_AUTHORITY_HEADER: &str = "respondent";
+const FUEL_HEADERS: [&str; 1] = ["fueltype"];
+const GENERATION_HEADERS: [&str; 1] = ["value"];
+const FUEL_CODES: [(&str, EnergySource); 14] = [
+    ("COL", EnergySource::Coal),
+    ("NG", EnergySource::NaturalGas),
+    ("NUC", EnergySource::Nuclear),
+    ("OIL", EnergySource::Petroleum),
+    ("WAT", EnergySource::Hydropower),
+    ("PS", EnergySource::WholesaleStorageLoad),
+    ("BAT", EnergySource::WholesaleStorageLoad),
+    ("OES", EnergySource::WholesaleStorageLoad),
+    ("SUN", EnergySource::Solar),
+    ("SNB", EnergySource::Solar),
+    ("WND", EnergySource::Wind),
+    ("GEO", EnergySource::Geothermal),
+    ("OTH", EnergySource::Unknown),
+    ("UNK", EnergySource::Unknown),
+];
+const BALANCE_BALANCING_AUTHORITY_HEADER: &str = "Balancing Authority";
+const BALANCE_FUELS: [(&str, EnergySource); 9] = [
+    ("Net Generation (MW) from Coal", EnergySource::Coal),
+    (
+        "Net Generation (MW) from Natural Gas",
+        EnergySource::NaturalGas,
+    ),
+    ("Net Generation (MW) from Nuclear", EnergySource::Nuclear),
+    (
+        "Net Generation (MW) from All Petroleum Products",
+        EnergySource::Petroleum,
+    ),
+    (
+        "Net Generation (MW) from Hydropower and Pumped Storage",
+        EnergySource::Hydropower,
+    ),
+    ("Net Generation (MW) from Solar", EnergySource::Solar),
+    ("Net Generation (MW) from Wind", EnergySource::Wind),
+    (
+        "Net Generation (MW) from Other Fuel Sources",
+        EnergySource::Unknown,
+    ),
+    (
+        "Net Generation (MW) from Unknown Fuel Sources",
+        EnergySource::Unknown,
+    ),
+];
+// EIA does not publish prices, so they come from a separate file with an
+// hourly price stamped at the end of the hour like the generation
+const PRICE_HEADERS: [&str; 4] = ["price", "Price ($/MWh)", "price_usd_per_mwh", "lmp"];
+// Every balancing authority shares a file, so the layouts are filtered to one
+// when it is read
+const FUEL_ROWS_LAYOUT: FuelMixLayout = FuelMixLayout {
+    time_headers: &TIME_HEADERS,
+    fuel_headers: &FUEL_HEADERS,
+    generation_headers: &GENERATION_HEADERS,
+    fuels: &FUEL_CODES,
+    filters: &[],
+    start_offset_seconds: -INTERVAL_SECONDS,
+};
+const BALANCE_LAYOUT: FuelMixLayout = FuelMixLayout {
+    time_headers: &TIME_HEADERS,
+    fuel_headers: &[],
+    generation_headers: &[],
+    fuels: &BALANCE_FUELS,
+    filters: &[],
+    start_offset_seconds: -INTERVAL_SECONDS,
+};
+const PRICE_LAYOUT: NodePriceLayout = NodePriceLayout {
+    time_headers: &TIME_HEADERS,
+    node_headers: &[],
+    price_headers: &PRICE_HEADERS,
+    filters: &[],
+    reference_node: "",
+    start_offset_seconds: -INTERVAL_SECONDS,
+};
+
+pub struct Eia930DataSource {
+    data_directory: String,
+    balancing_authority: String,
+}
+
+impl Eia930DataSource {
+    pub fn new(data_directory: &str, balancing_authority: &str) -> Self {
+        Self {
+            data_directory: String::from(data_directory),
+            balancing_authority: String::from(balancing_authority),
+        }
+    }
+
+    pub fn transform(
+        files: &RegionFiles,
+        balancing_authority: &str,
+        time_range: &TimeRange,
+    ) -> Result<Vec<GridDataMonth>> {
+        let mut issues = vec![];
+        let is_balance_file = files
+            .fuel_mix
+            .rows()
+            .next()
+            .is_some_and(|header| header.find_column(&FUEL_HEADERS).is_err());
+        let fuel_mixes = match is_balance_file {
+            true => read_fuel_columns(
+                &files.fuel_mix,
+                &FuelMixLayout {
+                    filters: &[(BALANCE_BALANCING_AUTHORITY_HEADER, balancing_authority)],
+                    ..BALANCE_LAYOUT
+                },
+                &mut issues,
+            )?,
+            false => read_fuel_rows(
+                &files.fuel_mix,
+                &FuelMixLayout {
+                    filters: &[(BALANCING_AUTHORITY_HEADER, balancing_authority)],
+                    ..FUEL_ROWS_LAYOUT
+                },
+                &mut issues,
+            )?,
+        };
+        let prices = read_node_prices(
+            &files.prices,
+            &NodePriceLayout {
+                reference_node: balancing_authority,
+                ..PRICE_LAYOUT
+            },
+            &mut issues,
+        )?;
+
+        form_months(
+            GridRegion::BalancingAuthority(String::from(balancing_authority)),
+            INTERVAL_SECONDS,
+            fuel_mixes,
+            prices,
+            issues,
+            time_range,
+        )
+    }
+}
+
+impl GridDataSource for Eia930DataSource {
+    fn region(&self) -> GridRegion {
+        GridRegion::BalancingAuthority(self.balancing_authority.clone())
+    }
+
+    fn months(&self, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        Eia930DataSource::transform(
+            &RegionFiles::open(&self.data_directory, &self.region())?,
+            &self.balancing_authority,
+            time_range,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        jobs::grid_data_source::{create_sheet, RegionFiles},
+        schema::{grid::GridRegion, time::TimeRange},
+    };
+
+    use super::Eia930DataSource;
+
+    fn summarize(files: &RegionFiles) -> Vec<(i64, f64, f64, f64, f64, usize)> {
+        Eia930DataSource::transform(files, "PACE", &TimeRange::all())
+            .unwrap()
+            .iter()
+            .flat_map(|month| {
+                month.generations.iter().map(|generation| {
+                    assert_eq!(
+                        generation.region,
+                        GridRegion::BalancingAuthority(String::from("PACE"))
+                    );
+
+                    (
+                        generation.time_generated.seconds,
+                        generation.sale_price_usd_per_mwh,
+                        generation.portfolio.coal_mwh,
+                        generation.portfolio.solar_mwh,
+                        generation.portfolio.wholesale_storage_load,
+                        month.unparseable_rows.len(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn create_prices() -> RegionFiles {
+        RegionFiles {
+            fuel_mix: create_sheet(""),
+            prices: create_sheet(
+                "period,price\n\
+                 2023-06-01T05,40\n",
+            ),
+        }
+    }
+
+    #[test]
+    fn should_map_fuel_codes_for_one_balancing_authority() {
+        let files = RegionFiles {
+            fuel_mix: create_sheet(
+                "period,respondent,respondent-name,fueltype,type-name,value,value-units\n\
+                 2023-06-01T05,PACE,PacifiCorp East,COL,Coal,4000,megawatthours\n\
+                 2023-06-01T05,PACE,PacifiCorp East,SUN,Solar,800,megawatthours\n\
+                 2023-06-01T05,PACE,PacifiCorp East,BAT,Battery storage,-40,megawatthours\n\
+                 2023-06-01T05,PACE,PacifiCorp East,XYZ,Unknown code,1,megawatthours\n\
+                 2023-06-01T05,PACW,PacifiCorp West,COL,Coal,9999,megawatthours\n",
+            ),
+            ..create_prices()
+        };
+
+        assert_eq!(
+            summarize(&files),
+            vec![
+                (1685592900, 40.0, 1000.0, 200.0, -10.0, 1),
+                (1685593800, 40.0, 1000.0, 200.0, -10.0, 1),
+                (1685594700, 40.0, 1000.0, 200.0, -10.0, 1),
+                (1685595600, 40.0, 1000.0, 200.0, -10.0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_read_balance_files() {
+        let files = RegionFiles {
+            fuel_mix: create_sheet(
+                "Balancing Authority,Data Date,Hour Number,UTC Time at End of Hour,Net Generation (MW) from Coal,Net Generation (MW) from Solar\n\
+                 PACE,06/01/2023,23,06/01/2023 5:00:00 AM,\"4,000\",800\n\
+                 PACW,06/01/2023,23,06/01/2023 5:00:00 AM,\"9,999\",1\n",
+            ),
+            ..create_prices()
+        };
+
+        assert_eq!(
+            summarize(&files)[0],
+            (1685592900, 40.0, 1000.0, 200.0, 0.0, 0)
+        );
+    }
+}
@@ -1,131 +1,771 @@
 use crate::{
-    parsers::excel::{ExcelSheet, ExcelWorkbook},
+    file_systems::directory::Directory,
+    parsers::{
+        csv::CsvFile,
+        excel::{ExcelRow, ExcelSheet, ExcelWorkbook},
+    },
     persistance::grid::GridClient,
     schema::{
-        ercot::{ErcotFuelMix, ErcotRTMPrice, SettlementPointLocation},
+        data_quality::{DataQualityIssue, DataQualityIssueKind},
+        ercot::{
+            ErcotDAMPrice, ErcotFuelMix, ErcotRTMPrice, RepeatedHourFlag, Settlement,
+            SettlementPointLocation,
+        },
         errors::{Error, Result},
+        grid::GridRegion,
+        plant::GRID_MIX_PLANT_ID,
         simulation::{EnergySourcePortfolio, GenerationMetric},
-        time::Timestamp,
+        time::{CentralPrevailingTime, TimeRange, Timestamp},
     },
 };
-use chrono::{Duration, NaiveDateTime};
-use std::collections::HashMap;
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime};
+
+use super::grid_data_source::{self, GridDataMonth, GridDataSource};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::Path,
+    sync::OnceLock,
+};
 
 const DATE_COLUNMN: usize = 0;
 const FUEL_SOURCE_COLUMN: usize = 1;
 const SETTLEMENT_COLUMN: usize = 2;
 const DAY_START_COLUNMN: usize = 4;
 const DAY_END_COLUNMN: usize = 99;
-const DELIVERY_HOUR_COLUMN: usize = 1;
-const DELIVERY_INTERVAL_COLUMN: usize = 2;
-const REPEATED_HOUR_FLAG_COLUMN: usize = 3;
-const SETTLEMENT_POINT_NAME_COLUMN: usize = 4;
-const SETTLEMENT_POINT_TYPE_COLUMN: usize = 5;
-const SETTLEMENT_POINT_PRICE_COLUMN: usize = 6;
+// The fall back day repeats 1:00 to 2:00 in columns after the rest of the day
+const REPEATED_HOUR_START_COLUMN: usize = 100;
+const REPEATED_HOUR_END_COLUMN: usize = 103;
+const REPEATED_HOUR: i64 = 1;
+// Annual workbooks and MIS report CSVs name and order their price columns
+// differently, so they are found by header
+// Report ids as they appear in ERCOT file names, either the NP report number,
+// the MIS report type id or the product name. Hand named files are matched by
+// their leading words instead
+const FUEL_MIX_REPORT_IDS: [&str; 1] = ["intgenbyfuel"];
+const DAM_PRICE_REPORT_IDS: [&str; 6] = [
+    "np4190",
+    "np4180",
+    "00012331",
+    "00013060",
+    "damspnp4190",
+    "damlzhbspp",
+];
+const RTM_PRICE_REPORT_IDS: [&str; 6] = [
+    "np6905",
+    "np6785",
+    "00012301",
+    "00013061",
+    "spphlznp6905",
+    "rtmlzhbspp",
+];
+const FUEL_MIX_FILE_PREFIX: &str = "fuel_mix";
+const DAM_PRICE_FILE_PREFIX: &str = "dam_prices";
+const RTM_PRICE_FILE_PREFIX: &str = "rtm_prices";
+const DATE_HEADERS: [&str; 2] = ["Date", "Delivery Date"];
+const DELIVERY_HOUR_HEADERS: [&str; 1] = ["Delivery Hour"];
+const DELIVERY_INTERVAL_HEADERS: [&str; 1] = ["Delivery Interval"];
+const HOUR_ENDING_HEADERS: [&str; 1] = ["Hour Ending"];
+const REPEATED_HOUR_FLAG_HEADERS: [&str; 2] = ["Repeated Hour Flag", "DST Flag"];
+const SETTLEMENT_POINT_NAME_HEADERS: [&str; 2] = ["Settlement Point Name", "Settlement Point"];
+const SETTLEMENT_POINT_TYPE_HEADERS: [&str; 1] = ["Settlement Point Type"];
+const SETTLEMENT_POINT_PRICE_HEADERS: [&str; 1] = ["Settlement Point Price"];
 const MINUTES_PER_INTERVAL: f64 = 15.0;
+const INTERVALS_PER_HOUR: i64 = 4;
+const MONTH_NAMES: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
 
 // 2023 link: https://www.ercot.com/files/docs/2023/02/07/IntGenbyFuel2023.xlsx
 pub struct ErcotDataRetrieverJob {}
 
 pub struct ErcotDataRetrieverInput {
+    year: i32,
+    month: String,
     fuel_mix_sheet: ExcelSheet,
     rtm_pricing_sheet: ExcelSheet,
+    dam_pricing_sheet: Option<ExcelSheet>,
+}
+
+impl ErcotDataRetrieverInput {
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> &str {
+        &self.month
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ErcotReport {
+    FuelMix,
+    RTMPrices,
+    DAMPrices,
+}
+
+impl std::fmt::Display for ErcotReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FuelMix => write!(f, "Fuel mix"),
+            Self::RTMPrices => write!(f, "Real-time prices"),
+            Self::DAMPrices => write!(f, "Day-ahead prices"),
+        }
+    }
+}
+
+// Each report may be a workbook or any number of CSV and ZIP files. Day-ahead
+// prices are optional, a year without them can only be simulated buying
+// real-time
+#[derive(Debug, PartialEq, Clone)]
+pub struct ErcotDataset {
+    pub year: i32,
+    pub fuel_mix_paths: Vec<String>,
+    pub rtm_price_paths: Vec<String>,
+    pub dam_price_paths: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct ErcotCatalog {
+    pub datasets: Vec<ErcotDataset>,
+    pub unmatched_files: Vec<String>,
+    pub unrecognised_files: Vec<String>,
+}
+
+// A report's months are taken from its workbooks' month sheets, or from its
+// CSV and ZIP files split by delivery date
+struct ErcotReportSources {
+    workbooks: Vec<ExcelWorkbook>,
+    tables: BTreeMap<u32, ExcelSheet>,
+}
+
+impl ErcotReportSources {
+    fn open(paths: &[String]) -> Result<ErcotReportSources> {
+        let (workbook_paths, table_paths): (Vec<String>, Vec<String>) = paths
+            .iter()
+            .cloned()
+            .partition(|path| ErcotDataRetrieverJob::is_workbook(path));
+        let workbooks = workbook_paths
+            .iter()
+            .map(|path| ExcelWorkbook::open(path))
+            .collect::<Result<Vec<ExcelWorkbook>>>()?;
+
+        if table_paths.is_empty() {
+            return Ok(ErcotReportSources {
+                workbooks,
+                tables: BTreeMap::new(),
+            });
+        }
+
+        let table = CsvFile::open_all(&table_paths)?;
+        let date_column = match table.rows().next() {
+            Some(header) => header.find_column(&DATE_HEADERS)?,
+            None => DATE_COLUNMN,
+        };
+
+        Ok(ErcotReportSources {
+            workbooks,
+            tables: table.partition(|row| Ok(row.get_date(date_column)?.month()))?,
+        })
+    }
+
+    fn months(&self) -> BTreeSet<u32> {
+        self.workbooks
+            .iter()
+            .flat_map(|workbook| ErcotDataRetrieverJob::month_sheets(workbook).into_keys())
+            .chain(self.tables.keys().copied())
+            .collect()
+    }
+
+    fn take_month(&mut self, month: u32) -> Result<Option<ExcelSheet>> {
+        for workbook in &mut self.workbooks {
+            if let Some(sheet_name) = ErcotDataRetrieverJob::month_sheets(workbook).get(&month) {
+                return workbook.get_sheet(sheet_name).map(Some);
+            }
+        }
+
+        Ok(self.tables.remove(&month))
+    }
 }
 
 impl ErcotDataRetrieverJob {
-    pub fn extract(data_directory: &str, month: &str) -> Result<ErcotDataRetrieverInput> {
-        let fuel_mix_path = format!("{}{}", data_directory, "/ercot/fuel_mix_2023.xlsx");
-        let rtm_price_path = format!("{}{}", data_directory, "/ercot/rtm_prices_2023.xlsx");
-        let mut fuel_source_workbook = ExcelWorkbook::open(&fuel_mix_path)?;
-        let mut rtm_price_workbook = ExcelWorkbook::open(&rtm_price_path)?;
-
-        let rtm_prices_sheet = rtm_price_workbook.get_sheet(&month)?;
-        let fuel_mix_sheet = fuel_source_workbook.get_sheet(&month)?;
-
-        Ok(ErcotDataRetrieverInput {
-            fuel_mix_sheet,
-            rtm_pricing_sheet: rtm_prices_sheet,
+    pub fn discover(data_directory: &str) -> Result<ErcotCatalog> {
+        let ercot_directory = Directory::new(&format!("{}{}", data_directory, "/ercot"));
+
+        Ok(ErcotDataRetrieverJob::catalog(
+            ercot_directory.list_files()?,
+        ))
+    }
+
+    // Files are grouped by the year in their name, years missing either the
+    // fuel mix or the real-time prices are reported rather than ingested
+    fn catalog(paths: Vec<String>) -> ErcotCatalog {
+        let mut catalog = ErcotCatalog::default();
+        let mut years: BTreeMap<i32, ErcotDataset> = BTreeMap::new();
+
+        for path in paths {
+            let Some((report, year)) = ErcotDataRetrieverJob::classify_file(&path) else {
+                catalog.unrecognised_files.push(path);
+                continue;
+            };
+            let dataset = years.entry(year).or_insert_with(|| ErcotDataset {
+                year,
+                fuel_mix_paths: vec![],
+                rtm_price_paths: vec![],
+                dam_price_paths: vec![],
+            });
+
+            match report {
+                ErcotReport::FuelMix => dataset.fuel_mix_paths.push(path),
+                ErcotReport::RTMPrices => dataset.rtm_price_paths.push(path),
+                ErcotReport::DAMPrices => dataset.dam_price_paths.push(path),
+            }
+        }
+
+        for dataset in years.into_values() {
+            if dataset.fuel_mix_paths.is_empty() || dataset.rtm_price_paths.is_empty() {
+                catalog.unmatched_files.extend(
+                    dataset
+                        .fuel_mix_paths
+                        .into_iter()
+                        .chain(dataset.rtm_price_paths)
+                        .chain(dataset.dam_price_paths),
+                );
+                continue;
+            }
+
+            catalog.datasets.push(dataset);
+        }
+
+        catalog
+    }
+
+    fn is_workbook(path: &str) -> bool {
+        Path::new(path)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("xlsx"))
+    }
+
+    fn classify_file(path: &str) -> Option<(ErcotReport, i32)> {
+        let path = Path::new(path);
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+
+        if !["xlsx", "csv", "zip"].contains(&extension.as_str()) {
+            return None;
+        }
+
+        let file_name = path.file_stem()?.to_string_lossy().to_lowercase();
+        let reports: Vec<ErcotReport> = [
+            (
+                ErcotReport::FuelMix,
+                FUEL_MIX_FILE_PREFIX,
+                FUEL_MIX_REPORT_IDS.as_slice(),
+            ),
+            (
+                ErcotReport::DAMPrices,
+                DAM_PRICE_FILE_PREFIX,
+                DAM_PRICE_REPORT_IDS.as_slice(),
+            ),
+            (
+                ErcotReport::RTMPrices,
+                RTM_PRICE_FILE_PREFIX,
+                RTM_PRICE_REPORT_IDS.as_slice(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, prefix, report_ids)| {
+            file_name.starts_with(prefix)
+                || ErcotDataRetrieverJob::has_report_id(&file_name, report_ids)
         })
+        .map(|(report, _, _)| report)
+        .collect();
+
+        // A name matching more than one report can't be trusted either way
+        let [report] = reports.as_slice() else {
+            return None;
+        };
+
+        Some((*report, ErcotDataRetrieverJob::file_year(&file_name)?))
     }
 
-    pub fn transform(input: ErcotDataRetrieverInput) -> Result<Vec<GenerationMetric>> {
-        let fuel_mixes = ErcotDataRetrieverJob::transform_fuel_mixes(input.fuel_mix_sheet)?;
-        let rtm_prices = ErcotDataRetrieverJob::transform_rtm_prices(input.rtm_pricing_sheet)?;
+    // Names are split into words on dots, underscores and spaces, a report id
+    // matches a whole word or leads one like IntGenbyFuel2019
+    fn has_report_id(file_name: &str, report_ids: &[&str]) -> bool {
+        file_name
+            .replace('-', "")
+            .split(['.', '_', ' '])
+            .any(|word| {
+                report_ids
+                    .iter()
+                    .any(|report_id| word.starts_with(report_id))
+            })
+    }
+
+    // Takes the last year in the file name, either on its own or leading a
+    // date like 20230101, so report ids such as rpt.00013061 don't shadow it
+    fn file_year(file_name: &str) -> Option<i32> {
+        file_name
+            .split(|character: char| !character.is_ascii_digit())
+            .filter(|digits| digits.len() == 4 || digits.len() == 8)
+            .filter_map(|digits| digits[..4].parse().ok())
+            .rfind(|year| (1990..=2100).contains(year))
+    }
+
+    // Month sheets are named like Jan, January or Jan19 depending on the year,
+    // every other sheet such as the disclaimer or summaries is ignored
+    fn sheet_month(sheet_name: &str) -> Option<u32> {
+        let prefix = sheet_name
+            .trim()
+            .chars()
+            .take_while(|character| character.is_ascii_alphabetic())
+            .collect::<String>()
+            .to_lowercase();
+
+        if prefix.len() < 3 {
+            return None;
+        }
+
+        MONTH_NAMES
+            .iter()
+            .position(|month| month.starts_with(&prefix))
+            .map(|month| month as u32 + 1)
+    }
+
+    fn month_sheets(workbook: &ExcelWorkbook) -> BTreeMap<u32, String> {
+        workbook
+            .sheet_names()
+            .into_iter()
+            .filter_map(|sheet_name| {
+                ErcotDataRetrieverJob::sheet_month(&sheet_name).map(|month| (month, sheet_name))
+            })
+            .collect()
+    }
+
+    fn month_name(month: u32) -> String {
+        let month_name = MONTH_NAMES[month as usize - 1];
+
+        format!("{}{}", month_name[..1].to_uppercase(), &month_name[1..3])
+    }
+
+    // Local dates from start up to end are stored as intervals ending after the
+    // first and through the last, so they overlap the time range when either
+    // end falls inside it
+    fn overlaps(time_range: &TimeRange, start: &NaiveDate, end: &NaiveDate) -> Result<bool> {
+        let first_interval_end =
+            ErcotDataRetrieverJob::interval_end(&start.and_time(NaiveTime::MIN), false)?;
+        let last_interval_end = Timestamp::from(CentralPrevailingTime::to_utc(
+            &end.and_time(NaiveTime::MIN),
+            false,
+        )?);
+
+        Ok(first_interval_end < time_range.end && time_range.start <= last_interval_end)
+    }
+
+    fn month_overlaps(time_range: &TimeRange, year: i32, month: u32) -> Result<bool> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| Error::invalid_argument("Invalid month"))?;
+        let end = start
+            .checked_add_months(Months::new(1))
+            .ok_or_else(|| Error::invalid_argument("Invalid month"))?;
+
+        ErcotDataRetrieverJob::overlaps(time_range, &start, &end)
+    }
+
+    // Only the months within the time range are read from the dataset's files
+    pub fn extract(
+        dataset: &ErcotDataset,
+        time_range: &TimeRange,
+    ) -> Result<Vec<ErcotDataRetrieverInput>> {
+        let mut fuel_mixes = ErcotReportSources::open(&dataset.fuel_mix_paths)?;
+        let mut rtm_prices = ErcotReportSources::open(&dataset.rtm_price_paths)?;
+        let mut dam_prices = ErcotReportSources::open(&dataset.dam_price_paths)?;
+        let mut inputs = vec![];
+
+        for month in fuel_mixes.months() {
+            if !ErcotDataRetrieverJob::month_overlaps(time_range, dataset.year, month)? {
+                continue;
+            }
+
+            let Some(rtm_pricing_sheet) = rtm_prices.take_month(month)? else {
+                continue;
+            };
+            let Some(fuel_mix_sheet) = fuel_mixes.take_month(month)? else {
+                continue;
+            };
+
+            inputs.push(ErcotDataRetrieverInput {
+                year: dataset.year,
+                month: ErcotDataRetrieverJob::month_name(month),
+                fuel_mix_sheet,
+                rtm_pricing_sheet,
+                dam_pricing_sheet: dam_prices.take_month(month)?,
+            });
+        }
+
+        Ok(inputs)
+    }
+
+    // Rows that cannot be parsed are left out and returned as issues for the
+    // data quality report rather than failing the whole month
+    pub fn transform(
+        input: ErcotDataRetrieverInput,
+    ) -> Result<(Vec<GenerationMetric>, Vec<DataQualityIssue>)> {
+        let mut issues = vec![];
+        let fuel_mixes =
+            ErcotDataRetrieverJob::transform_fuel_mixes(input.fuel_mix_sheet, &mut issues)?;
+        let rtm_prices =
+            ErcotDataRetrieverJob::transform_rtm_prices(input.rtm_pricing_sheet, &mut issues)?;
+        let dam_prices = match input.dam_pricing_sheet {
+            Some(dam_pricing_sheet) => {
+                ErcotDataRetrieverJob::transform_dam_prices(dam_pricing_sheet, &mut issues)?
+            }
+            None => vec![],
+        };
+
+        Ok((
+            ErcotDataRetrieverJob::form_generations(fuel_mixes, rtm_prices, dam_prices)?,
+            issues,
+        ))
+    }
 
-        ErcotDataRetrieverJob::form_generations(fuel_mixes, rtm_prices)
+    fn unparseable_row(report: ErcotReport, row_index: usize, error: &Error) -> DataQualityIssue {
+        grid_data_source::unparseable_row(&report.to_string(), row_index, error)
     }
 
     fn transform_fuel_mixes(
         fuel_mix_sheet: ExcelSheet,
+        issues: &mut Vec<DataQualityIssue>,
     ) -> Result<HashMap<Timestamp, Vec<ErcotFuelMix>>> {
         let mut fuel_mixes: HashMap<Timestamp, Vec<ErcotFuelMix>> = HashMap::new();
-        let mut fuel_mix_rows = fuel_mix_sheet.rows();
-        fuel_mix_rows.next();
-
-        for row in fuel_mix_rows {
-            let mut date = row.get_date(DATE_COLUNMN)?;
-            let fuel_source = row.get_string(FUEL_SOURCE_COLUMN)?;
-            let settlement = row.get_string(SETTLEMENT_COLUMN)?;
 
-            for interval_column in DAY_START_COLUNMN..=DAY_END_COLUNMN {
-                if row.is_empty_cell(interval_column)? {
-                    continue;
+        for (row_index, row) in fuel_mix_sheet.rows().enumerate().skip(1) {
+            match ErcotDataRetrieverJob::transform_fuel_mix_row(&row, row_index, issues) {
+                Ok(row_fuel_mixes) => {
+                    for fuel_mix in row_fuel_mixes {
+                        ErcotDataRetrieverJob::add_fuel_mix(&mut fuel_mixes, fuel_mix);
+                    }
                 }
-                date += Duration::minutes(15);
-                let fuel_mix = ErcotFuelMix {
+                Err(error) => issues.push(ErcotDataRetrieverJob::unparseable_row(
+                    ErcotReport::FuelMix,
+                    row_index,
+                    &error,
+                )),
+            }
+        }
+
+        Ok(fuel_mixes)
+    }
+
+    // The spring forward day leaves the skipped hour's columns empty. An
+    // interval that cannot be read, like a skipped hour that has data anyway,
+    // is reported on its own and the rest of the row is kept
+    fn transform_fuel_mix_row(
+        row: &ExcelRow,
+        row_index: usize,
+        issues: &mut Vec<DataQualityIssue>,
+    ) -> Result<Vec<ErcotFuelMix>> {
+        let mut fuel_mixes = vec![];
+        let date = row.get_date(DATE_COLUNMN)?;
+        let fuel_source = row.get_string(FUEL_SOURCE_COLUMN)?;
+        let settlement: Settlement = row.get_string(SETTLEMENT_COLUMN)?.parse()?;
+
+        for interval_column in (DAY_START_COLUNMN..=DAY_END_COLUNMN)
+            .chain(REPEATED_HOUR_START_COLUMN..=REPEATED_HOUR_END_COLUMN)
+        {
+            if !row.has_cell(interval_column) || row.is_empty_cell(interval_column)? {
+                continue;
+            }
+
+            let (interval_start, repeated_hour) =
+                ErcotDataRetrieverJob::fuel_mix_interval_start(&date, interval_column);
+            let fuel_mix = (|| -> Result<ErcotFuelMix> {
+                Ok(ErcotFuelMix {
                     electricity_produced: row.get_float(interval_column)?,
-                    settlement: settlement.parse()?,
+                    settlement,
+                    fuel_type: String::from(fuel_source),
                     fuel_source: fuel_source.parse()?,
-                    date: Timestamp::from(date),
-                };
+                    date: ErcotDataRetrieverJob::interval_end(&interval_start, repeated_hour)?,
+                })
+            })();
 
-                if let Some(fuel_mix_list) = fuel_mixes.get_mut(&fuel_mix.date) {
-                    fuel_mix_list.push(fuel_mix);
-                } else {
-                    fuel_mixes.insert(fuel_mix.date, vec![fuel_mix]);
-                }
+            match fuel_mix {
+                Ok(fuel_mix) => fuel_mixes.push(fuel_mix),
+                Err(error) => issues.push(DataQualityIssue::new(
+                    DataQualityIssueKind::UnparseableRow,
+                    None,
+                    &format!(
+                        "{} row {} interval starting {interval_start}: {error}",
+                        ErcotReport::FuelMix,
+                        row_index + 1
+                    ),
+                )),
             }
         }
 
         Ok(fuel_mixes)
     }
 
-    fn transform_rtm_prices(rtm_prices_sheet: ExcelSheet) -> Result<Vec<ErcotRTMPrice>> {
+    // A fuel is counted once per interval, taking its final settlement over
+    // its initial one. Fuels are told apart by ERCOT's fuel type since several
+    // types map to the same energy source
+    fn add_fuel_mix(
+        fuel_mixes: &mut HashMap<Timestamp, Vec<ErcotFuelMix>>,
+        fuel_mix: ErcotFuelMix,
+    ) {
+        let fuel_mix_list = fuel_mixes.entry(fuel_mix.date).or_default();
+        let existing_fuel_mix = fuel_mix_list
+            .iter_mut()
+            .find(|existing| existing.fuel_type == fuel_mix.fuel_type);
+
+        match existing_fuel_mix {
+            Some(existing) => {
+                if existing.settlement == Settlement::Initial
+                    && fuel_mix.settlement == Settlement::Final
+                {
+                    *existing = fuel_mix;
+                }
+            }
+            None => fuel_mix_list.push(fuel_mix),
+        }
+    }
+
+    pub fn initial_settlement_issue(generations: &[GenerationMetric]) -> Option<DataQualityIssue> {
+        let initial_intervals: Vec<Timestamp> = generations
+            .iter()
+            .filter(|generation| generation.settlement == Settlement::Initial)
+            .map(|generation| generation.time_generated)
+            .collect();
+        let first = initial_intervals.first()?;
+        let last = initial_intervals.last()?;
+
+        Some(DataQualityIssue::new(
+            DataQualityIssueKind::InitialSettlement,
+            Some(*first),
+            &format!(
+                "{} intervals only have INITIAL settlement data through {}",
+                initial_intervals.len(),
+                last.to_utc_date_time()
+                    .map_or(String::new(), |date| date.to_rfc3339()),
+            ),
+        ))
+    }
+
+    // MIS reports list prices at every resource node too, only hubs and load
+    // zones are kept
+    fn transform_rtm_prices(
+        rtm_prices_sheet: ExcelSheet,
+        issues: &mut Vec<DataQualityIssue>,
+    ) -> Result<Vec<ErcotRTMPrice>> {
         let mut rtm_prices: Vec<ErcotRTMPrice> = vec![];
-        let mut rtm_prices_rows = rtm_prices_sheet.rows();
-        rtm_prices_rows.next();
-
-        for row in rtm_prices_rows {
-            let mut date = NaiveDateTime::parse_from_str(
-                &format!(
-                    "{} {}:{}",
-                    row.get_string(DATE_COLUNMN)?,
-                    format!("{:02}", row.get_float(DELIVERY_HOUR_COLUMN)? - 1.0),
-                    format!(
-                        "{:02}",
-                        (row.get_float(DELIVERY_INTERVAL_COLUMN)? - 1.0) * MINUTES_PER_INTERVAL
-                    ),
-                ),
-                "%m/%d/%Y %0H:%0M",
-            )
-            .map_err(|err| Error::invalid_argument(&err.to_string()))?;
-            date += Duration::minutes(15);
-            rtm_prices.push(ErcotRTMPrice {
-                delivery_timestamp: Timestamp::from(date),
-                repeated_hour_flag: row.get_string(REPEATED_HOUR_FLAG_COLUMN)?.parse()?,
-                settlement_point_type: row.get_string(SETTLEMENT_POINT_TYPE_COLUMN)?.parse()?,
-                settlement_point_location: row.get_string(SETTLEMENT_POINT_NAME_COLUMN)?.parse()?,
-                settlement_point_price: row.get_float(SETTLEMENT_POINT_PRICE_COLUMN)?,
-            });
+        let mut rtm_prices_rows = rtm_prices_sheet.rows().enumerate();
+        let Some((_, header)) = rtm_prices_rows.next() else {
+            return Ok(rtm_prices);
+        };
+        let date_column = header.find_column(&DATE_HEADERS)?;
+        let delivery_hour_column = header.find_column(&DELIVERY_HOUR_HEADERS)?;
+        let delivery_interval_column = header.find_column(&DELIVERY_INTERVAL_HEADERS)?;
+        let repeated_hour_flag_column = header.find_column(&REPEATED_HOUR_FLAG_HEADERS)?;
+        let settlement_point_name_column = header.find_column(&SETTLEMENT_POINT_NAME_HEADERS)?;
+        let settlement_point_type_column = header.find_column(&SETTLEMENT_POINT_TYPE_HEADERS)?;
+        let settlement_point_price_column = header.find_column(&SETTLEMENT_POINT_PRICE_HEADERS)?;
+
+        for (row_index, row) in rtm_prices_rows {
+            let rtm_price = (|| -> Result<Option<ErcotRTMPrice>> {
+                let Ok(settlement_point_location) = row
+                    .get_string(settlement_point_name_column)?
+                    .parse::<SettlementPointLocation>()
+                else {
+                    return Ok(None);
+                };
+                let interval_start = row.get_date(date_column)?
+                    + Duration::hours(row.get_float(delivery_hour_column)? as i64 - 1)
+                    + Duration::minutes(
+                        ((row.get_float(delivery_interval_column)? - 1.0) * MINUTES_PER_INTERVAL)
+                            as i64,
+                    );
+                let repeated_hour_flag: RepeatedHourFlag =
+                    row.get_string(repeated_hour_flag_column)?.parse()?;
+
+                Ok(Some(ErcotRTMPrice {
+                    delivery_timestamp: ErcotDataRetrieverJob::interval_end(
+                        &interval_start,
+                        repeated_hour_flag == RepeatedHourFlag::Repeated,
+                    )?,
+                    repeated_hour_flag,
+                    settlement_point_type: row.get_string(settlement_point_type_column)?.parse()?,
+                    settlement_point_location,
+                    settlement_point_price: row.get_float(settlement_point_price_column)?,
+                }))
+            })();
+
+            match rtm_price {
+                Ok(rtm_price) => rtm_prices.extend(rtm_price),
+                Err(error) => issues.push(ErcotDataRetrieverJob::unparseable_row(
+                    ErcotReport::RTMPrices,
+                    row_index,
+                    &error,
+                )),
+            }
         }
 
         Ok(rtm_prices)
     }
 
+    fn transform_dam_prices(
+        dam_prices_sheet: ExcelSheet,
+        issues: &mut Vec<DataQualityIssue>,
+    ) -> Result<Vec<ErcotDAMPrice>> {
+        let mut dam_prices: Vec<ErcotDAMPrice> = vec![];
+        let mut dam_prices_rows = dam_prices_sheet.rows().enumerate();
+        let Some((_, header)) = dam_prices_rows.next() else {
+            return Ok(dam_prices);
+        };
+        let date_column = header.find_column(&DATE_HEADERS)?;
+        let hour_ending_column = header.find_column(&HOUR_ENDING_HEADERS)?;
+        let repeated_hour_flag_column = header.find_column(&REPEATED_HOUR_FLAG_HEADERS)?;
+        let settlement_point_name_column = header.find_column(&SETTLEMENT_POINT_NAME_HEADERS)?;
+        let settlement_point_price_column = header.find_column(&SETTLEMENT_POINT_PRICE_HEADERS)?;
+
+        for (row_index, row) in dam_prices_rows {
+            let row_dam_prices = (|| -> Result<Vec<ErcotDAMPrice>> {
+                let Ok(settlement_point_location) = row
+                    .get_string(settlement_point_name_column)?
+                    .parse::<SettlementPointLocation>()
+                else {
+                    return Ok(vec![]);
+                };
+                let repeated_hour_flag: RepeatedHourFlag =
+                    row.get_string(repeated_hour_flag_column)?.parse()?;
+                let settlement_point_price = row.get_float(settlement_point_price_column)?;
+
+                Ok(ErcotDataRetrieverJob::dam_intervals(
+                    &row.get_date(date_column)?.date(),
+                    row.get_string(hour_ending_column)?,
+                    repeated_hour_flag == RepeatedHourFlag::Repeated,
+                )?
+                .into_iter()
+                .map(|delivery_timestamp| ErcotDAMPrice {
+                    delivery_timestamp,
+                    repeated_hour_flag,
+                    settlement_point_location,
+                    settlement_point_price,
+                })
+                .collect())
+            })();
+
+            match row_dam_prices {
+                Ok(row_dam_prices) => dam_prices.extend(row_dam_prices),
+                Err(error) => issues.push(ErcotDataRetrieverJob::unparseable_row(
+                    ErcotReport::DAMPrices,
+                    row_index,
+                    &error,
+                )),
+            }
+        }
+
+        Ok(dam_prices)
+    }
+
+    // The hour ending is written like 01:00 through 24:00
+    fn dam_intervals(
+        delivery_date: &NaiveDate,
+        hour_ending: &str,
+        repeated_hour: bool,
+    ) -> Result<Vec<Timestamp>> {
+        let hour_ending: i64 = hour_ending
+            .split(':')
+            .next()
+            .and_then(|hour| hour.trim().parse().ok())
+            .ok_or_else(|| Error::invalid_argument(hour_ending))?;
+        let hour_start = delivery_date
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| Error::invalid_argument("Invalid delivery date"))?
+            + Duration::hours(hour_ending - 1);
+
+        (0..INTERVALS_PER_HOUR)
+            .map(|interval| {
+                ErcotDataRetrieverJob::interval_end(
+                    &(hour_start + Duration::minutes(interval * MINUTES_PER_INTERVAL as i64)),
+                    repeated_hour,
+                )
+            })
+            .collect()
+    }
+
+    fn fuel_mix_interval_start(date: &NaiveDateTime, column: usize) -> (NaiveDateTime, bool) {
+        if column >= REPEATED_HOUR_START_COLUMN {
+            let interval = (column - REPEATED_HOUR_START_COLUMN) as i64;
+
+            return (
+                *date
+                    + Duration::hours(REPEATED_HOUR)
+                    + Duration::minutes(interval * MINUTES_PER_INTERVAL as i64),
+                true,
+            );
+        }
+
+        let interval = (column - DAY_START_COLUNMN) as i64;
+
+        (
+            *date + Duration::minutes(interval * MINUTES_PER_INTERVAL as i64),
+            false,
+        )
+    }
+
+    // ERCOT labels intervals by the Central Prevailing Time they end at, so the
+    // local start is converted to UTC and the interval is stored at its end
+    fn interval_end(interval_start: &NaiveDateTime, repeated_hour: bool) -> Result<Timestamp> {
+        Ok(Timestamp::from(
+            CentralPrevailingTime::to_utc(interval_start, repeated_hour)?
+                + Duration::minutes(MINUTES_PER_INTERVAL as i64),
+        ))
+    }
+
+    // Generations are priced at the hub average by default and keep every other
+    // settlement point's real-time and day-ahead price so a simulation can buy
+    // at its own location in either market
     fn form_generations(
         fuel_mixes: HashMap<Timestamp, Vec<ErcotFuelMix>>,
         rtm_prices: Vec<ErcotRTMPrice>,
+        dam_prices: Vec<ErcotDAMPrice>,
     ) -> Result<Vec<GenerationMetric>> {
+        let mut settlement_point_prices: HashMap<Timestamp, HashMap<SettlementPointLocation, f64>> =
+            HashMap::new();
+        let mut day_ahead_settlement_point_prices: HashMap<
+            Timestamp,
+            HashMap<SettlementPointLocation, f64>,
+        > = HashMap::new();
+
+        for price in &dam_prices {
+            day_ahead_settlement_point_prices
+                .entry(price.delivery_timestamp)
+                .or_default()
+                .insert(
+                    price.settlement_point_location,
+                    price.settlement_point_price,
+                );
+        }
+
+        for price in &rtm_prices {
+            settlement_point_prices
+                .entry(price.delivery_timestamp)
+                .or_default()
+                .insert(
+                    price.settlement_point_location,
+                    price.settlement_point_price,
+                );
+        }
+
         rtm_prices
             .iter()
             .filter(|price| {
@@ -133,25 +773,43 @@ impl ErcotDataRetrieverJob {
                     && fuel_mixes.contains_key(&price.delivery_timestamp)
             })
             .map(|price| {
-                let portfolio = fuel_mixes
+                let interval_fuel_mixes = fuel_mixes
                     .get(&price.delivery_timestamp)
-                    .ok_or_else(|| Error::not_found("No timestamp"))?
-                    .iter()
-                    .fold(
-                        EnergySourcePortfolio::default(),
-                        |mut portfolio, fuel_mix| {
-                            portfolio
-                                .add_energy(&fuel_mix.fuel_source, fuel_mix.electricity_produced);
-
-                            portfolio
-                        },
-                    );
+                    .ok_or_else(|| Error::not_found("No timestamp"))?;
+                let portfolio = interval_fuel_mixes.iter().fold(
+                    EnergySourcePortfolio::default(),
+                    |mut portfolio, fuel_mix| {
+                        portfolio.add_energy(&fuel_mix.fuel_source, fuel_mix.electricity_produced);
+
+                        portfolio
+                    },
+                );
 
                 Ok(GenerationMetric {
-                    plant_id: 0,
+                    plant_id: GRID_MIX_PLANT_ID,
                     time_generated: price.delivery_timestamp.clone(),
                     portfolio,
                     sale_price_usd_per_mwh: price.settlement_point_price,
+                    settlement_point_prices: settlement_point_prices
+                        .get(&price.delivery_timestamp)
+                        .cloned()
+                        .unwrap_or_default(),
+                    settlement: if interval_fuel_mixes
+                        .iter()
+                        .any(|fuel_mix| fuel_mix.settlement == Settlement::Initial)
+                    {
+                        Settlement::Initial
+                    } else {
+                        Settlement::Final
+                    },
+                    day_ahead_settlement_point_prices: day_ahead_settlement_point_prices
+                        .get(&price.delivery_timestamp)
+                        .cloned()
+                        .unwrap_or_default(),
+                    real_time_price_usd_per_mwh: None,
+                    filled: false,
+                    region: GridRegion::Ercot,
+                    marginal_emission_rate_kg_co2e_per_mwh: None,
                 })
             })
             .collect()
@@ -164,3 +822,433 @@ impl ErcotDataRetrieverJob {
         grid_client.add_generations(generations)
     }
 }
+
+// The data directory is listed once, the catalog serves both the months and
+// the skipped files
+pub struct ErcotDataSource {
+    data_directory: String,
+    catalog: OnceLock<ErcotCatalog>,
+}
+
+impl ErcotDataSource {
+    pub fn new(data_directory: &str) -> Self {
+        Self {
+            data_directory: String::from(data_directory),
+            catalog: OnceLock::new(),
+        }
+    }
+
+    fn catalog(&self) -> Result<&ErcotCatalog> {
+        if let Some(catalog) = self.catalog.get() {
+            return Ok(catalog);
+        }
+
+        let catalog = ErcotDataRetrieverJob::discover(&self.data_directory)?;
+
+        Ok(self.catalog.get_or_init(|| catalog))
+    }
+}
+
+impl GridDataSource for ErcotDataSource {
+    fn region(&self) -> GridRegion {
+        GridRegion::Ercot
+    }
+
+    fn months(&self, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        let mut months = vec![];
+
+        for dataset in &self.catalog()?.datasets {
+            let year_start = NaiveDate::from_ymd_opt(dataset.year, 1, 1)
+                .ok_or_else(|| Error::invalid_argument("Invalid year"))?;
+            let year_end = NaiveDate::from_ymd_opt(dataset.year + 1, 1, 1)
+                .ok_or_else(|| Error::invalid_argument("Invalid year"))?;
+
+            if !ErcotDataRetrieverJob::overlaps(time_range, &year_start, &year_end)? {
+                continue;
+            }
+
+            for input in ErcotDataRetrieverJob::extract(dataset, time_range)? {
+                let month = format!("{} {}", input.month(), input.year());
+                let (generations, unparseable_rows) = ErcotDataRetrieverJob::transform(input)?;
+
+                months.push(GridDataMonth {
+                    month,
+                    generations: generations
+                        .into_iter()
+                        .filter(|generation| time_range.contains(&generation.time_generated))
+                        .collect(),
+                    unparseable_rows,
+                });
+            }
+        }
+
+        Ok(months)
+    }
+
+    fn skipped_files(&self) -> Result<Vec<String>> {
+        let catalog = self.catalog()?;
+
+        Ok(catalog
+            .unrecognised_files
+            .iter()
+            .map(|file| format!("{file}: not a recognised ERCOT report"))
+            .chain(catalog.unmatched_files.iter().map(|file| {
+                format!("{file}: no matching ERCOT fuel mix or price file for the same year")
+            }))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use chrono::NaiveDate;
+
+    use calamine::DataType;
+
+    use crate::{
+        parsers::excel::ExcelSheet,
+        schema::{
+            data_quality::{DataQualityIssue, DataQualityIssueKind},
+            ercot::{
+                ErcotDAMPrice, ErcotFuelMix, ErcotRTMPrice, RepeatedHourFlag, Settlement,
+                SettlementPointLocation, SettlementPointType,
+            },
+            time::{TimeRange, Timestamp},
+        },
+    };
+
+    use super::{
+        ErcotCatalog, ErcotDataRetrieverJob, ErcotDataset, ErcotReport, DAY_END_COLUNMN,
+        DAY_START_COLUNMN, REPEATED_HOUR_END_COLUMN, REPEATED_HOUR_START_COLUMN,
+    };
+
+    fn create_fuel_mix(fuel_type: &str, settlement: Settlement, amount: f64) -> ErcotFuelMix {
+        ErcotFuelMix {
+            date: Timestamp::new(900, 0),
+            fuel_type: String::from(fuel_type),
+            fuel_source: fuel_type.parse().unwrap(),
+            settlement,
+            electricity_produced: amount,
+        }
+    }
+
+    fn create_price() -> ErcotRTMPrice {
+        ErcotRTMPrice {
+            delivery_timestamp: Timestamp::new(900, 0),
+            settlement_point_location: SettlementPointLocation::HubAverage,
+            settlement_point_price: 20.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_prefer_final_settlement() {
+        let mut fuel_mixes = HashMap::new();
+
+        for fuel_mix in [
+            create_fuel_mix("Wind", Settlement::Initial, 10.0),
+            create_fuel_mix("Wind", Settlement::Final, 12.0),
+            create_fuel_mix("Wind", Settlement::Initial, 8.0),
+            create_fuel_mix("Gas", Settlement::Final, 3.0),
+            create_fuel_mix("Gas-CC", Settlement::Final, 4.0),
+        ] {
+            ErcotDataRetrieverJob::add_fuel_mix(&mut fuel_mixes, fuel_mix);
+        }
+
+        let generations =
+            ErcotDataRetrieverJob::form_generations(fuel_mixes, vec![create_price()], vec![])
+                .unwrap();
+
+        assert_eq!(generations[0].portfolio.wind_mwh, 12.0);
+        assert_eq!(generations[0].portfolio.natural_gas_mwh, 7.0);
+        assert_eq!(generations[0].settlement, Settlement::Final);
+    }
+
+    #[test]
+    fn should_report_initial_only_intervals() {
+        let mut fuel_mixes = HashMap::new();
+        ErcotDataRetrieverJob::add_fuel_mix(
+            &mut fuel_mixes,
+            create_fuel_mix("Wind", Settlement::Initial, 10.0),
+        );
+
+        let generations =
+            ErcotDataRetrieverJob::form_generations(fuel_mixes, vec![create_price()], vec![])
+                .unwrap();
+
+        assert_eq!(generations[0].settlement, Settlement::Initial);
+        assert_eq!(
+            ErcotDataRetrieverJob::initial_settlement_issue(&generations),
+            Some(DataQualityIssue::new(
+                DataQualityIssueKind::InitialSettlement,
+                Some(Timestamp::new(900, 0)),
+                "1 intervals only have INITIAL settlement data through 1970-01-01T00:15:00+00:00",
+            ))
+        );
+    }
+
+    #[test]
+    fn should_pair_reports_by_year() {
+        let mis_report =
+            "cdr.00012301.0000000000000000.20230101.001500.SPPHLZNP6905_20230101_0015_csv";
+        let catalog = ErcotDataRetrieverJob::catalog(vec![
+            String::from("data/ercot/IntGenbyFuel2019.xlsx"),
+            String::from("data/ercot/IntGenbyFuel2020.xlsx"),
+            String::from("data/ercot/README.md"),
+            format!("data/ercot/{mis_report}.zip"),
+            String::from("data/ercot/dam_prices_2023.csv"),
+            String::from("data/ercot/fuel_mix_2023.xlsx"),
+            String::from("data/ercot/rpt.00013061.0000000000000000.RTMLZHBSPP_2019.xlsx"),
+            String::from("data/ercot/rtm_prices_2023.xlsx"),
+        ]);
+
+        assert_eq!(
+            catalog,
+            ErcotCatalog {
+                datasets: vec![
+                    ErcotDataset {
+                        year: 2019,
+                        fuel_mix_paths: vec![String::from("data/ercot/IntGenbyFuel2019.xlsx")],
+                        rtm_price_paths: vec![String::from(
+                            "data/ercot/rpt.00013061.0000000000000000.RTMLZHBSPP_2019.xlsx"
+                        )],
+                        dam_price_paths: vec![],
+                    },
+                    ErcotDataset {
+                        year: 2023,
+                        fuel_mix_paths: vec![String::from("data/ercot/fuel_mix_2023.xlsx")],
+                        rtm_price_paths: vec![
+                            format!("data/ercot/{mis_report}.zip"),
+                            String::from("data/ercot/rtm_prices_2023.xlsx"),
+                        ],
+                        dam_price_paths: vec![String::from("data/ercot/dam_prices_2023.csv")],
+                    },
+                ],
+                unmatched_files: vec![String::from("data/ercot/IntGenbyFuel2020.xlsx")],
+                unrecognised_files: vec![String::from("data/ercot/README.md")],
+            }
+        );
+    }
+
+    #[test]
+    fn should_classify_files_by_report_id() {
+        let paths = [
+            "data/ercot/rtm_prices_amsterdam_2023.csv",
+            "data/ercot/cdr.00012331.0000000000000000.20230101.DAMSPNP4190_csv.zip",
+            "data/ercot/NP4-190-CD_2023.csv",
+            "data/ercot/IntGenByFuel_2023.xlsx",
+        ];
+
+        assert_eq!(
+            paths
+                .iter()
+                .map(|path| ErcotDataRetrieverJob::classify_file(path))
+                .collect::<Vec<Option<(ErcotReport, i32)>>>(),
+            vec![
+                Some((ErcotReport::RTMPrices, 2023)),
+                Some((ErcotReport::DAMPrices, 2023)),
+                Some((ErcotReport::DAMPrices, 2023)),
+                Some((ErcotReport::FuelMix, 2023)),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_classify_unrelated_files() {
+        let paths = [
+            "data/ercot/adam_notes_2023.csv",
+            "data/ercot/amsterdam_2023.csv",
+            "data/ercot/rtm_backup_fuel_2023.xlsx",
+            "data/ercot/dam_rtm_2023.csv",
+        ];
+
+        assert_eq!(
+            paths
+                .iter()
+                .map(|path| ErcotDataRetrieverJob::classify_file(path))
+                .collect::<Vec<Option<(ErcotReport, i32)>>>(),
+            vec![None, None, None, None]
+        );
+    }
+
+    fn create_sheet(rows: &[&[&str]]) -> ExcelSheet {
+        ExcelSheet::from_rows(
+            rows.iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| match cell.parse::<f64>() {
+                            Ok(number) => DataType::Float(number),
+                            Err(_) => DataType::String(String::from(*cell)),
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    // One fuel's row with every quarter hour of the day filled in, and the
+    // repeated hour's columns only when asked for
+    fn create_fuel_mix_sheet(date: &str, repeated_hour: bool) -> ExcelSheet {
+        let mut row = vec![
+            DataType::String(String::from(date)),
+            DataType::String(String::from("Wind")),
+            DataType::String(String::from("FINAL")),
+            DataType::String(String::from("")),
+        ];
+        row.extend((DAY_START_COLUNMN..=DAY_END_COLUNMN).map(|_| DataType::Float(1.0)));
+        row.extend(
+            (REPEATED_HOUR_START_COLUMN..=REPEATED_HOUR_END_COLUMN).map(|_| match repeated_hour {
+                true => DataType::Float(2.0),
+                false => DataType::Empty,
+            }),
+        );
+
+        ExcelSheet::from_rows(vec![vec![DataType::String(String::from("Date"))], row])
+    }
+
+    #[test]
+    fn should_read_repeated_hour_on_fall_back_day() {
+        let mut issues = vec![];
+        let fuel_mixes = ErcotDataRetrieverJob::transform_fuel_mixes(
+            create_fuel_mix_sheet("11/05/2023", true),
+            &mut issues,
+        )
+        .unwrap();
+
+        assert!(issues.is_empty());
+        assert_eq!(fuel_mixes.len(), 100);
+        assert_eq!(
+            fuel_mixes[&Timestamp::new(1699164900, 0)][0].electricity_produced,
+            1.0
+        );
+        assert_eq!(
+            fuel_mixes[&Timestamp::new(1699168500, 0)][0].electricity_produced,
+            2.0
+        );
+    }
+
+    #[test]
+    fn should_report_skipped_hour_on_spring_forward_day() {
+        let mut issues = vec![];
+        let fuel_mixes = ErcotDataRetrieverJob::transform_fuel_mixes(
+            create_fuel_mix_sheet("03/12/2023", false),
+            &mut issues,
+        )
+        .unwrap();
+
+        assert_eq!(fuel_mixes.len(), 92);
+        assert_eq!(
+            issues[0],
+            DataQualityIssue::new(
+                DataQualityIssueKind::UnparseableRow,
+                None,
+                "Fuel mix row 2 interval starting 2023-03-12 02:00:00: Invalid Argument: 2023-03-12 02:00:00 does not exist in Central Prevailing Time",
+            )
+        );
+        assert_eq!(issues.len(), 4);
+    }
+
+    #[test]
+    fn should_find_price_columns_by_header() {
+        let sheet = create_sheet(&[
+            &[
+                "DeliveryDate",
+                "DeliveryHour",
+                "DeliveryInterval",
+                "SettlementPointName",
+                "SettlementPointType",
+                "SettlementPointPrice",
+                "DSTFlag",
+            ],
+            &["07/01/2023", "1", "1", "HB_NORTH", "HU", "25.5", "N"],
+            &["07/01/2023", "1", "1", "RN_NODE", "RN", "10", "N"],
+        ]);
+
+        let mut issues = vec![];
+        let prices = ErcotDataRetrieverJob::transform_rtm_prices(sheet, &mut issues).unwrap();
+
+        assert!(issues.is_empty());
+        assert_eq!(
+            prices,
+            vec![ErcotRTMPrice {
+                delivery_timestamp: Timestamp::new(1688188500, 0),
+                repeated_hour_flag: RepeatedHourFlag::Unrepeated,
+                settlement_point_location: SettlementPointLocation::NorthHub,
+                settlement_point_type: SettlementPointType::HU,
+                settlement_point_price: 25.5,
+            }]
+        );
+    }
+
+    #[test]
+    fn should_only_read_months_within_time_range() {
+        let time_range = TimeRange {
+            start: Timestamp::new(1685577600, 0),
+            end: Timestamp::new(1688169600, 0),
+        };
+
+        assert_eq!(
+            [4, 5, 6, 7]
+                .into_iter()
+                .map(
+                    |month| ErcotDataRetrieverJob::month_overlaps(&time_range, 2023, month)
+                        .unwrap()
+                )
+                .collect::<Vec<bool>>(),
+            vec![false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn should_detect_month_sheets() {
+        assert_eq!(ErcotDataRetrieverJob::sheet_month("Jan"), Some(1));
+        assert_eq!(ErcotDataRetrieverJob::sheet_month("September"), Some(9));
+        assert_eq!(ErcotDataRetrieverJob::sheet_month("Dec19"), Some(12));
+        assert_eq!(ErcotDataRetrieverJob::sheet_month("Disclaimer"), None);
+        assert_eq!(ErcotDataRetrieverJob::sheet_month("Summary"), None);
+        assert_eq!(ErcotDataRetrieverJob::sheet_month("data_Summary_1"), None);
+    }
+
+    #[test]
+    fn should_repeat_day_ahead_price_over_hour() {
+        let intervals = ErcotDataRetrieverJob::dam_intervals(
+            &NaiveDate::from_ymd_opt(2023, 7, 1).unwrap(),
+            "01:00",
+            false,
+        )
+        .unwrap();
+        let mut fuel_mixes = HashMap::new();
+        ErcotDataRetrieverJob::add_fuel_mix(
+            &mut fuel_mixes,
+            create_fuel_mix("Wind", Settlement::Final, 10.0),
+        );
+
+        let generations = ErcotDataRetrieverJob::form_generations(
+            fuel_mixes,
+            vec![create_price()],
+            vec![ErcotDAMPrice {
+                delivery_timestamp: Timestamp::new(900, 0),
+                settlement_point_location: SettlementPointLocation::NorthHub,
+                settlement_point_price: 35.0,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(
+            intervals
+                .iter()
+                .map(|interval| interval.seconds)
+                .collect::<Vec<i64>>(),
+            vec![1688188500, 1688189400, 1688190300, 1688191200]
+        );
+        assert_eq!(
+            generations[0].day_ahead_settlement_point_prices,
+            HashMap::from([(SettlementPointLocation::NorthHub, 35.0)])
+        );
+    }
+}
@@ -0,0 +1,554 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Utc};
+
+use crate::{
+    file_systems::directory::Directory,
+    logic::resampling::resample_series,
+    parsers::{
+        csv::CsvFile,
+        excel::{ExcelRow, ExcelSheet},
+    },
+    schema::{
+        data_quality::{DataQualityIssue, DataQualityIssueKind},
+        errors::{Error, Result},
+        grid::GridRegion,
+        plant::GRID_MIX_PLANT_ID,
+        simulation::{EnergySource, EnergySourcePortfolio, GenerationMetric},
+        time::{TimeRange, Timestamp},
+    },
+};
+
+use super::{
+    caiso_data_source::CaisoDataSource, eia930_data_source::Eia930DataSource,
+    ercot_data_retriever::ErcotDataSource, pjm_data_source::PjmDataSource,
+    spp_data_source::SppDataSource,
+};
+
+// Every region is resampled into the quarter hours ERCOT settles in
+pub const GRID_INTERVAL_SECONDS: i64 = 900;
+const SECONDS_PER_HOUR: f64 = 3600.0;
+const EIA930_DIRECTORY: &str = "eia930";
+const PRICE_FILE_NAMES: [&str; 2] = ["lmp", "price"];
+const TIMESTAMP_FORMATS: [&str; 6] = [
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%m/%d/%Y %I:%M:%S %p",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+];
+// EIA-930 periods stop at the hour, 2023-06-01T04
+const HOUR_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+// A month of a region's generations along with the rows of its files that
+// could not be read
+#[derive(Debug, PartialEq, Default)]
+pub struct GridDataMonth {
+    pub month: String,
+    pub generations: Vec<GenerationMetric>,
+    pub unparseable_rows: Vec<DataQualityIssue>,
+}
+
+pub trait GridDataSource {
+    fn region(&self) -> GridRegion;
+    fn months(&self, time_range: &TimeRange) -> Result<Vec<GridDataMonth>>;
+
+    // Files in the source's directory that were not ingested, with the reason
+    fn skipped_files(&self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+}
+
+// ERCOT is always read, other regions only when their directory exists and
+// balancing authorities for each directory under eia930
+pub fn grid_data_sources(data_directory: &str) -> Result<Vec<Box<dyn GridDataSource>>> {
+    let mut sources: Vec<Box<dyn GridDataSource>> =
+        vec![Box::new(ErcotDataSource::new(data_directory))];
+
+    for region in GridRegion::all() {
+        if !Path::new(&region_directory(data_directory, &region)).is_dir() {
+            continue;
+        }
+
+        match region {
+            GridRegion::Caiso => sources.push(Box::new(CaisoDataSource::new(data_directory))),
+            GridRegion::Pjm => sources.push(Box::new(PjmDataSource::new(data_directory))),
+            GridRegion::Spp => sources.push(Box::new(SppDataSource::new(data_directory))),
+            GridRegion::Ercot | GridRegion::BalancingAuthority(_) => continue,
+        }
+    }
+
+    let eia930_directory = Directory::new(&format!("{}/{}", data_directory, EIA930_DIRECTORY));
+
+    if Path::new(eia930_directory.path()).is_dir() {
+        for path in eia930_directory.list_directories()? {
+            let Some(code) = Path::new(&path).file_name() else {
+                continue;
+            };
+
+            sources.push(Box::new(Eia930DataSource::new(
+                data_directory,
+                &code.to_string_lossy(),
+            )));
+        }
+    }
+
+    Ok(sources)
+}
+
+pub fn region_directory(data_directory: &str, region: &GridRegion) -> String {
+    format!("{}/{}", data_directory, region.directory())
+}
+
+// A region's CSV and ZIP files, with price files told apart from fuel mix files
+// by lmp or price in their name
+pub struct RegionFiles {
+    pub fuel_mix: ExcelSheet,
+    pub prices: ExcelSheet,
+}
+
+impl RegionFiles {
+    pub fn open(data_directory: &str, region: &GridRegion) -> Result<RegionFiles> {
+        let (price_paths, fuel_mix_paths): (Vec<String>, Vec<String>) =
+            Directory::new(&region_directory(data_directory, region))
+                .list_files()?
+                .into_iter()
+                .filter(|path| {
+                    Path::new(path).extension().is_some_and(|extension| {
+                        ["csv", "zip"]
+                            .contains(&extension.to_string_lossy().to_lowercase().as_str())
+                    })
+                })
+                .partition(|path| {
+                    Path::new(path).file_stem().is_some_and(|name| {
+                        let name = name.to_string_lossy().to_lowercase();
+
+                        PRICE_FILE_NAMES.iter().any(|price| name.contains(price))
+                    })
+                });
+
+        Ok(RegionFiles {
+            fuel_mix: CsvFile::open_all(&fuel_mix_paths)?,
+            prices: CsvFile::open_all(&price_paths)?,
+        })
+    }
+}
+
+// Where a source's fuel mix file keeps its timestamps and generation. Files
+// with a column per fuel leave the fuel and generation headers empty. The
+// offset moves timestamps stamped at the end of the interval to its start
+pub struct FuelMixLayout<'a> {
+    pub time_headers: &'a [&'a str],
+    pub fuel_headers: &'a [&'a str],
+    pub generation_headers: &'a [&'a str],
+    pub fuels: &'a [(&'a str, EnergySource)],
+    pub filters: &'a [(&'a str, &'a str)],
+    pub start_offset_seconds: i64,
+}
+
+// Where a source's price file keeps its timestamps and prices. Files without
+// node headers hold a single price per interval
+pub struct NodePriceLayout<'a> {
+    pub time_headers: &'a [&'a str],
+    pub node_headers: &'a [&'a str],
+    pub price_headers: &'a [&'a str],
+    pub filters: &'a [(&'a str, &'a str)],
+    pub reference_node: &'a str,
+    pub start_offset_seconds: i64,
+}
+
+// Timestamps without an offset are read as UTC
+pub fn parse_utc_timestamp(value: &str) -> Result<Timestamp> {
+    let value = value.trim();
+
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Timestamp::from(date_time));
+    }
+
+    TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| NaiveDateTime::parse_from_str(&format!("{}:00", value), HOUR_FORMAT).ok())
+        .map(|date_time| Timestamp::from(Utc.from_utc_datetime(&date_time)))
+        .ok_or_else(|| Error::invalid_argument(&format!("Invalid timestamp {}", value)))
+}
+
+// Rows are only read when every filter column holds the filter's value
+fn filter_columns<'a>(
+    header: &ExcelRow,
+    filters: &[(&str, &'a str)],
+) -> Result<Vec<(usize, &'a str)>> {
+    filters
+        .iter()
+        .map(|(name, value)| Ok((header.find_column(&[name])?, *value)))
+        .collect()
+}
+
+fn is_filtered(row: &ExcelRow, filter_columns: &[(usize, &str)]) -> bool {
+    filter_columns
+        .iter()
+        .any(|(column, value)| row.get_string(*column).ok() != Some(*value))
+}
+
+pub fn unparseable_row(report: &str, row_index: usize, error: &Error) -> DataQualityIssue {
+    DataQualityIssue::new(
+        DataQualityIssueKind::UnparseableRow,
+        None,
+        &format!("{report} row {}: {error}", row_index + 1),
+    )
+}
+
+// Reads a sheet with a timestamp column and a column of MW per fuel, adding
+// together the columns of fuels that count as the same energy source. A row
+// with a blank fuel is reported and left out rather than read as no generation
+pub fn read_fuel_columns(
+    sheet: &ExcelSheet,
+    layout: &FuelMixLayout,
+    issues: &mut Vec<DataQualityIssue>,
+) -> Result<HashMap<Timestamp, EnergySourcePortfolio>> {
+    let mut fuel_mixes: HashMap<Timestamp, EnergySourcePortfolio> = HashMap::new();
+    let mut rows = sheet.rows().enumerate();
+    let Some((_, header)) = rows.next() else {
+        return Ok(fuel_mixes);
+    };
+    let time_column = header.find_column(layout.time_headers)?;
+    let filter_columns = filter_columns(&header, layout.filters)?;
+    let fuel_columns = header
+        .string_cells()
+        .into_iter()
+        .filter_map(|(column, name)| {
+            layout
+                .fuels
+                .iter()
+                .find(|(fuel, _)| {
+                    ExcelRow::normalize_header(fuel) == ExcelRow::normalize_header(name)
+                })
+                .map(|(_, source)| (column, name, source.clone()))
+        })
+        .collect::<Vec<(usize, &str, EnergySource)>>();
+
+    for (row_index, row) in rows {
+        if is_filtered(&row, &filter_columns) {
+            continue;
+        }
+
+        let fuel_mix = (|| -> Result<(Timestamp, EnergySourcePortfolio, Vec<&str>)> {
+            let start = parse_utc_timestamp(row.get_string(time_column)?)?;
+            let mut portfolio = EnergySourcePortfolio::default();
+            let mut blank_fuels = vec![];
+
+            for (column, name, source) in &fuel_columns {
+                if !row.has_cell(*column) || row.is_empty_cell(*column)? {
+                    blank_fuels.push(*name);
+                    continue;
+                }
+
+                portfolio.add_energy(source, row.get_float(*column)?);
+            }
+
+            Ok((
+                Timestamp::new(start.seconds + layout.start_offset_seconds, 0),
+                portfolio,
+                blank_fuels,
+            ))
+        })();
+
+        match fuel_mix {
+            Ok((start, portfolio, blank_fuels)) if blank_fuels.is_empty() => {
+                fuel_mixes.insert(start, portfolio);
+            }
+            Ok((_, _, blank_fuels)) => issues.push(DataQualityIssue::new(
+                DataQualityIssueKind::BlankCell,
+                None,
+                &format!(
+                    "Fuel mix row {}: blank {}",
+                    row_index + 1,
+                    blank_fuels.join(", ")
+                ),
+            )),
+            Err(error) => issues.push(unparseable_row("Fuel mix", row_index, &error)),
+        }
+    }
+
+    Ok(fuel_mixes)
+}
+
+// Reads a sheet with a row per fuel and interval, the way PJM and EIA publish
+// generation by fuel
+pub fn read_fuel_rows(
+    sheet: &ExcelSheet,
+    layout: &FuelMixLayout,
+    issues: &mut Vec<DataQualityIssue>,
+) -> Result<HashMap<Timestamp, EnergySourcePortfolio>> {
+    let mut fuel_mixes: HashMap<Timestamp, EnergySourcePortfolio> = HashMap::new();
+    let mut rows = sheet.rows().enumerate();
+    let Some((_, header)) = rows.next() else {
+        return Ok(fuel_mixes);
+    };
+    let time_column = header.find_column(layout.time_headers)?;
+    let fuel_column = header.find_column(layout.fuel_headers)?;
+    let generation_column = header.find_column(layout.generation_headers)?;
+    let filter_columns = filter_columns(&header, layout.filters)?;
+
+    for (row_index, row) in rows {
+        if is_filtered(&row, &filter_columns) {
+            continue;
+        }
+
+        let fuel_mix = (|| -> Result<(Timestamp, EnergySource, f64)> {
+            let start = parse_utc_timestamp(row.get_string(time_column)?)?;
+            let fuel = row.get_string(fuel_column)?;
+            let source = layout
+                .fuels
+                .iter()
+                .find(|(name, _)| {
+                    ExcelRow::normalize_header(name) == ExcelRow::normalize_header(fuel)
+                })
+                .map(|(_, source)| source.clone())
+                .ok_or_else(|| Error::invalid_argument(&format!("Unknown fuel {}", fuel)))?;
+
+            Ok((
+                Timestamp::new(start.seconds + layout.start_offset_seconds, 0),
+                source,
+                row.get_float(generation_column)?,
+            ))
+        })();
+
+        match fuel_mix {
+            Ok((start, source, generation)) => fuel_mixes
+                .entry(start)
+                .or_default()
+                .add_energy(&source, generation),
+            Err(error) => issues.push(unparseable_row("Fuel mix", row_index, &error)),
+        }
+    }
+
+    Ok(fuel_mixes)
+}
+
+// Reads a sheet with a row per pricing node and interval, keeping the price at
+// the reference node or averaging every node when the reference is missing
+pub fn read_node_prices(
+    sheet: &ExcelSheet,
+    layout: &NodePriceLayout,
+    issues: &mut Vec<DataQualityIssue>,
+) -> Result<HashMap<Timestamp, f64>> {
+    let mut node_prices: BTreeMap<Timestamp, Vec<(String, f64)>> = BTreeMap::new();
+    let mut rows = sheet.rows().enumerate();
+    let Some((_, header)) = rows.next() else {
+        return Ok(HashMap::new());
+    };
+    let time_column = header.find_column(layout.time_headers)?;
+    let node_column = match layout.node_headers.is_empty() {
+        true => None,
+        false => Some(header.find_column(layout.node_headers)?),
+    };
+    let price_column = header.find_column(layout.price_headers)?;
+    let filter_columns = filter_columns(&header, layout.filters)?;
+
+    for (row_index, row) in rows {
+        if is_filtered(&row, &filter_columns) {
+            continue;
+        }
+
+        let node_price = (|| -> Result<(Timestamp, String, f64)> {
+            let start = parse_utc_timestamp(row.get_string(time_column)?)?;
+
+            Ok((
+                Timestamp::new(start.seconds + layout.start_offset_seconds, 0),
+                match node_column {
+                    Some(node_column) => String::from(row.get_string(node_column)?),
+                    None => String::from(layout.reference_node),
+                },
+                row.get_float(price_column)?,
+            ))
+        })();
+
+        match node_price {
+            Ok((start, node, price)) => node_prices.entry(start).or_default().push((node, price)),
+            Err(error) => issues.push(unparseable_row("LMP", row_index, &error)),
+        }
+    }
+
+    // Other nodes are not blended in for a missing reference price, the
+    // interval is reported and left out instead
+    let mut reference_prices = HashMap::new();
+
+    for (start, prices) in node_prices {
+        match prices
+            .iter()
+            .find(|(node, _)| node == layout.reference_node)
+        {
+            Some((_, price)) => {
+                reference_prices.insert(start, *price);
+            }
+            None => issues.push(DataQualityIssue::new(
+                DataQualityIssueKind::MissingPrice,
+                None,
+                &format!(
+                    "No {} price for the interval starting {}",
+                    layout.reference_node,
+                    start
+                        .to_utc_date_time()
+                        .map_or(String::new(), |date| date.to_rfc3339())
+                ),
+            )),
+        }
+    }
+
+    Ok(reference_prices)
+}
+
+// Joins a region's fuel mix, in MW, and prices on the start of each interval.
+// They are resampled into quarter hours stamped at the end of the interval like
+// ERCOT's and split into months in UTC. Rows that could not be read have no
+// month, so they are reported with the first
+pub fn form_months(
+    region: GridRegion,
+    interval_seconds: i64,
+    fuel_mixes: HashMap<Timestamp, EnergySourcePortfolio>,
+    prices: HashMap<Timestamp, f64>,
+    unparseable_rows: Vec<DataQualityIssue>,
+    time_range: &TimeRange,
+) -> Result<Vec<GridDataMonth>> {
+    let mut generations = prices
+        .iter()
+        .filter_map(|(start, price)| {
+            fuel_mixes.get(start).map(|portfolio| {
+                let mut generation = GenerationMetric::new(
+                    GRID_MIX_PLANT_ID,
+                    start,
+                    *price,
+                    EnergySourcePortfolio::scale(
+                        portfolio,
+                        interval_seconds as f64 / SECONDS_PER_HOUR,
+                    ),
+                );
+                generation.region = region.clone();
+
+                generation
+            })
+        })
+        .collect::<Vec<GenerationMetric>>();
+    generations.sort_by_key(|generation| generation.time_generated);
+    let mut months: BTreeMap<(i32, u32), GridDataMonth> = BTreeMap::new();
+
+    for generation in resample_series(&generations, interval_seconds, GRID_INTERVAL_SECONDS) {
+        let start = generation.time_generated.to_utc_date_time()?;
+        let generation = GenerationMetric {
+            time_generated: Timestamp::new(
+                generation.time_generated.seconds + GRID_INTERVAL_SECONDS,
+                0,
+            ),
+            ..generation
+        };
+
+        if !time_range.contains(&generation.time_generated) {
+            continue;
+        }
+
+        months
+            .entry((start.year(), start.month()))
+            .or_insert_with(|| GridDataMonth {
+                month: start.format("%b %Y").to_string(),
+                ..Default::default()
+            })
+            .generations
+            .push(generation);
+    }
+
+    let mut months = months.into_values().collect::<Vec<GridDataMonth>>();
+
+    match months.first_mut() {
+        Some(month) => month.unparseable_rows = unparseable_rows,
+        None if !unparseable_rows.is_empty() => months.push(GridDataMonth {
+            month: String::from("No month"),
+            generations: vec![],
+            unparseable_rows,
+        }),
+        None => {}
+    }
+
+    Ok(months)
+}
+
+#[cfg(test)]
+pub fn create_sheet(csv: &str) -> ExcelSheet {
+    ExcelSheet::from_rows(CsvFile::read_records(csv.as_bytes()).unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        grid::GridRegion,
+        simulation::EnergySourcePortfolio,
+        time::{TimeRange, Timestamp},
+    };
+
+    use super::{form_months, parse_utc_timestamp};
+
+    #[test]
+    fn should_parse_utc_timestamps() {
+        assert_eq!(
+            parse_utc_timestamp("2023-01-01T08:00:00-00:00").unwrap(),
+            Timestamp::new(1672560000, 0)
+        );
+        assert_eq!(
+            parse_utc_timestamp("01/01/2023 8:00:00 AM").unwrap(),
+            Timestamp::new(1672560000, 0)
+        );
+        assert_eq!(
+            parse_utc_timestamp("2023-01-01 08:00").unwrap(),
+            Timestamp::new(1672560000, 0)
+        );
+        assert!(parse_utc_timestamp("Hour 1").is_err());
+    }
+
+    #[test]
+    fn should_resample_hourly_data_into_quarter_hours_by_month() {
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.add_energy(&crate::schema::simulation::EnergySource::Wind, 100.0);
+        let january_end = 1675209600 - 3600;
+        let fuel_mixes = HashMap::from([
+            (Timestamp::new(january_end, 0), portfolio),
+            (Timestamp::new(january_end + 3600, 0), portfolio),
+        ]);
+        let prices = HashMap::from([
+            (Timestamp::new(january_end, 0), 30.0),
+            (Timestamp::new(january_end + 3600, 0), 40.0),
+        ]);
+
+        let months = form_months(
+            GridRegion::Pjm,
+            3600,
+            fuel_mixes,
+            prices,
+            vec![],
+            &TimeRange::all(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            months
+                .iter()
+                .map(|month| (month.month.as_str(), month.generations.len()))
+                .collect::<Vec<(&str, usize)>>(),
+            vec![("Jan 2023", 4), ("Feb 2023", 4)]
+        );
+        assert_eq!(
+            months[1].generations[0].time_generated,
+            Timestamp::new(1675209600 + 900, 0)
+        );
+        assert_eq!(months[1].generations[0].portfolio.wind_mwh, 25.0);
+        assert_eq!(months[1].generations[0].sale_price_usd_per_mwh, 40.0);
+        assert_eq!(months[1].generations[0].region, GridRegion::Pjm);
+    }
+}
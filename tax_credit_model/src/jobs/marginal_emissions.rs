@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use crate::{
+    parsers::{csv::CsvFile, excel::ExcelSheet},
+    schema::{
+        data_quality::DataQualityIssue, errors::Result, grid::GridRegion,
+        simulation::GenerationMetric,
+    },
+};
+
+use super::grid_data_source::{parse_utc_timestamp, unparseable_row, GRID_INTERVAL_SECONDS};
+
+const TIME_HEADERS: [&str; 3] = ["Interval Start", "interval_start_utc", "point_time"];
+const RATE_HEADERS: [&str; 2] = ["kg_co2e_per_mwh", "Marginal Emission Rate (kg/MWh)"];
+
+// An imported marginal emissions series is read from a CSV named after the
+// region's data directory, such as marginal_emissions/ercot.csv, with the start
+// of each interval in UTC and the kg CO2e per MWh emitted by the marginal unit
+pub struct MarginalEmissionsJob {}
+
+impl MarginalEmissionsJob {
+    pub fn extract(data_directory: &str, region: &GridRegion) -> Result<ExcelSheet> {
+        let path = format!(
+            "{}/{}/{}.csv",
+            data_directory,
+            "marginal_emissions",
+            region.directory()
+        );
+
+        if !Path::new(&path).is_file() {
+            return Ok(ExcelSheet::from_rows(vec![]));
+        }
+
+        CsvFile::open(&path)
+    }
+
+    pub fn transform(
+        region: &GridRegion,
+        sheet: &ExcelSheet,
+        issues: &mut Vec<DataQualityIssue>,
+    ) -> Result<Vec<(i64, f64)>> {
+        let mut rows = sheet.rows().enumerate();
+        let Some((_, header)) = rows.next() else {
+            return Ok(vec![]);
+        };
+        let time_column = header.find_column(&TIME_HEADERS)?;
+        let rate_column = header.find_column(&RATE_HEADERS)?;
+        let mut rates = vec![];
+
+        for (row_index, row) in rows {
+            let rate = (|| -> Result<(i64, f64)> {
+                Ok((
+                    parse_utc_timestamp(row.get_string(time_column)?)?.seconds,
+                    row.get_float(rate_column)?,
+                ))
+            })();
+
+            match rate {
+                Ok(rate) => rates.push(rate),
+                Err(error) => issues.push(unparseable_row(
+                    &format!("{} marginal emissions", region),
+                    row_index,
+                    &error,
+                )),
+            }
+        }
+
+        rates.sort_by_key(|(start, _)| *start);
+
+        Ok(rates)
+    }
+
+    // Each generation, stamped at the end of its interval, takes the rate of
+    // the series interval it starts in. Generations outside the series keep no
+    // rate
+    pub fn apply(generations: &mut [GenerationMetric], region: &GridRegion, rates: &[(i64, f64)]) {
+        let series_seconds = rates
+            .windows(2)
+            .map(|pair| pair[1].0 - pair[0].0)
+            .filter(|seconds| *seconds > 0)
+            .min()
+            .unwrap_or(GRID_INTERVAL_SECONDS);
+
+        for generation in generations
+            .iter_mut()
+            .filter(|generation| &generation.region == region)
+        {
+            let start = generation.time_generated.seconds - GRID_INTERVAL_SECONDS;
+            let index = rates.partition_point(|(rate_start, _)| *rate_start <= start);
+
+            generation.marginal_emission_rate_kg_co2e_per_mwh = index
+                .checked_sub(1)
+                .map(|index| rates[index])
+                .filter(|(rate_start, _)| start < rate_start + series_seconds)
+                .map(|(_, rate)| rate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        jobs::grid_data_source::create_sheet,
+        schema::{
+            grid::GridRegion,
+            simulation::{EnergySourcePortfolio, GenerationMetric},
+            time::Timestamp,
+        },
+    };
+
+    use super::MarginalEmissionsJob;
+
+    #[test]
+    fn should_apply_hourly_rates_to_quarter_hours() {
+        let sheet = create_sheet(
+            "Interval Start,kg_co2e_per_mwh\n\
+             1970-01-01 01:00,400\n\
+             1970-01-01 00:00,500\n\
+             1970-01-01 02:00,n/a\n",
+        );
+        let mut issues = vec![];
+        let mut generations = (1..=10)
+            .map(|interval| {
+                GenerationMetric::new(
+                    0,
+                    &Timestamp::new(interval * 900, 0),
+                    0.0,
+                    EnergySourcePortfolio::default(),
+                )
+            })
+            .collect::<Vec<GenerationMetric>>();
+
+        let rates =
+            MarginalEmissionsJob::transform(&GridRegion::Ercot, &sheet, &mut issues).unwrap();
+        MarginalEmissionsJob::apply(&mut generations, &GridRegion::Ercot, &rates);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            generations
+                .iter()
+                .map(|generation| generation.marginal_emission_rate_kg_co2e_per_mwh)
+                .collect::<Vec<Option<f64>>>(),
+            vec![
+                Some(500.0),
+                Some(500.0),
+                Some(500.0),
+                Some(500.0),
+                Some(400.0),
+                Some(400.0),
+                Some(400.0),
+                Some(400.0),
+                None,
+                None,
+            ]
+        );
+    }
+}
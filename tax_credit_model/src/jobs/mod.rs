@@ -1 +1,10 @@
+pub mod caiso_data_source;
+pub mod data_quality;
+pub mod eia930_data_source;
 pub mod ercot_data_retriever;
+pub mod grid_data_source;
+pub mod marginal_emissions;
+pub mod pjm_data_source;
+pub mod plant_generation;
+pub mod simulation_runner;
+pub mod spp_data_source;
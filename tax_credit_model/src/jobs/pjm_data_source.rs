@@ -0,0 +1,148 @@
+use crate::schema::{errors::Result, grid::GridRegion, simulation::EnergySource, time::TimeRange};
+
+use super::grid_data_source::{
+    form_months, read_fuel_rows, read_node_prices, FuelMixLayout, GridDataMonth, GridDataSource,
+    NodePriceLayout, RegionFiles,
+};
+
+// Data Miner publishes hourly generation by fuel and real-time LMPs with a row
+// per fuel or pricing node
+const INTERVAL_SECONDS: i64 = 3600;
+const TIME_HEADERS: [&str; 1] = ["datetime_beginning_utc"];
+const FUEL_HEADERS: [&str; 1] = ["fuel_type"];
+const GENERATION_HEADERS: [&str; 1] = ["mw"];
+const FUELS: [(&str, EnergySource); 11] = [
+    ("Coal", EnergySource::Coal),
+    ("Gas", EnergySource::NaturalGas),
+    ("Hydro", EnergySource::Hydropower),
+    ("Multiple Fuels", EnergySource::Unknown),
+    ("Nuclear", EnergySource::Nuclear),
+    ("Oil", EnergySource::Petroleum),
+    ("Other Renewables", EnergySource::Biomass),
+    ("Other", EnergySource::Unknown),
+    ("Solar", EnergySource::Solar),
+    ("Storage", EnergySource::WholesaleStorageLoad),
+    ("Wind", EnergySource::Wind),
+];
+const NODE_HEADERS: [&str; 1] = ["pnode_name"];
+const PRICE_HEADERS: [&str; 1] = ["total_lmp_rt"];
+const REFERENCE_NODE: &str = "WESTERN HUB";
+const FUEL_MIX_LAYOUT: FuelMixLayout = FuelMixLayout {
+    time_headers: &TIME_HEADERS,
+    fuel_headers: &FUEL_HEADERS,
+    generation_headers: &GENERATION_HEADERS,
+    fuels: &FUELS,
+    filters: &[],
+    start_offset_seconds: 0,
+};
+const LMP_LAYOUT: NodePriceLayout = NodePriceLayout {
+    time_headers: &TIME_HEADERS,
+    node_headers: &NODE_HEADERS,
+    price_headers: &PRICE_HEADERS,
+    filters: &[],
+    reference_node: REFERENCE_NODE,
+    start_offset_seconds: 0,
+};
+
+pub struct PjmDataSource {
+    data_directory: String,
+}
+
+impl PjmDataSource {
+    pub fn new(data_directory: &str) -> Self {
+        Self {
+            data_directory: String::from(data_directory),
+        }
+    }
+
+    pub fn transform(files: &RegionFiles, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        let mut issues = vec![];
+        let fuel_mixes = read_fuel_rows(&files.fuel_mix, &FUEL_MIX_LAYOUT, &mut issues)?;
+        let prices = read_node_prices(&files.prices, &LMP_LAYOUT, &mut issues)?;
+
+        form_months(
+            GridRegion::Pjm,
+            INTERVAL_SECONDS,
+            fuel_mixes,
+            prices,
+            issues,
+            time_range,
+        )
+    }
+}
+
+impl GridDataSource for PjmDataSource {
+    fn region(&self) -> GridRegion {
+        GridRegion::Pjm
+    }
+
+    fn months(&self, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        PjmDataSource::transform(
+            &RegionFiles::open(&self.data_directory, &self.region())?,
+            time_range,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        jobs::grid_data_source::{create_sheet, RegionFiles},
+        schema::{
+            grid::GridRegion,
+            time::{TimeRange, Timestamp},
+        },
+    };
+
+    use super::PjmDataSource;
+
+    #[test]
+    fn should_split_hourly_fuel_rows_into_quarter_hours() {
+        let files = RegionFiles {
+            fuel_mix: create_sheet(
+                "datetime_beginning_utc,datetime_beginning_ept,fuel_type,mw,fuel_percentage_of_total,is_renewable\n\
+                 6/1/2023 4:00:00 AM,6/1/2023 12:00:00 AM,Gas,40000,0.5,false\n\
+                 6/1/2023 4:00:00 AM,6/1/2023 12:00:00 AM,Nuclear,32000,0.4,false\n\
+                 6/1/2023 4:00:00 AM,6/1/2023 12:00:00 AM,Wind,8000,0.1,true\n\
+                 6/1/2023 4:00:00 AM,6/1/2023 12:00:00 AM,Tidal,10,0.0,true\n",
+            ),
+            prices: create_sheet(
+                "datetime_beginning_utc,pnode_name,total_lmp_rt\n\
+                 6/1/2023 4:00:00 AM,WESTERN HUB,28.5\n\
+                 6/1/2023 4:00:00 AM,AEP-DAYTON HUB,31.5\n",
+            ),
+        };
+
+        let months = PjmDataSource::transform(
+            &files,
+            &TimeRange {
+                start: Timestamp::new(1685592000, 0),
+                end: Timestamp::new(1685595600, 0),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(months.len(), 1);
+        assert_eq!(months[0].unparseable_rows.len(), 1);
+        assert_eq!(
+            months[0]
+                .generations
+                .iter()
+                .map(|generation| (
+                    generation.time_generated.seconds,
+                    generation.sale_price_usd_per_mwh,
+                    generation.portfolio.natural_gas_mwh,
+                    generation.portfolio.wind_mwh,
+                ))
+                .collect::<Vec<(i64, f64, f64, f64)>>(),
+            vec![
+                (1685592900, 28.5, 10000.0, 2000.0),
+                (1685593800, 28.5, 10000.0, 2000.0),
+                (1685594700, 28.5, 10000.0, 2000.0),
+            ]
+        );
+        assert_eq!(months[0].generations[0].region, GridRegion::Pjm);
+    }
+}
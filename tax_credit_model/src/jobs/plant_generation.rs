@@ -0,0 +1,185 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    file_systems::directory::Directory,
+    logic::resampling::{interval_seconds, resample_series},
+    parsers::{csv::CsvFile, excel::ExcelSheet},
+    schema::{
+        data_quality::DataQualityIssue,
+        errors::Result,
+        plant::{PowerPlantDetails, GRID_MIX_PLANT_ID},
+        simulation::{EnergySourcePortfolio, GenerationMetric},
+        time::Timestamp,
+    },
+};
+
+use super::grid_data_source::{parse_utc_timestamp, unparseable_row, GRID_INTERVAL_SECONDS};
+
+const TIME_HEADERS: [&str; 3] = ["Interval End", "interval_end_utc", "time_generated"];
+const GENERATION_HEADERS: [&str; 3] = ["MWh", "generation_mwh", "Net Generation (MWh)"];
+
+// A registered plant's output is read from CSV or ZIP files named by its id in
+// the plants directory, with the end of each interval in UTC and the MWh
+// generated in it
+pub struct PlantGenerationJob {}
+
+impl PlantGenerationJob {
+    pub fn extract(plants_directory: &str, plant: &PowerPlantDetails) -> Result<ExcelSheet> {
+        if !Path::new(plants_directory).is_dir() {
+            return Ok(ExcelSheet::from_rows(vec![]));
+        }
+
+        let paths = Directory::new(plants_directory)
+            .list_files()?
+            .into_iter()
+            .filter(|path| {
+                let path = Path::new(path);
+
+                path.file_stem()
+                    .is_some_and(|name| name.to_string_lossy() == plant.plant_id.to_string())
+                    && path.extension().is_some_and(|extension| {
+                        ["csv", "zip"]
+                            .contains(&extension.to_string_lossy().to_lowercase().as_str())
+                    })
+            })
+            .collect::<Vec<String>>();
+
+        CsvFile::open_all(&paths)
+    }
+
+    // The plant's output is resampled into the grid's quarter hours and takes
+    // the grid mix's prices at every settlement point for the same interval, so
+    // a simulation can buy it at the plant's location. Intervals the grid has
+    // no prices for and those before the plant began commercial operation are
+    // left out
+    pub fn transform(
+        plant: &PowerPlantDetails,
+        sheet: &ExcelSheet,
+        grid_generations: &[GenerationMetric],
+        issues: &mut Vec<DataQualityIssue>,
+    ) -> Result<Vec<GenerationMetric>> {
+        let mut rows = sheet.rows().enumerate();
+        let Some((_, header)) = rows.next() else {
+            return Ok(vec![]);
+        };
+        let time_column = header.find_column(&TIME_HEADERS)?;
+        let generation_column = header.find_column(&GENERATION_HEADERS)?;
+        let mut generations = vec![];
+
+        for (row_index, row) in rows {
+            let generation = (|| -> Result<GenerationMetric> {
+                let end = parse_utc_timestamp(row.get_string(time_column)?)?;
+                let mut portfolio = EnergySourcePortfolio::default();
+                portfolio.add_energy(&plant.fuel_type, row.get_float(generation_column)?);
+
+                Ok(GenerationMetric::new(plant.plant_id, &end, 0.0, portfolio))
+            })();
+
+            match generation {
+                Ok(generation) => generations.push(generation),
+                Err(error) => issues.push(unparseable_row(&plant.name, row_index, &error)),
+            }
+        }
+
+        generations.sort_by_key(|generation| generation.time_generated);
+        let series_seconds = interval_seconds(&generations).unwrap_or(GRID_INTERVAL_SECONDS);
+
+        for generation in &mut generations {
+            generation.time_generated.seconds -= series_seconds;
+        }
+
+        let grid_mix = grid_generations
+            .iter()
+            .filter(|generation| {
+                generation.plant_id == GRID_MIX_PLANT_ID && generation.region == plant.region
+            })
+            .map(|generation| (generation.time_generated, generation))
+            .collect::<HashMap<Timestamp, &GenerationMetric>>();
+
+        Ok(
+            resample_series(&generations, series_seconds, GRID_INTERVAL_SECONDS)
+                .into_iter()
+                .filter(|generation| generation.time_generated >= plant.commercial_operation_date)
+                .filter_map(|generation| {
+                    let end = Timestamp::new(
+                        generation.time_generated.seconds + GRID_INTERVAL_SECONDS,
+                        0,
+                    );
+
+                    grid_mix.get(&end).map(|grid_mix| GenerationMetric {
+                        plant_id: plant.plant_id,
+                        portfolio: generation.portfolio,
+                        filled: grid_mix.filled || generation.filled,
+                        ..(*grid_mix).clone()
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        jobs::grid_data_source::create_sheet,
+        schema::{
+            plant::PowerPlantDetails,
+            simulation::{EnergySource, EnergySourcePortfolio, GenerationMetric},
+            time::Timestamp,
+        },
+    };
+
+    use super::PlantGenerationJob;
+
+    #[test]
+    fn should_price_plant_output_from_the_grid_mix_after_commercial_operation() {
+        let plant = PowerPlantDetails {
+            plant_id: 7,
+            name: String::from("Solar Farm"),
+            fuel_type: EnergySource::Solar,
+            commercial_operation_date: Timestamp::new(3600, 0),
+            ..Default::default()
+        };
+        let grid_generations = (1..=8)
+            .map(|interval| {
+                GenerationMetric::new(
+                    0,
+                    &Timestamp::new(interval * 900, 0),
+                    interval as f64,
+                    EnergySourcePortfolio::default(),
+                )
+            })
+            .collect::<Vec<GenerationMetric>>();
+        let sheet = create_sheet(
+            "Interval End,MWh\n\
+             1970-01-01 01:00,40\n\
+             1970-01-01 02:00,80\n\
+             1970-01-01 03:00,not a number\n",
+        );
+        let mut issues = vec![];
+
+        let generations =
+            PlantGenerationJob::transform(&plant, &sheet, &grid_generations, &mut issues).unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            generations
+                .iter()
+                .map(|generation| (
+                    generation.plant_id,
+                    generation.time_generated.seconds,
+                    generation.sale_price_usd_per_mwh,
+                    generation.portfolio.solar_mwh
+                ))
+                .collect::<Vec<(usize, i64, f64, f64)>>(),
+            vec![
+                (7, 4500, 5.0, 20.0),
+                (7, 5400, 6.0, 20.0),
+                (7, 6300, 7.0, 20.0),
+                (7, 7200, 8.0, 20.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,520 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    concurrency::mutex::Mutex,
+    logic::simulation::{simulate, SimulationContext},
+    persistance::simulation::SimulationClient,
+    schema::{
+        certificate::EnergyAttributeCertificate,
+        electrolyzer::Electrolyzer,
+        emissions::EmissionFactorSet,
+        errors::{Error, Result},
+        simulation::{
+            ExecuteSimulationRequest, PowerGrid, SimulationId, SimulationResult, SimulationStatus,
+        },
+        user::UserId,
+    },
+};
+
+pub struct SimulationJob {
+    pub simulation_id: SimulationId,
+    pub user_id: UserId,
+    pub power_grid: PowerGrid,
+    pub electrolyzer: Electrolyzer,
+    pub emission_factors: EmissionFactorSet,
+    pub certificates: Vec<EnergyAttributeCertificate>,
+    pub request: ExecuteSimulationRequest,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct SimulationJobStatus {
+    pub status: SimulationStatus,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct SimulationJobRecord {
+    user_id: UserId,
+    status: SimulationJobStatus,
+    result: Option<SimulationResult>,
+    finished_at: Option<Instant>,
+}
+
+// Finished jobs are kept so their results can be read more than once, the
+// oldest are dropped once there are more than this many
+const MAX_FINISHED_JOBS: usize = 32;
+
+type SimulationJobs = Arc<Mutex<HashMap<SimulationId, SimulationJobRecord>>>;
+
+// Runs simulations on a fixed pool of worker threads so a long run neither ties
+// up a request worker nor outlives a proxy timeout
+pub struct SimulationJobRunner {
+    sender: Mutex<Sender<SimulationJob>>,
+    // Held so jobs still queue while no worker is running
+    _receiver: Arc<Mutex<Receiver<SimulationJob>>>,
+    jobs: SimulationJobs,
+    simulation_client: Arc<dyn SimulationClient>,
+}
+
+impl SimulationJobRunner {
+    pub fn new(workers: usize, simulation_client: Arc<dyn SimulationClient>) -> Self {
+        let (sender, receiver) = channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let jobs: SimulationJobs = Arc::new(Mutex::new(HashMap::new()));
+
+        for _ in 0..workers {
+            let receiver = receiver.clone();
+            let jobs = jobs.clone();
+            let simulation_client = simulation_client.clone();
+
+            thread::spawn(move || {
+                SimulationJobRunner::work(receiver, jobs, simulation_client.as_ref())
+            });
+        }
+
+        Self {
+            sender: Mutex::new(sender),
+            _receiver: receiver,
+            jobs,
+            simulation_client,
+        }
+    }
+
+    pub fn submit(&self, job: SimulationJob) -> Result<()> {
+        let simulation_id = job.simulation_id;
+        {
+            let mut jobs = Mutex::lock(&self.jobs)?;
+            prune_finished(&mut jobs);
+            jobs.insert(
+                simulation_id,
+                SimulationJobRecord {
+                    user_id: job.user_id,
+                    ..Default::default()
+                },
+            );
+        }
+        set_status(
+            &self.jobs,
+            self.simulation_client.as_ref(),
+            simulation_id,
+            SimulationStatus::Queued,
+            "",
+        )?;
+
+        Mutex::lock(&self.sender)?
+            .send(job)
+            .map_err(|err| Error::unknown(&err.to_string()))
+    }
+
+    pub fn status(
+        &self,
+        user_id: &UserId,
+        simulation_id: &SimulationId,
+    ) -> Result<SimulationJobStatus> {
+        let mut jobs = Mutex::lock(&self.jobs)?;
+
+        Ok(owned_record(&mut jobs, user_id, simulation_id)?
+            .status
+            .clone())
+    }
+
+    pub fn result(
+        &self,
+        user_id: &UserId,
+        simulation_id: &SimulationId,
+    ) -> Result<SimulationResult> {
+        let mut jobs = Mutex::lock(&self.jobs)?;
+
+        owned_record(&mut jobs, user_id, simulation_id)?
+            .result
+            .clone()
+            .ok_or_else(|| Error::not_found("No simulation result found"))
+    }
+
+    // Queued jobs are skipped when a worker picks them up and running jobs stop
+    // the next time they report progress
+    pub fn cancel(
+        &self,
+        user_id: &UserId,
+        simulation_id: &SimulationId,
+    ) -> Result<SimulationJobStatus> {
+        let status = {
+            let mut jobs = Mutex::lock(&self.jobs)?;
+            let record = owned_record(&mut jobs, user_id, simulation_id)?;
+
+            if record.status.status.is_finished() {
+                return Ok(record.status.clone());
+            }
+
+            record.status = SimulationJobStatus {
+                status: SimulationStatus::Cancelled,
+                message: String::from("Cancelled by user"),
+            };
+            record.finished_at = Some(Instant::now());
+            record.status.clone()
+        };
+
+        update_state(
+            self.simulation_client.as_ref(),
+            *simulation_id,
+            status.status.clone(),
+        )?;
+
+        Ok(status)
+    }
+
+    fn work(
+        receiver: Arc<Mutex<Receiver<SimulationJob>>>,
+        jobs: SimulationJobs,
+        simulation_client: &dyn SimulationClient,
+    ) {
+        loop {
+            let job = match Mutex::lock(&receiver).map(|receiver| receiver.recv()) {
+                Ok(Ok(job)) => job,
+                _ => return,
+            };
+
+            if let Err(err) = SimulationJobRunner::run(&job, &jobs, simulation_client) {
+                eprintln!("Simulation {} failed: {}", job.simulation_id, err);
+            }
+        }
+    }
+
+    fn run(
+        job: &SimulationJob,
+        jobs: &SimulationJobs,
+        simulation_client: &dyn SimulationClient,
+    ) -> Result<()> {
+        if is_cancelled(jobs, &job.simulation_id)? {
+            return Ok(());
+        }
+
+        set_status(
+            jobs,
+            simulation_client,
+            job.simulation_id,
+            SimulationStatus::Running { progress: 0.0 },
+            "",
+        )?;
+        let outcome = simulate(
+            job.simulation_id,
+            &SimulationContext {
+                power_grid: &job.power_grid,
+                electrolyzer: &job.electrolyzer,
+                emission_factors: &job.emission_factors,
+                certificates: &job.certificates,
+                request: &job.request,
+            },
+            simulation_client,
+            &|progress| {
+                let mut jobs = Mutex::lock(jobs)?;
+                let record = jobs.entry(job.simulation_id).or_default();
+
+                if record.status.status == SimulationStatus::Cancelled {
+                    return Err(Error::cancelled("Simulation was cancelled"));
+                }
+
+                record.status.status = SimulationStatus::Running { progress };
+
+                Ok(())
+            },
+        );
+
+        match outcome {
+            Ok(result) => complete(jobs, simulation_client, job.simulation_id, result),
+            Err(Error::Cancelled(_)) => Ok(()),
+            Err(err) => set_status(
+                jobs,
+                simulation_client,
+                job.simulation_id,
+                SimulationStatus::Failed,
+                &err.to_string(),
+            ),
+        }
+    }
+}
+
+fn is_cancelled(jobs: &SimulationJobs, simulation_id: &SimulationId) -> Result<bool> {
+    Ok(Mutex::lock(jobs)?
+        .get(simulation_id)
+        .is_some_and(|record| record.status.status == SimulationStatus::Cancelled))
+}
+
+// Jobs submitted by another user are reported as missing
+fn owned_record<'a>(
+    jobs: &'a mut HashMap<SimulationId, SimulationJobRecord>,
+    user_id: &UserId,
+    simulation_id: &SimulationId,
+) -> Result<&'a mut SimulationJobRecord> {
+    jobs.get_mut(simulation_id)
+        .filter(|record| record.user_id == *user_id)
+        .ok_or_else(|| Error::not_found("No simulation job found"))
+}
+
+// The cancellation check and the result are made under one lock so a cancel
+// landing after the last progress report is not overwritten. The cancelled
+// status is persisted again in case the simulation saved its state after it
+fn complete(
+    jobs: &SimulationJobs,
+    simulation_client: &dyn SimulationClient,
+    simulation_id: SimulationId,
+    result: SimulationResult,
+) -> Result<()> {
+    {
+        let mut jobs = Mutex::lock(jobs)?;
+        let record = jobs.entry(simulation_id).or_default();
+
+        if record.status.status == SimulationStatus::Cancelled {
+            drop(jobs);
+
+            return update_state(
+                simulation_client,
+                simulation_id,
+                SimulationStatus::Cancelled,
+            );
+        }
+
+        record.result = Some(result);
+        record.status = SimulationJobStatus {
+            status: SimulationStatus::Complete,
+            message: String::new(),
+        };
+        record.finished_at = Some(Instant::now());
+    }
+
+    update_state(simulation_client, simulation_id, SimulationStatus::Complete)
+}
+
+fn prune_finished(jobs: &mut HashMap<SimulationId, SimulationJobRecord>) {
+    let mut finished: Vec<(Instant, SimulationId)> = jobs
+        .iter()
+        .filter_map(|(id, record)| record.finished_at.map(|finished_at| (finished_at, *id)))
+        .collect();
+
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+
+    finished.sort();
+    for (_, simulation_id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+        jobs.remove(simulation_id);
+    }
+}
+
+fn set_status(
+    jobs: &SimulationJobs,
+    simulation_client: &dyn SimulationClient,
+    simulation_id: SimulationId,
+    status: SimulationStatus,
+    message: &str,
+) -> Result<()> {
+    {
+        let mut jobs = Mutex::lock(jobs)?;
+        let record = jobs.entry(simulation_id).or_default();
+        record.status = SimulationJobStatus {
+            status: status.clone(),
+            message: String::from(message),
+        };
+        record.finished_at = status.is_finished().then(Instant::now);
+    }
+
+    update_state(simulation_client, simulation_id, status)
+}
+
+fn update_state(
+    simulation_client: &dyn SimulationClient,
+    simulation_id: SimulationId,
+    status: SimulationStatus,
+) -> Result<()> {
+    let mut state = simulation_client.get_simulation_state(&simulation_id)?;
+    state.status = status;
+    simulation_client.update(&state)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        logic::simulation::SimulationState,
+        persistance::simulation::{InMemorySimulationClient, SimulationClient},
+        schema::{
+            electrolyzer::Electrolyzer,
+            emissions::EmissionFactorSet,
+            simulation::{ExecuteSimulationRequest, PowerGrid, SimulationResult, SimulationStatus},
+            time::DateTimeRange,
+            user::UserId,
+        },
+    };
+
+    use super::{complete, SimulationJob, SimulationJobRunner, MAX_FINISHED_JOBS};
+
+    const USER_ID: UserId = 1;
+
+    fn create_job(simulation_client: &dyn SimulationClient) -> SimulationJob {
+        let simulation_state = simulation_client
+            .create_simulation_state(&SimulationState::default())
+            .unwrap();
+
+        SimulationJob {
+            simulation_id: simulation_state.id,
+            user_id: USER_ID,
+            power_grid: PowerGrid::default(),
+            electrolyzer: Electrolyzer::default(),
+            emission_factors: EmissionFactorSet::default(),
+            certificates: vec![],
+            request: ExecuteSimulationRequest::new(
+                0,
+                DateTimeRange {
+                    start: String::from("2023-01-01T00:00"),
+                    end: String::from("2023-01-01T00:00"),
+                },
+            ),
+        }
+    }
+
+    #[test]
+    fn should_complete_submitted_simulation() {
+        let simulation_client = Arc::new(InMemorySimulationClient::new());
+        let runner = SimulationJobRunner::new(1, simulation_client.clone());
+        let job = create_job(simulation_client.as_ref());
+        let simulation_id = job.simulation_id;
+
+        runner.submit(job).unwrap();
+
+        for _ in 0..100 {
+            if runner
+                .status(&USER_ID, &simulation_id)
+                .unwrap()
+                .status
+                .is_finished()
+            {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(
+            runner.status(&USER_ID, &simulation_id).unwrap().status,
+            SimulationStatus::Complete
+        );
+        assert_eq!(
+            simulation_client
+                .get_simulation_state(&simulation_id)
+                .unwrap()
+                .status,
+            SimulationStatus::Complete
+        );
+        assert!(runner.result(&USER_ID, &simulation_id).is_ok());
+        assert!(runner.result(&USER_ID, &simulation_id).is_ok());
+    }
+
+    #[test]
+    fn should_keep_cancel_landing_after_last_progress_report() {
+        let simulation_client = Arc::new(InMemorySimulationClient::new());
+        let runner = SimulationJobRunner::new(0, simulation_client.clone());
+        let job = create_job(simulation_client.as_ref());
+        let simulation_id = job.simulation_id;
+
+        runner.submit(job).unwrap();
+        runner.cancel(&USER_ID, &simulation_id).unwrap();
+        // The simulation saves its state after the last progress report
+        let mut simulation_state = simulation_client
+            .get_simulation_state(&simulation_id)
+            .unwrap();
+        simulation_state.status = SimulationStatus::Running { progress: 1.0 };
+        simulation_client.update(&simulation_state).unwrap();
+        complete(
+            &runner.jobs,
+            simulation_client.as_ref(),
+            simulation_id,
+            SimulationResult::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            runner.status(&USER_ID, &simulation_id).unwrap().status,
+            SimulationStatus::Cancelled
+        );
+        assert_eq!(
+            simulation_client
+                .get_simulation_state(&simulation_id)
+                .unwrap()
+                .status,
+            SimulationStatus::Cancelled
+        );
+        assert!(runner.result(&USER_ID, &simulation_id).is_err());
+    }
+
+    #[test]
+    fn should_hide_jobs_from_other_users() {
+        let simulation_client = Arc::new(InMemorySimulationClient::new());
+        let runner = SimulationJobRunner::new(0, simulation_client.clone());
+        let job = create_job(simulation_client.as_ref());
+        let simulation_id = job.simulation_id;
+
+        runner.submit(job).unwrap();
+
+        assert!(runner.status(&(USER_ID + 1), &simulation_id).is_err());
+        assert!(runner.cancel(&(USER_ID + 1), &simulation_id).is_err());
+        assert_eq!(
+            runner.status(&USER_ID, &simulation_id).unwrap().status,
+            SimulationStatus::Queued
+        );
+    }
+
+    #[test]
+    fn should_prune_oldest_finished_jobs() {
+        let simulation_client = Arc::new(InMemorySimulationClient::new());
+        let runner = SimulationJobRunner::new(0, simulation_client.clone());
+        let mut simulation_ids = vec![];
+
+        for _ in 0..MAX_FINISHED_JOBS + 2 {
+            let job = create_job(simulation_client.as_ref());
+            simulation_ids.push(job.simulation_id);
+            runner.submit(job).unwrap();
+            runner
+                .cancel(&USER_ID, &simulation_ids[simulation_ids.len() - 1])
+                .unwrap();
+        }
+
+        assert!(runner.status(&USER_ID, &simulation_ids[0]).is_err());
+        assert!(runner.status(&USER_ID, &simulation_ids[1]).is_ok());
+        assert!(runner
+            .status(&USER_ID, &simulation_ids[simulation_ids.len() - 1])
+            .is_ok());
+    }
+
+    #[test]
+    fn should_cancel_queued_simulation() {
+        let simulation_client = Arc::new(InMemorySimulationClient::new());
+        let runner = SimulationJobRunner::new(0, simulation_client.clone());
+        let job = create_job(simulation_client.as_ref());
+        let simulation_id = job.simulation_id;
+
+        runner.submit(job).unwrap();
+        let status = runner.cancel(&USER_ID, &simulation_id).unwrap();
+
+        assert_eq!(status.status, SimulationStatus::Cancelled);
+        assert_eq!(
+            simulation_client
+                .get_simulation_state(&simulation_id)
+                .unwrap()
+                .status,
+            SimulationStatus::Cancelled
+        );
+    }
+}
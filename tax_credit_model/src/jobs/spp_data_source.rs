@@ -0,0 +1,140 @@
+use crate::schema::{errors::Result, grid::GridRegion, simulation::EnergySource, time::TimeRange};
+
+use super::grid_data_source::{
+    form_months, read_fuel_columns, read_node_prices, FuelMixLayout, GridDataMonth, GridDataSource,
+    NodePriceLayout, RegionFiles,
+};
+
+// SPP stamps its five minute generation mix and LMPs at the end of the interval
+const INTERVAL_SECONDS: i64 = 300;
+const FUEL_MIX_TIME_HEADERS: [&str; 1] = ["GMT MKT Interval"];
+// Each fuel is split between what the market dispatched and what units
+// self-scheduled
+const FUELS: [(&str, EnergySource); 22] = [
+    ("Coal Market", EnergySource::Coal),
+    ("Coal Self", EnergySource::Coal),
+    ("Diesel Fuel Oil Market", EnergySource::Petroleum),
+    ("Diesel Fuel Oil Self", EnergySource::Petroleum),
+    ("Hydro Market", EnergySource::Hydropower),
+    ("Hydro Self", EnergySource::Hydropower),
+    ("Natural Gas Market", EnergySource::NaturalGas),
+    ("Natural Gas Self", EnergySource::NaturalGas),
+    ("Nuclear Market", EnergySource::Nuclear),
+    ("Nuclear Self", EnergySource::Nuclear),
+    ("Solar Market", EnergySource::Solar),
+    ("Solar Self", EnergySource::Solar),
+    ("Waste Disposal Services Market", EnergySource::Biomass),
+    ("Waste Disposal Services Self", EnergySource::Biomass),
+    ("Wind Market", EnergySource::Wind),
+    ("Wind Self", EnergySource::Wind),
+    ("Waste Heat Market", EnergySource::Unknown),
+    ("Waste Heat Self", EnergySource::Unknown),
+    ("Other Market", EnergySource::Unknown),
+    ("Other Self", EnergySource::Unknown),
+    ("Energy Storage Market", EnergySource::WholesaleStorageLoad),
+    ("Energy Storage Self", EnergySource::WholesaleStorageLoad),
+];
+const LMP_TIME_HEADERS: [&str; 1] = ["GMTIntervalEnd"];
+const NODE_HEADERS: [&str; 1] = ["Settlement Location"];
+const PRICE_HEADERS: [&str; 1] = ["LMP"];
+const REFERENCE_NODE: &str = "SPPNORTH_HUB";
+const FUEL_MIX_LAYOUT: FuelMixLayout = FuelMixLayout {
+    time_headers: &FUEL_MIX_TIME_HEADERS,
+    fuel_headers: &[],
+    generation_headers: &[],
+    fuels: &FUELS,
+    filters: &[],
+    start_offset_seconds: -INTERVAL_SECONDS,
+};
+const LMP_LAYOUT: NodePriceLayout = NodePriceLayout {
+    time_headers: &LMP_TIME_HEADERS,
+    node_headers: &NODE_HEADERS,
+    price_headers: &PRICE_HEADERS,
+    filters: &[],
+    reference_node: REFERENCE_NODE,
+    start_offset_seconds: -INTERVAL_SECONDS,
+};
+
+pub struct SppDataSource {
+    data_directory: String,
+}
+
+impl SppDataSource {
+    pub fn new(data_directory: &str) -> Self {
+        Self {
+            data_directory: String::from(data_directory),
+        }
+    }
+
+    pub fn transform(files: &RegionFiles, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        let mut issues = vec![];
+        let fuel_mixes = read_fuel_columns(&files.fuel_mix, &FUEL_MIX_LAYOUT, &mut issues)?;
+        let prices = read_node_prices(&files.prices, &LMP_LAYOUT, &mut issues)?;
+
+        form_months(
+            GridRegion::Spp,
+            INTERVAL_SECONDS,
+            fuel_mixes,
+            prices,
+            issues,
+            time_range,
+        )
+    }
+}
+
+impl GridDataSource for SppDataSource {
+    fn region(&self) -> GridRegion {
+        GridRegion::Spp
+    }
+
+    fn months(&self, time_range: &TimeRange) -> Result<Vec<GridDataMonth>> {
+        SppDataSource::transform(
+            &RegionFiles::open(&self.data_directory, &self.region())?,
+            time_range,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        jobs::grid_data_source::{create_sheet, RegionFiles},
+        schema::{grid::GridRegion, time::TimeRange},
+    };
+
+    use super::SppDataSource;
+
+    #[test]
+    fn should_add_market_and_self_scheduled_generation() {
+        let files = RegionFiles {
+            fuel_mix: create_sheet(
+                "GMT MKT Interval,Coal Market,Coal Self,Wind Market,Wind Self,Load\n\
+                 2023-06-01T00:05:00Z,3600,1200,6000,0,30000\n\
+                 2023-06-01T00:10:00Z,3600,1200,6000,0,30000\n\
+                 2023-06-01T00:15:00Z,3600,1200,6000,0,30000\n",
+            ),
+            prices: create_sheet(
+                "Interval,GMTIntervalEnd,Settlement Location,Pnode,LMP,MLC,MCC,MEC\n\
+                 06/01/2023 00:05:00,06/01/2023 05:05:00,SPPNORTH_HUB,SPPNORTH_HUB,20,0,0,20\n\
+                 06/01/2023 00:10:00,06/01/2023 05:10:00,SPPNORTH_HUB,SPPNORTH_HUB,20,0,0,20\n\
+                 06/01/2023 00:05:00,06/01/2023 00:05:00,SPPNORTH_HUB,SPPNORTH_HUB,20,0,0,20\n\
+                 06/01/2023 00:10:00,06/01/2023 00:10:00,SPPNORTH_HUB,SPPNORTH_HUB,35,0,0,35\n\
+                 06/01/2023 00:15:00,06/01/2023 00:15:00,SPPNORTH_HUB,SPPNORTH_HUB,50,0,0,50\n",
+            ),
+        };
+
+        let months = SppDataSource::transform(&files, &TimeRange::all()).unwrap();
+
+        assert_eq!(months.len(), 1);
+        assert_eq!(months[0].generations.len(), 1);
+        let generation = &months[0].generations[0];
+        assert_eq!(generation.region, GridRegion::Spp);
+        assert_eq!(generation.time_generated.seconds, 1685578500);
+        assert_eq!(generation.sale_price_usd_per_mwh, 35.0);
+        assert_eq!(generation.portfolio.coal_mwh, 1200.0);
+        assert_eq!(generation.portfolio.wind_mwh, 1500.0);
+        assert_eq!(generation.portfolio.total_electricity_mwh, 2700.0);
+    }
+}
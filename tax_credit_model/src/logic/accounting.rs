@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use chrono::Datelike;
+
+use crate::schema::{
+    errors::Result,
+    policy::TaxCredit45VRuleset,
+    simulation::{
+        AccountingMode, EmissionEvent, HydrogenProductionEvent, TaxCredit45V, TaxCredit45VTier,
+        TaxCreditSummary,
+    },
+    time::Timestamp,
+};
+
+use super::simulation::calculate_tax_credit;
+
+const SECONDS_PER_HOUR: i64 = 3600;
+
+pub fn accounting_window(timestamp: &Timestamp, accounting_mode: AccountingMode) -> Result<i64> {
+    Ok(match accounting_mode {
+        AccountingMode::Interval => timestamp.seconds,
+        AccountingMode::Hourly => {
+            timestamp.seconds - timestamp.seconds.rem_euclid(SECONDS_PER_HOUR)
+        }
+        AccountingMode::Annual => timestamp.to_utc_date_time()?.year() as i64,
+    })
+}
+
+// Every interval earns the tier of the window it falls in, so a dirty interval
+// can still be credited when the window as a whole stays under a threshold. The
+// ruleset narrows annual windows to the hour once hourly matching starts
+pub fn assign_tax_credits(
+    emissions: &[EmissionEvent],
+    hydrogen_productions: &[HydrogenProductionEvent],
+    accounting_mode: AccountingMode,
+    ruleset: &TaxCredit45VRuleset,
+) -> Result<Vec<TaxCredit45V>> {
+    let mut windows: HashMap<i64, (EmissionEvent, HydrogenProductionEvent)> = HashMap::new();
+    let interval_windows = hydrogen_productions
+        .iter()
+        .map(|production| {
+            let timestamp = &production.production_timestamp;
+            let year = timestamp.to_utc_date_time()?.year();
+
+            accounting_window(timestamp, ruleset.accounting_mode(accounting_mode, year))
+        })
+        .collect::<Result<Vec<i64>>>()?;
+
+    for ((emission, production), window) in emissions
+        .iter()
+        .zip(hydrogen_productions)
+        .zip(&interval_windows)
+    {
+        let (window_emission, window_production) = windows.entry(*window).or_insert_with(|| {
+            (
+                EmissionEvent::default(),
+                HydrogenProductionEvent {
+                    production_timestamp: production.production_timestamp,
+                    ..Default::default()
+                },
+            )
+        });
+        window_emission.amount_emitted_kg += emission.amount_emitted_kg;
+        window_production.kg_hydrogen += production.kg_hydrogen;
+    }
+
+    let window_tiers = windows
+        .into_iter()
+        .map(|(window, (emission, production))| {
+            Ok((
+                window,
+                calculate_tax_credit(&emission, &production, ruleset)?.tier,
+            ))
+        })
+        .collect::<Result<HashMap<i64, TaxCredit45VTier>>>()?;
+
+    hydrogen_productions
+        .iter()
+        .zip(&interval_windows)
+        .map(|(production, window)| {
+            let tier = window_tiers.get(window).copied().unwrap_or_default();
+            let year = production.production_timestamp.to_utc_date_time()?.year();
+
+            Ok(TaxCredit45V {
+                tier,
+                total_usd: ruleset.credit_usd_per_kg(tier, year) * production.kg_hydrogen,
+            })
+        })
+        .collect()
+}
+
+pub fn summarize_tax_credits(
+    summary: &mut TaxCreditSummary,
+    tax_credits: &[TaxCredit45V],
+    interval_hours: f64,
+) {
+    for tax_credit in tax_credits {
+        match tax_credit.tier {
+            TaxCredit45VTier::Max => summary.credit_hours_full += interval_hours,
+            TaxCredit45VTier::Tier1 => summary.credit_hours_33 += interval_hours,
+            TaxCredit45VTier::Tier2 => summary.credit_hours_25 += interval_hours,
+            TaxCredit45VTier::Tier3 => summary.credit_hours_20 += interval_hours,
+            TaxCredit45VTier::None => summary.credit_hours_none += interval_hours,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        policy::TaxCredit45VRuleset,
+        simulation::{AccountingMode, EmissionEvent, HydrogenProductionEvent, TaxCredit45VTier},
+        time::Timestamp,
+    };
+
+    use super::assign_tax_credits;
+
+    fn create_events(
+        intervals: Vec<(i64, f64, f64)>,
+    ) -> (Vec<EmissionEvent>, Vec<HydrogenProductionEvent>) {
+        intervals
+            .into_iter()
+            .map(|(seconds, amount_emitted_kg, kg_hydrogen)| {
+                let mut emission = EmissionEvent::default();
+                emission.emission_timestamp = Timestamp::new(seconds, 0);
+                emission.amount_emitted_kg = amount_emitted_kg;
+                let mut production = HydrogenProductionEvent::default();
+                production.production_timestamp = Timestamp::new(seconds, 0);
+                production.kg_hydrogen = kg_hydrogen;
+
+                (emission, production)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn should_tier_each_interval_on_its_own() {
+        let (emissions, productions) = create_events(vec![(0, 0.0, 10.0), (900, 50.0, 10.0)]);
+
+        let tax_credits = assign_tax_credits(
+            &emissions,
+            &productions,
+            AccountingMode::Interval,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
+
+        assert_eq!(tax_credits[0].tier, TaxCredit45VTier::Max);
+        assert_eq!(tax_credits[1].tier, TaxCredit45VTier::None);
+    }
+
+    #[test]
+    fn should_tier_intervals_by_hour() {
+        let (emissions, productions) =
+            create_events(vec![(0, 0.0, 10.0), (900, 10.0, 10.0), (3600, 50.0, 10.0)]);
+
+        let tax_credits = assign_tax_credits(
+            &emissions,
+            &productions,
+            AccountingMode::Hourly,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
+
+        assert_eq!(tax_credits[0].tier, TaxCredit45VTier::Tier1);
+        assert_eq!(tax_credits[1].tier, TaxCredit45VTier::Tier1);
+        assert_eq!(tax_credits[2].tier, TaxCredit45VTier::None);
+    }
+
+    #[test]
+    fn should_tier_intervals_by_year() {
+        let (emissions, productions) = create_events(vec![(0, 0.0, 90.0), (3600, 30.0, 10.0)]);
+
+        let tax_credits = assign_tax_credits(
+            &emissions,
+            &productions,
+            AccountingMode::Annual,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
+
+        assert_eq!(tax_credits[0].tier, TaxCredit45VTier::Max);
+        assert_eq!(tax_credits[1].tier, TaxCredit45VTier::Max);
+        assert_eq!(tax_credits[1].total_usd, 30.0);
+    }
+
+    #[test]
+    fn should_match_hourly_once_ruleset_requires_it() {
+        // The first two hours of 2028
+        let (emissions, productions) =
+            create_events(vec![(1830297600, 0.0, 90.0), (1830301200, 10.0, 2.0)]);
+
+        let final_credits = assign_tax_credits(
+            &emissions,
+            &productions,
+            AccountingMode::Annual,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
+        let proposed_credits = assign_tax_credits(
+            &emissions,
+            &productions,
+            AccountingMode::Annual,
+            &TaxCredit45VRuleset::proposed_rules(),
+        )
+        .unwrap();
+
+        assert_eq!(final_credits[1].tier, TaxCredit45VTier::Max);
+        assert_eq!(proposed_credits[0].tier, TaxCredit45VTier::Max);
+        assert_eq!(proposed_credits[1].tier, TaxCredit45VTier::None);
+    }
+}
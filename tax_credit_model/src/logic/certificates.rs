@@ -0,0 +1,150 @@
+use crate::schema::{
+    certificate::{CertificateLedger, CertificateRetirement},
+    grid::GridRegion,
+    simulation::{EnergySource, EnergySourcePortfolio, SimulationId},
+    time::Timestamp,
+};
+
+const VINTAGE_SECONDS: i64 = 3600;
+const CLEAN_FUELS: [EnergySource; 5] = [
+    EnergySource::Solar,
+    EnergySource::Wind,
+    EnergySource::Hydropower,
+    EnergySource::Geothermal,
+    EnergySource::Nuclear,
+];
+
+// Retires clean certificates from the region whose vintage hour contains the
+// interval, oldest first, until the energy consumed is covered. Returns the MWh
+// retired
+pub fn retire_certificates(
+    ledger: &mut CertificateLedger,
+    simulation_id: SimulationId,
+    timestamp: &Timestamp,
+    region: &GridRegion,
+    consumed_mwh: f64,
+) -> f64 {
+    let mut retired_mwh = 0.0;
+
+    for entry in ledger.entries.iter_mut() {
+        if retired_mwh >= consumed_mwh {
+            break;
+        }
+
+        let certificate = &entry.certificate;
+        let is_matching = &certificate.region == region
+            && CLEAN_FUELS.contains(&certificate.fuel_type)
+            && certificate.vintage.seconds <= timestamp.seconds
+            && timestamp.seconds < certificate.vintage.seconds + VINTAGE_SECONDS;
+
+        if !is_matching || entry.remaining_mwh <= 0.0 {
+            continue;
+        }
+
+        let retirement_mwh = entry.remaining_mwh.min(consumed_mwh - retired_mwh);
+        entry.remaining_mwh -= retirement_mwh;
+        retired_mwh += retirement_mwh;
+        ledger.retirements.push(CertificateRetirement {
+            simulation_id,
+            certificate_id: certificate.certificate_id.clone(),
+            timestamp: *timestamp,
+            retired_mwh: retirement_mwh,
+        });
+    }
+
+    retired_mwh
+}
+
+// Retired certificates claim part of the energy consumed as zero-emission, so
+// only the rest of the portfolio is left to emit
+pub fn uncertified_portfolio(
+    portfolio: &EnergySourcePortfolio,
+    retired_mwh: f64,
+) -> EnergySourcePortfolio {
+    if portfolio.total_electricity_mwh <= 0.0 {
+        return *portfolio;
+    }
+
+    EnergySourcePortfolio::scale(
+        portfolio,
+        (1.0 - retired_mwh / portfolio.total_electricity_mwh).max(0.0),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        certificate::{CertificateLedger, EnergyAttributeCertificate},
+        grid::GridRegion,
+        simulation::{EnergySource, EnergySourcePortfolio},
+        time::Timestamp,
+    };
+
+    use super::{retire_certificates, uncertified_portfolio};
+
+    fn create_certificate(
+        certificate_id: &str,
+        fuel_type: EnergySource,
+        vintage: i64,
+        mwh: f64,
+    ) -> EnergyAttributeCertificate {
+        EnergyAttributeCertificate {
+            certificate_id: String::from(certificate_id),
+            fuel_type,
+            vintage: Timestamp::new(vintage, 0),
+            mwh,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_retire_clean_certificates_from_the_same_hour() {
+        let mut ledger = CertificateLedger::new(vec![
+            create_certificate("wind", EnergySource::Wind, 3600, 3.0),
+            create_certificate("gas", EnergySource::NaturalGas, 3600, 10.0),
+            create_certificate("solar", EnergySource::Solar, 0, 10.0),
+        ]);
+
+        let retired = [3600, 4500, 5400]
+            .iter()
+            .map(|seconds| {
+                retire_certificates(
+                    &mut ledger,
+                    0,
+                    &Timestamp::new(*seconds, 0),
+                    &GridRegion::Ercot,
+                    2.0,
+                )
+            })
+            .collect::<Vec<f64>>();
+
+        assert_eq!(retired, vec![2.0, 1.0, 0.0]);
+        assert_eq!(ledger.retired_mwh(), 3.0);
+        assert_eq!(
+            ledger
+                .entries
+                .iter()
+                .map(|entry| (
+                    entry.certificate.certificate_id.as_str(),
+                    entry.remaining_mwh
+                ))
+                .collect::<Vec<(&str, f64)>>(),
+            vec![("solar", 10.0), ("gas", 10.0), ("wind", 0.0)]
+        );
+    }
+
+    #[test]
+    fn should_leave_uncertified_energy_to_emit() {
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.add_energy(&EnergySource::NaturalGas, 3.0);
+        portfolio.add_energy(&EnergySource::Wind, 1.0);
+
+        let uncertified = uncertified_portfolio(&portfolio, 3.0);
+
+        assert_eq!(uncertified.total_electricity_mwh, 1.0);
+        assert_eq!(uncertified.natural_gas_mwh, 0.75);
+        assert_eq!(uncertified.wind_mwh, 0.25);
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::schema::{
+    errors::{Error, Result},
+    policy::CREDIT_PERIOD_YEARS,
+    simulation::{
+        CreditWindowConfiguration, CreditWindowYear, CreditYearSummary, GenerationMetric,
+        MissingYearSource, PowerGrid,
+    },
+    time::{TimeRange, Timestamp},
+};
+
+use super::simulation::SimulationState;
+
+const PLACED_IN_SERVICE_FORMAT: &str = "%Y-%m-%d";
+const SECONDS_PER_HOUR: i64 = 3600;
+
+// Splits the credit period into calendar years, simulating each year with its
+// own grid data when the grid covers it and a historical year otherwise
+pub fn plan_credit_window(
+    power_grid: &PowerGrid,
+    configuration: &CreditWindowConfiguration,
+) -> Result<Vec<CreditWindowYear>> {
+    let placed_in_service =
+        NaiveDate::parse_from_str(&configuration.placed_in_service, PLACED_IN_SERVICE_FORMAT)
+            .map_err(|err| Error::invalid_argument(&err.to_string()))?;
+    let start = Utc.from_utc_datetime(&placed_in_service.and_time(NaiveTime::MIN));
+    let end = start
+        .checked_add_months(Months::new(12 * CREDIT_PERIOD_YEARS))
+        .ok_or_else(|| Error::invalid_argument("Credit window ends out of range"))?;
+    let mut window_years = vec![];
+
+    for year in start.year()..=end.year() {
+        let year_start = std::cmp::max(start, start_of_year(year)?);
+        let year_end = std::cmp::min(end, start_of_year(year + 1)?);
+
+        if year_start >= year_end {
+            continue;
+        }
+
+        let (source_year, price_scale) = if covers(power_grid, &year_start, &year_end) {
+            (year, 1.0)
+        } else {
+            let source_year = historical_year(power_grid, configuration)?;
+            let price_scale = match configuration.missing_year_source {
+                MissingYearSource::HistoricalYear => 1.0,
+                MissingYearSource::ScaledSyntheticYear => {
+                    (1.0 + configuration.price_escalation_rate / 100.0).powi(year - source_year)
+                }
+            };
+
+            (source_year, price_scale)
+        };
+
+        window_years.push(CreditWindowYear {
+            year,
+            start: Timestamp::from(year_start),
+            end: Timestamp::from(year_end),
+            source_year,
+            price_scale,
+        });
+    }
+
+    Ok(window_years)
+}
+
+// Moves the source year's generations onto the simulated year with their
+// prices scaled, leaving out a source leap day and repeating the 28th of
+// February when only the simulated year has one. Generations are stamped at the
+// end of their interval, so the interval's start decides the day it falls on
+pub fn project_power_grid(
+    power_grid: &PowerGrid,
+    window_year: &CreditWindowYear,
+) -> Result<PowerGrid> {
+    let fills_leap_day = is_leap_year(window_year.year) && !is_leap_year(window_year.source_year);
+    let mut projected_grid = PowerGrid::new(power_grid.region.clone());
+
+    for power_plant in &power_grid.power_plants {
+        let mut projected_plant = power_plant.with_generations(vec![]);
+
+        for generation in power_plant.generations() {
+            let interval_start =
+                generation.time_generated.to_utc_date_time()? - Duration::seconds(1);
+
+            if interval_start.year() != window_year.source_year {
+                continue;
+            }
+
+            let Some(shifted) = interval_start.with_year(window_year.year) else {
+                continue;
+            };
+            let shifted = shifted + Duration::seconds(1);
+            let mut shifted_times = vec![shifted];
+
+            if fills_leap_day && interval_start.month() == 2 && interval_start.day() == 28 {
+                shifted_times.push(shifted + Duration::days(1));
+            }
+
+            for shifted_time in shifted_times {
+                projected_plant.add_generation(GenerationMetric {
+                    time_generated: Timestamp::from(shifted_time),
+                    sale_price_usd_per_mwh: generation.sale_price_usd_per_mwh
+                        * window_year.price_scale,
+                    real_time_price_usd_per_mwh: generation
+                        .real_time_price_usd_per_mwh
+                        .map(|price| price * window_year.price_scale),
+                    ..generation.clone()
+                });
+            }
+        }
+
+        projected_grid.add_power_plant(projected_plant);
+    }
+
+    Ok(projected_grid)
+}
+
+pub fn summarize_credit_years(state: &SimulationState) -> Result<Vec<CreditYearSummary>> {
+    let mut summaries: BTreeMap<i32, CreditYearSummary> = BTreeMap::new();
+
+    for (production, tax_credit) in state.hydrogen_productions.iter().zip(&state.tax_credit) {
+        let year = production.production_timestamp.to_utc_date_time()?.year();
+        let summary = summaries.entry(year).or_insert_with(|| CreditYearSummary {
+            year,
+            data_source: state
+                .credit_window_years
+                .iter()
+                .find(|window_year| window_year.year == year)
+                .map_or(String::from("Recorded"), |window_year| {
+                    window_year.to_string()
+                }),
+            max_credit_usd_per_kg: state.tax_credit_ruleset.max_credit_usd_per_kg(year),
+            ..Default::default()
+        });
+        summary.kg_hydrogen += production.kg_hydrogen;
+        summary.tax_credit_usd += tax_credit.total_usd;
+
+        if production.kg_hydrogen > 0.0 {
+            summary.hours_operated += state.simulation_step.hours();
+        }
+    }
+
+    for transaction in &state.transactions {
+        let year = transaction.timestamp.to_utc_date_time()?.year();
+
+        if let Some(summary) = summaries.get_mut(&year) {
+            summary.energy_usd += transaction.price_usd;
+        }
+    }
+
+    Ok(summaries.into_values().collect())
+}
+
+fn historical_year(
+    power_grid: &PowerGrid,
+    configuration: &CreditWindowConfiguration,
+) -> Result<i32> {
+    if let Some(year) = configuration.historical_year {
+        if !covers(power_grid, &start_of_year(year)?, &start_of_year(year + 1)?) {
+            return Err(Error::invalid_argument(&format!(
+                "{} is not a complete year of grid data",
+                year
+            )));
+        }
+
+        return Ok(year);
+    }
+
+    let mut years = BTreeSet::new();
+
+    for generation in power_grid
+        .power_plants
+        .iter()
+        .flat_map(|power_plant| power_plant.generations())
+    {
+        years.insert((generation.time_generated.to_utc_date_time()? - Duration::seconds(1)).year());
+    }
+
+    for year in years.into_iter().rev() {
+        if covers(power_grid, &start_of_year(year)?, &start_of_year(year + 1)?) {
+            return Ok(year);
+        }
+    }
+
+    Err(Error::not_found("No complete year of grid data to replay"))
+}
+
+// Grid data is hourly or finer, so the grid covers a range when every plant
+// has a record ending in the first hour of the range and one ending as the
+// last hour does
+fn covers(power_grid: &PowerGrid, start: &DateTime<Utc>, end: &DateTime<Utc>) -> bool {
+    let first_hour = floor_hour(start.timestamp());
+    let last_hour = floor_hour(end.timestamp() - 1);
+
+    !power_grid.power_plants.is_empty()
+        && power_grid.power_plants.iter().all(|power_plant| {
+            let generations = power_plant.generations();
+
+            generations.first().is_some_and(|generation| {
+                generation.time_generated.seconds <= first_hour + SECONDS_PER_HOUR
+            }) && generations.last().is_some_and(|generation| {
+                generation.time_generated.seconds >= last_hour + SECONDS_PER_HOUR
+            })
+        })
+}
+
+fn floor_hour(seconds: i64) -> i64 {
+    seconds - seconds.rem_euclid(SECONDS_PER_HOUR)
+}
+
+// The grid data a window year is projected from
+pub fn source_time_range(window_year: &CreditWindowYear) -> Result<TimeRange> {
+    Ok(TimeRange {
+        start: Timestamp::from(start_of_year(window_year.source_year)?),
+        end: Timestamp::from(start_of_year(window_year.source_year + 1)?),
+    })
+}
+
+fn start_of_year(year: i32) -> Result<DateTime<Utc>> {
+    Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+        .single()
+        .ok_or_else(|| Error::invalid_argument("Invalid year"))
+}
+
+fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 2, 29).is_some()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        simulation::{
+            CreditWindowConfiguration, CreditWindowYear, EnergySourcePortfolio, GenerationMetric,
+            MissingYearSource, PowerGrid, PowerPlant,
+        },
+        time::Timestamp,
+    };
+
+    use super::{plan_credit_window, project_power_grid};
+
+    fn create_power_grid(year: i32, price: f64) -> PowerGrid {
+        let mut power_plant = PowerPlant::default();
+
+        for (year, month, day, hour) in [(year, 1, 1, 1), (year, 2, 28, 12), (year + 1, 1, 1, 0)] {
+            power_plant.add_generation(GenerationMetric::new(
+                0,
+                &Timestamp::from(Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()),
+                price,
+                EnergySourcePortfolio::default(),
+            ));
+        }
+
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(power_plant);
+
+        power_grid
+    }
+
+    fn create_configuration(missing_year_source: MissingYearSource) -> CreditWindowConfiguration {
+        CreditWindowConfiguration {
+            enabled: true,
+            placed_in_service: String::from("2023-07-01"),
+            missing_year_source,
+            historical_year: None,
+            price_escalation_rate: 10.0,
+        }
+    }
+
+    #[test]
+    fn should_plan_ten_years_from_placed_in_service() {
+        let window_years = plan_credit_window(
+            &create_power_grid(2023, 10.0),
+            &create_configuration(MissingYearSource::HistoricalYear),
+        )
+        .unwrap();
+
+        assert_eq!(window_years.len(), 11);
+        assert_eq!(
+            window_years[0].start,
+            Timestamp::from(Utc.with_ymd_and_hms(2023, 7, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(window_years[0].source_year, 2023);
+        assert_eq!(
+            window_years[10].end,
+            Timestamp::from(Utc.with_ymd_and_hms(2033, 7, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(window_years[10].source_year, 2023);
+        assert_eq!(window_years[10].price_scale, 1.0);
+    }
+
+    #[test]
+    fn should_escalate_prices_for_synthetic_years() {
+        let window_years = plan_credit_window(
+            &create_power_grid(2023, 10.0),
+            &create_configuration(MissingYearSource::ScaledSyntheticYear),
+        )
+        .unwrap();
+
+        assert_eq!(window_years[0].price_scale, 1.0);
+        assert_eq!(window_years[2].price_scale, 1.1 * 1.1);
+    }
+
+    #[test]
+    fn should_repeat_february_28_on_leap_day() {
+        let window_year = CreditWindowYear {
+            year: 2024,
+            source_year: 2023,
+            price_scale: 2.0,
+            ..Default::default()
+        };
+
+        let projected_grid =
+            project_power_grid(&create_power_grid(2023, 10.0), &window_year).unwrap();
+
+        assert_eq!(
+            projected_grid.power_plants[0]
+                .generations()
+                .iter()
+                .map(|generation| generation.time_generated)
+                .collect::<Vec<Timestamp>>(),
+            vec![
+                Timestamp::from(Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap()),
+                Timestamp::from(Utc.with_ymd_and_hms(2024, 2, 28, 12, 0, 0).unwrap()),
+                Timestamp::from(Utc.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap()),
+                Timestamp::from(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+            ]
+        );
+        assert_eq!(
+            projected_grid.power_plants[0].generations()[0].sale_price_usd_per_mwh,
+            20.0
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{
+    electrolyzer::Electrolyzer,
+    simulation::{SimulationId, StackEfficiencyEvent, StackReplacementEvent},
+    time::Timestamp,
+};
+
+pub const HOURS_PER_YEAR: f64 = 8760.0;
+
+// Efficiency is tracked as a fraction of the stack's beginning of life
+// conversion rate, so a fresh stack is always 1.0
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct StackState {
+    pub operating_hours: f64,
+    pub efficiency: f64,
+}
+
+impl Default for StackState {
+    fn default() -> Self {
+        Self {
+            operating_hours: 0.0,
+            efficiency: 1.0,
+        }
+    }
+}
+
+impl StackState {
+    pub fn efficiency_after(electrolyzer: &Electrolyzer, operating_hours: f64) -> f64 {
+        let lost_efficiency =
+            (electrolyzer.degradation_rate / 100.0) * (operating_hours / HOURS_PER_YEAR);
+
+        f64::max(1.0 - lost_efficiency, 0.0)
+    }
+
+    pub fn needs_replacement(&self, electrolyzer: &Electrolyzer) -> bool {
+        self.efficiency * 100.0 < electrolyzer.replacement_threshold
+    }
+
+    // Operating hours a fresh stack lasts before it falls to the replacement
+    // threshold, or None when the stack never wears out
+    pub fn hours_until_replacement(electrolyzer: &Electrolyzer) -> Option<f64> {
+        if electrolyzer.degradation_rate <= 0.0 {
+            return None;
+        }
+
+        let allowed_loss = 1.0 - electrolyzer.replacement_threshold / 100.0;
+
+        Some(f64::max(allowed_loss, 0.0) / (electrolyzer.degradation_rate / 100.0) * HOURS_PER_YEAR)
+    }
+}
+
+pub fn create_stack_efficiency_event(
+    simulation_id: SimulationId,
+    timestamp: &Timestamp,
+    electrolyzer: &Electrolyzer,
+    stack: &StackState,
+) -> StackEfficiencyEvent {
+    StackEfficiencyEvent {
+        simulation_id,
+        electrolyzer_id: electrolyzer.id,
+        timestamp: *timestamp,
+        efficiency: stack.efficiency,
+    }
+}
+
+// Wears the stack down by the hours it operated during the interval and swaps
+// it for a fresh one once it falls below the replacement threshold
+pub fn operate_stack(
+    simulation_id: SimulationId,
+    timestamp: &Timestamp,
+    electrolyzer: &Electrolyzer,
+    stack: &mut StackState,
+    hours_operated: f64,
+) -> Option<StackReplacementEvent> {
+    stack.operating_hours += hours_operated;
+    stack.efficiency = StackState::efficiency_after(electrolyzer, stack.operating_hours);
+
+    if !stack.needs_replacement(electrolyzer) {
+        return None;
+    }
+
+    let replacement = StackReplacementEvent {
+        simulation_id,
+        electrolyzer_id: electrolyzer.id,
+        replacement_timestamp: *timestamp,
+        operating_hours: stack.operating_hours,
+        efficiency_at_replacement: stack.efficiency,
+        cost_usd: electrolyzer.replacement_cost,
+    };
+    *stack = StackState::default();
+
+    Some(replacement)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        electrolyzer::Electrolyzer, simulation::StackReplacementEvent, time::Timestamp,
+    };
+
+    use super::{operate_stack, StackState};
+
+    #[test]
+    fn should_not_degrade_without_degradation_rate() {
+        let electrolyzer = Electrolyzer::default();
+        let mut stack = StackState::default();
+
+        let replacement = operate_stack(0, &Timestamp::default(), &electrolyzer, &mut stack, 0.25);
+
+        assert_eq!(replacement, None);
+        assert_eq!(stack.efficiency, 1.0);
+        assert_eq!(stack.operating_hours, 0.25);
+    }
+
+    #[test]
+    fn should_degrade_over_operating_hours() {
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.degradation_rate = 10.0;
+        let mut stack = StackState::default();
+
+        operate_stack(0, &Timestamp::default(), &electrolyzer, &mut stack, 4380.0);
+
+        assert_eq!(stack.efficiency, 0.95);
+    }
+
+    #[test]
+    fn should_replace_stack_below_threshold() {
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.degradation_rate = 10.0;
+        electrolyzer.replacement_threshold = 90.0;
+        electrolyzer.replacement_cost = 1000.0;
+        let mut stack = StackState::default();
+        let expected_replacement = StackReplacementEvent {
+            simulation_id: 0,
+            electrolyzer_id: 0,
+            replacement_timestamp: Timestamp::default(),
+            operating_hours: 8760.0 * 1.5,
+            efficiency_at_replacement: 0.85,
+            cost_usd: 1000.0,
+        };
+
+        let replacement = operate_stack(
+            0,
+            &Timestamp::default(),
+            &electrolyzer,
+            &mut stack,
+            8760.0 * 1.5,
+        );
+
+        assert_eq!(replacement, Some(expected_replacement));
+        assert_eq!(stack, StackState::default());
+    }
+}
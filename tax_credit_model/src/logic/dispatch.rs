@@ -0,0 +1,1156 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::schema::{
+    electrolyzer::Electrolyzer,
+    emissions::EmissionFactorSet,
+    errors::{Error, Result},
+    policy::TaxCredit45VRuleset,
+    simulation::{
+        DispatchDecision, DispatchObjective, DispatchReason, DispatchStrategyConfiguration,
+        DispatchStrategyKind, EnergySourcePortfolio, EnergyTransaction, GenerationMetric,
+        PowerGrid, PowerPlant, PowerPlantId, SimulationId, SimulationStep,
+    },
+    time::{CentralPrevailingTime, Timestamp},
+};
+
+use super::degradation::StackState;
+
+const SECONDS_PER_HOUR: i64 = 3600;
+
+// Everything a strategy may look at when deciding what to buy for a single
+// simulation interval
+pub struct DispatchInterval<'a> {
+    pub simulation_id: SimulationId,
+    pub timestamp: Timestamp,
+    pub interval_hours: f64,
+    pub electrolyzer: &'a Electrolyzer,
+    pub stack: &'a StackState,
+    pub emission_factors: &'a EmissionFactorSet,
+    pub ruleset: &'a TaxCredit45VRuleset,
+    pub power_grid: &'a PowerGrid,
+    pub generations: Vec<GenerationMetric>,
+}
+
+pub trait DispatchStrategy {
+    fn dispatch(
+        &self,
+        interval: &DispatchInterval,
+    ) -> Result<(Vec<EnergyTransaction>, DispatchDecision)>;
+}
+
+pub struct AlwaysOn {
+    pub objective: DispatchObjective,
+}
+
+pub struct PriceCeiling {
+    pub objective: DispatchObjective,
+    pub ceiling_usd_per_mwh: f64,
+}
+
+pub struct CarbonIntensityCeiling {
+    pub objective: DispatchObjective,
+    pub ceiling_co2_per_h2: f64,
+}
+
+pub struct CheapestHours {
+    pub objective: DispatchObjective,
+    pub cheapest_hours: HashSet<i64>,
+}
+
+impl DispatchStrategy for AlwaysOn {
+    fn dispatch(
+        &self,
+        interval: &DispatchInterval,
+    ) -> Result<(Vec<EnergyTransaction>, DispatchDecision)> {
+        dispatch_merit_order(interval, self.objective, |_| None)
+    }
+}
+
+impl DispatchStrategy for PriceCeiling {
+    fn dispatch(
+        &self,
+        interval: &DispatchInterval,
+    ) -> Result<(Vec<EnergyTransaction>, DispatchDecision)> {
+        dispatch_merit_order(interval, self.objective, |offer| {
+            (offer.cost_usd_per_mwh > self.ceiling_usd_per_mwh)
+                .then_some(DispatchReason::AbovePriceCeiling)
+        })
+    }
+}
+
+impl DispatchStrategy for CarbonIntensityCeiling {
+    fn dispatch(
+        &self,
+        interval: &DispatchInterval,
+    ) -> Result<(Vec<EnergyTransaction>, DispatchDecision)> {
+        dispatch_merit_order(interval, self.objective, |offer| {
+            (emissions_intensity(interval, offer.generation) > self.ceiling_co2_per_h2)
+                .then_some(DispatchReason::AboveCarbonIntensityCeiling)
+        })
+    }
+}
+
+impl DispatchStrategy for CheapestHours {
+    fn dispatch(
+        &self,
+        interval: &DispatchInterval,
+    ) -> Result<(Vec<EnergyTransaction>, DispatchDecision)> {
+        let hour_start = interval.timestamp.seconds - interval.timestamp.seconds % SECONDS_PER_HOUR;
+        let outside_cheapest_hours = !self.cheapest_hours.contains(&hour_start);
+
+        dispatch_merit_order(interval, self.objective, |_| {
+            outside_cheapest_hours.then_some(DispatchReason::OutsideCheapestHours)
+        })
+    }
+}
+
+impl CheapestHours {
+    // Ranks each day's hours by their average price across every plant so the
+    // strategy can tell whether an interval falls in one of the cheapest hours.
+    // Days run midnight to midnight in Central Prevailing Time like ERCOT's
+    pub fn new(
+        objective: DispatchObjective,
+        hours_per_day: u32,
+        power_grid: &PowerGrid,
+    ) -> Result<Self> {
+        if hours_per_day > 24 {
+            return Err(Error::invalid_argument(
+                "Cheapest hours per day must be between 0 and 24",
+            ));
+        }
+
+        let mut prices_by_hour: HashMap<i64, (f64, f64)> = HashMap::new();
+
+        for generation in power_grid
+            .power_plants
+            .iter()
+            .flat_map(|power_plant| power_plant.generations().iter())
+        {
+            // Generations are stamped at the end of their interval, so one
+            // stamped on the hour belongs to the hour before it
+            let seconds = generation.time_generated.seconds - 1;
+            let (total_price, count) = prices_by_hour
+                .entry(seconds - seconds % SECONDS_PER_HOUR)
+                .or_default();
+            *total_price += generation.sale_price_usd_per_mwh;
+            *count += 1.0;
+        }
+
+        let mut hours_by_day: HashMap<NaiveDate, Vec<(i64, f64)>> = HashMap::new();
+
+        for (hour_start, (total_price, count)) in prices_by_hour {
+            let date = CentralPrevailingTime::from_utc(
+                &Timestamp::new(hour_start, 0).to_utc_date_time()?,
+            )?;
+            hours_by_day
+                .entry(date.date())
+                .or_default()
+                .push((hour_start, total_price / count));
+        }
+
+        let cheapest_hours = hours_by_day
+            .into_values()
+            .flat_map(|mut hours| {
+                hours.sort_by(|a, b| a.1.total_cmp(&b.1));
+                hours
+                    .into_iter()
+                    .take(hours_per_day as usize)
+                    .map(|(hour_start, _)| hour_start)
+            })
+            .collect();
+
+        Ok(Self {
+            objective,
+            cheapest_hours,
+        })
+    }
+}
+
+pub fn create_dispatch_strategy(
+    configuration: &DispatchStrategyConfiguration,
+    power_grid: &PowerGrid,
+) -> Result<Box<dyn DispatchStrategy>> {
+    let objective = configuration.objective;
+
+    Ok(match configuration.kind {
+        DispatchStrategyKind::AlwaysOn => Box::new(AlwaysOn { objective }),
+        DispatchStrategyKind::PriceCeiling => Box::new(PriceCeiling {
+            objective,
+            ceiling_usd_per_mwh: configuration.price_ceiling_usd_per_mwh.ok_or_else(|| {
+                Error::invalid_argument("Price ceiling strategy requires a ceiling")
+            })?,
+        }),
+        DispatchStrategyKind::CarbonIntensityCeiling => Box::new(CarbonIntensityCeiling {
+            objective,
+            ceiling_co2_per_h2: configuration.carbon_intensity_ceiling.ok_or_else(|| {
+                Error::invalid_argument("Carbon intensity ceiling strategy requires a ceiling")
+            })?,
+        }),
+        DispatchStrategyKind::CheapestHours => Box::new(CheapestHours::new(
+            objective,
+            configuration.hours_per_day.ok_or_else(|| {
+                Error::invalid_argument("Cheapest hours strategy requires hours per day")
+            })?,
+            power_grid,
+        )?),
+    })
+}
+
+// A plant's offer is ranked by its price, or under the net cost objective by
+// its price less the 45V credit the hydrogen made from it would earn
+pub struct Offer<'a> {
+    pub generation: &'a GenerationMetric,
+    pub cost_usd_per_mwh: f64,
+}
+
+// Fills the electrolyzer's capacity for the interval from the cheapest offers
+// first, skipping any offer the strategy rejects, and records why the interval
+// did or did not run at full capacity
+pub fn dispatch_merit_order<F>(
+    interval: &DispatchInterval,
+    objective: DispatchObjective,
+    reject: F,
+) -> Result<(Vec<EnergyTransaction>, DispatchDecision)>
+where
+    F: Fn(&Offer) -> Option<DispatchReason>,
+{
+    let electrolyzer = interval.electrolyzer;
+    let demand_mwh = electrolyzer.capacity_mw * interval.interval_hours;
+    let mut decision = DispatchDecision {
+        simulation_id: interval.simulation_id,
+        electrolyzer_id: electrolyzer.id,
+        timestamp: interval.timestamp,
+        demand_mwh,
+        purchased_mwh: 0.0,
+        reason: DispatchReason::Dispatched,
+        gap_filled: false,
+    };
+
+    if demand_mwh <= 0.0 {
+        decision.reason = DispatchReason::NoCapacity;
+        return Ok((vec![], decision));
+    }
+
+    let mut offers = interval
+        .generations
+        .iter()
+        .map(|generation| Offer {
+            cost_usd_per_mwh: offer_cost(interval, generation, objective),
+            generation,
+        })
+        .collect::<Vec<Offer>>();
+    offers.sort_by(|a, b| a.cost_usd_per_mwh.total_cmp(&b.cost_usd_per_mwh));
+
+    let mut transactions = vec![];
+    let mut remaining_mwh = demand_mwh;
+    let mut rejection = None;
+
+    for offer in &offers {
+        if remaining_mwh <= 0.0 {
+            break;
+        }
+
+        if let Some(reason) = reject(offer) {
+            rejection = Some(reason);
+            continue;
+        }
+
+        let amount_mwh = f64::min(remaining_mwh, offered_mwh(interval, offer.generation));
+
+        if amount_mwh <= 0.0 {
+            continue;
+        }
+
+        transactions.push(purchase(
+            interval.simulation_id,
+            electrolyzer,
+            offer.generation,
+            amount_mwh,
+            &interval.timestamp,
+        )?);
+        remaining_mwh -= amount_mwh;
+    }
+
+    decision.purchased_mwh = demand_mwh - remaining_mwh;
+    decision.reason = match (decision.purchased_mwh > 0.0, remaining_mwh > 0.0, rejection) {
+        (false, _, Some(reason)) => reason,
+        (true, true, Some(_)) => DispatchReason::PartiallyCurtailed,
+        (_, true, None) => DispatchReason::SupplyLimited,
+        _ => DispatchReason::Dispatched,
+    };
+
+    if electrolyzer.is_variable_production()
+        && decision.purchased_mwh > 0.0
+        && decision.purchased_mwh / demand_mwh
+            < electrolyzer.variable_production.minimum_stable_load
+    {
+        decision.purchased_mwh = 0.0;
+        decision.reason = DispatchReason::BelowMinimumStableLoad;
+        transactions.clear();
+    }
+
+    if offers
+        .iter()
+        .any(|offer| offer.generation.real_time_price_usd_per_mwh.is_some())
+    {
+        transactions = true_up(interval, &offers, demand_mwh, transactions);
+    }
+
+    Ok((transactions, decision))
+}
+
+// The electrolyzer's full capacity is scheduled day-ahead in merit order
+// without the strategy's rejections. Each plant's scheduled energy is paid at
+// the day-ahead price and the difference to what was consumed from it is
+// bought or sold back at the real-time price
+fn true_up(
+    interval: &DispatchInterval,
+    offers: &[Offer],
+    demand_mwh: f64,
+    transactions: Vec<EnergyTransaction>,
+) -> Vec<EnergyTransaction> {
+    let mut consumed = transactions
+        .into_iter()
+        .map(|transaction| (transaction.plant_id, transaction))
+        .collect::<HashMap<PowerPlantId, EnergyTransaction>>();
+    let mut settled = vec![];
+    let mut unscheduled_mwh = demand_mwh;
+
+    for offer in offers {
+        let generation = offer.generation;
+        let scheduled_mwh = f64::min(
+            unscheduled_mwh.max(0.0),
+            offered_mwh(interval, generation).max(0.0),
+        );
+        unscheduled_mwh -= scheduled_mwh;
+        let transaction = consumed.remove(&generation.plant_id);
+        let consumed_mwh = transaction.as_ref().map_or(0.0, |transaction| {
+            transaction.portfolio.total_electricity_mwh
+        });
+
+        if scheduled_mwh <= 0.0 && transaction.is_none() {
+            continue;
+        }
+
+        let real_time_price = generation
+            .real_time_price_usd_per_mwh
+            .unwrap_or(generation.sale_price_usd_per_mwh);
+        let price_usd = scheduled_mwh * generation.sale_price_usd_per_mwh
+            + (consumed_mwh - scheduled_mwh) * real_time_price;
+
+        settled.push(match transaction {
+            Some(transaction) => EnergyTransaction {
+                price_usd,
+                ..transaction
+            },
+            None => EnergyTransaction {
+                simulation_id: interval.simulation_id,
+                electrolyzer_id: interval.electrolyzer.id,
+                plant_id: generation.plant_id,
+                timestamp: interval.timestamp,
+                price_usd,
+                portfolio: EnergySourcePortfolio::default(),
+            },
+        });
+    }
+
+    settled
+}
+
+fn offer_cost(
+    interval: &DispatchInterval,
+    generation: &GenerationMetric,
+    objective: DispatchObjective,
+) -> f64 {
+    match objective {
+        DispatchObjective::MinimizeCost => generation.sale_price_usd_per_mwh,
+        DispatchObjective::MinimizeNetCost => {
+            generation.sale_price_usd_per_mwh - credit_value_per_mwh(interval, generation)
+        }
+    }
+}
+
+fn kg_hydrogen_per_mwh(interval: &DispatchInterval) -> f64 {
+    interval.electrolyzer.production.conversion_rate * interval.stack.efficiency
+}
+
+fn emissions_intensity(interval: &DispatchInterval, generation: &GenerationMetric) -> f64 {
+    let kg_hydrogen_per_mwh = kg_hydrogen_per_mwh(interval);
+    let portfolio = &generation.portfolio;
+
+    if kg_hydrogen_per_mwh <= 0.0 || portfolio.total_electricity_mwh <= 0.0 {
+        return f64::INFINITY;
+    }
+
+    interval.emission_factors.purchase_emitted_kg(
+        interval.power_grid.plant_details(generation.plant_id),
+        portfolio,
+    ) / portfolio.total_electricity_mwh
+        / kg_hydrogen_per_mwh
+}
+
+fn credit_value_per_mwh(interval: &DispatchInterval, generation: &GenerationMetric) -> f64 {
+    let tier = interval
+        .ruleset
+        .tier(emissions_intensity(interval, generation));
+    let year = interval
+        .timestamp
+        .to_utc_date_time()
+        .map_or(interval.ruleset.inflation_base_year, |date_time| {
+            date_time.year()
+        });
+
+    interval.ruleset.credit_usd_per_kg(tier, year) * kg_hydrogen_per_mwh(interval)
+}
+
+// A registered plant sells no more than its capacity over the interval
+fn offered_mwh(interval: &DispatchInterval, generation: &GenerationMetric) -> f64 {
+    let generated_mwh = generation.portfolio.total_electricity_mwh;
+
+    interval
+        .power_grid
+        .plant_details(generation.plant_id)
+        .map_or(generated_mwh, |details| {
+            f64::min(generated_mwh, details.capacity_mw * interval.interval_hours)
+        })
+}
+
+// Records are stamped at the end of the interval they cover, like the grid data
+// they are read from. Each record that overlaps the step contributes the share
+// of its energy that falls inside the step, so hourly data is split across
+// shorter steps and shorter records are summed into longer ones, with prices
+// averaged over time
+pub fn find_generation(
+    power_plant: &PowerPlant,
+    timestamp: &Timestamp,
+    step: SimulationStep,
+) -> Result<GenerationMetric> {
+    let step_start = timestamp.seconds;
+    let step_end = step_start + step.seconds();
+    let generations = power_plant.generations();
+    let first =
+        generations.partition_point(|generation| generation.time_generated.seconds <= step_start);
+    let mut portfolio = EnergySourcePortfolio::default();
+    let mut price_seconds = 0.0;
+    let mut real_time_price_seconds = Some(0.0);
+    let mut covered_seconds = 0;
+    let mut filled = false;
+
+    for (index, generation) in generations.iter().enumerate().skip(first) {
+        let record_end = generation.time_generated.seconds;
+        let record_seconds = record_seconds(generations, index, step);
+        let record_start = record_end - record_seconds;
+
+        if record_start >= step_end {
+            break;
+        }
+
+        let overlap_seconds = step_end.min(record_end) - step_start.max(record_start);
+
+        if overlap_seconds <= 0 {
+            continue;
+        }
+
+        portfolio = EnergySourcePortfolio::merge(
+            &portfolio,
+            &EnergySourcePortfolio::scale(
+                &generation.portfolio,
+                overlap_seconds as f64 / record_seconds as f64,
+            ),
+        );
+        price_seconds += generation.sale_price_usd_per_mwh * overlap_seconds as f64;
+        real_time_price_seconds = real_time_price_seconds
+            .zip(generation.real_time_price_usd_per_mwh)
+            .map(|(total, price)| total + price * overlap_seconds as f64);
+        covered_seconds += overlap_seconds;
+        filled |= generation.filled;
+    }
+
+    if covered_seconds < step.seconds() {
+        return Err(Error::not_found("Generation not found for timestep"));
+    }
+
+    let mut generation = GenerationMetric::new(
+        power_plant.plant_id,
+        timestamp,
+        price_seconds / covered_seconds as f64,
+        portfolio,
+    );
+    generation.real_time_price_usd_per_mwh =
+        real_time_price_seconds.map(|total| total / covered_seconds as f64);
+    generation.filled = filled;
+
+    Ok(generation)
+}
+
+// A record starts where the one before it ends, but lasts no longer than the gap
+// to the next one so the record after missing data does not stretch over the gap
+fn record_seconds(generations: &[GenerationMetric], index: usize, step: SimulationStep) -> i64 {
+    let record_end = generations[index].time_generated.seconds;
+    let next = generations
+        .get(index + 1)
+        .map(|generation| generation.time_generated.seconds - record_end);
+    let previous = index
+        .checked_sub(1)
+        .map(|previous| record_end - generations[previous].time_generated.seconds);
+
+    [next, previous]
+        .into_iter()
+        .flatten()
+        .filter(|seconds| *seconds > 0)
+        .min()
+        .unwrap_or(step.seconds())
+}
+
+// A plant without generation for the whole step, like one that had not started
+// operating yet, offers nothing in it. The step can only be simulated when at
+// least one plant has generation for it
+pub fn find_generations(
+    power_grid: &PowerGrid,
+    timestamp: &Timestamp,
+    step: SimulationStep,
+) -> Result<Vec<GenerationMetric>> {
+    let mut error = Error::not_found("Generation not found for timestep");
+    let generations = power_grid
+        .power_plants
+        .iter()
+        .filter_map(
+            |power_plant| match find_generation(power_plant, timestamp, step) {
+                Ok(generation) => Some(generation),
+                Err(err) => {
+                    error = err;
+                    None
+                }
+            },
+        )
+        .collect::<Vec<GenerationMetric>>();
+
+    match generations.is_empty() {
+        true => Err(error),
+        false => Ok(generations),
+    }
+}
+
+fn purchase(
+    simulation_id: SimulationId,
+    electrolyzer: &Electrolyzer,
+    generation: &GenerationMetric,
+    amount_mwh: f64,
+    timestamp: &Timestamp,
+) -> Result<EnergyTransaction> {
+    Ok(EnergyTransaction {
+        simulation_id,
+        electrolyzer_id: electrolyzer.id,
+        plant_id: generation.plant_id,
+        timestamp: *timestamp,
+        price_usd: generation.sale_price_usd_per_mwh * amount_mwh,
+        portfolio: EnergySourcePortfolio::scale_to_amount(&generation.portfolio, amount_mwh)?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        logic::degradation::StackState,
+        schema::{
+            electrolyzer::{ConstantProduction, Electrolyzer},
+            emissions::{EmissionFactor, EmissionFactorSet},
+            plant::PowerPlantDetails,
+            policy::TaxCredit45VRuleset,
+            simulation::{
+                DispatchObjective, DispatchReason, EnergySourcePortfolio, EnergyTransaction,
+                GenerationMetric, PowerGrid, PowerPlant, SimulationStep,
+            },
+            time::Timestamp,
+        },
+    };
+
+    use super::{
+        find_generation, find_generations, AlwaysOn, CarbonIntensityCeiling, CheapestHours,
+        DispatchInterval, DispatchStrategy, PriceCeiling,
+    };
+
+    fn create_power_grid(
+        generations: Vec<(usize, Timestamp, f64, EnergySourcePortfolio)>,
+    ) -> PowerGrid {
+        let mut power_grid = PowerGrid::default();
+
+        // Each generation covers the quarter hour starting at its timestamp,
+        // so it is stamped at the end of that quarter hour like grid data
+        for (plant_id, timestamp, price, portfolio) in generations {
+            let mut power_plant = PowerPlant::default();
+            power_plant.plant_id = plant_id;
+            power_plant.add_generation(GenerationMetric::new(
+                plant_id,
+                &Timestamp::new(timestamp.seconds + 900, timestamp.nanos),
+                price,
+                portfolio,
+            ));
+            power_grid.add_power_plant(power_plant);
+        }
+
+        power_grid
+    }
+
+    fn create_interval<'a>(
+        timestamp: &Timestamp,
+        electrolyzer: &'a Electrolyzer,
+        stack: &'a StackState,
+        emission_factors: &'a EmissionFactorSet,
+        ruleset: &'a TaxCredit45VRuleset,
+        power_grid: &'a PowerGrid,
+    ) -> DispatchInterval<'a> {
+        DispatchInterval {
+            simulation_id: 0,
+            timestamp: *timestamp,
+            interval_hours: 0.25,
+            electrolyzer,
+            stack,
+            emission_factors,
+            ruleset,
+            power_grid,
+            generations: find_generations(power_grid, timestamp, SimulationStep::FifteenMinutes)
+                .unwrap(),
+        }
+    }
+
+    fn create_emission_factors() -> EmissionFactorSet {
+        let mut emission_factors = EmissionFactorSet::default();
+        emission_factors.factors.coal = EmissionFactor::new(353.88, 0.0);
+
+        emission_factors
+    }
+
+    fn create_portfolio(wind_mwh: f64, coal_mwh: f64) -> EnergySourcePortfolio {
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.total_electricity_mwh = wind_mwh + coal_mwh;
+        portfolio.wind_mwh = wind_mwh;
+        portfolio.coal_mwh = coal_mwh;
+
+        portfolio
+    }
+
+    #[test]
+    fn should_make_optimal_transaction() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 8.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let mut generation_portfolio = EnergySourcePortfolio::default();
+        generation_portfolio.total_electricity_mwh = 4.0;
+        generation_portfolio.natural_gas_mwh = 4.0;
+        let mut transaction_portfolio = EnergySourcePortfolio::default();
+        transaction_portfolio.total_electricity_mwh = 2.0;
+        transaction_portfolio.natural_gas_mwh = 2.0;
+        let power_grid = create_power_grid(vec![(0, timestamp, 1.0, generation_portfolio)]);
+        let expected_transactions = vec![EnergyTransaction {
+            simulation_id: 0,
+            electrolyzer_id: 0,
+            plant_id: 0,
+            timestamp: Timestamp::new(timestamp.seconds, timestamp.nanos),
+            price_usd: 2.0,
+            portfolio: transaction_portfolio,
+        }];
+        let strategy = AlwaysOn {
+            objective: DispatchObjective::MinimizeCost,
+        };
+
+        let (transactions, decision) = strategy
+            .dispatch(&create_interval(
+                &timestamp,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+
+        assert_eq!(transactions, expected_transactions);
+        assert_eq!(decision.reason, DispatchReason::Dispatched);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_fail_to_find_missing_generation() {
+        let timestamp = Timestamp::default();
+        let power_grid = create_power_grid(vec![(0, timestamp, 1.0, create_portfolio(4.0, 0.0))]);
+        let future_timestamp = Timestamp::new(timestamp.seconds + 3600, timestamp.nanos);
+
+        find_generations(
+            &power_grid,
+            &future_timestamp,
+            SimulationStep::FifteenMinutes,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn should_match_exact_settlement_interval() {
+        let mut power_plant = PowerPlant::default();
+
+        for (seconds, price) in [(900, 10.0), (1800, 20.0), (2700, 30.0), (3600, 40.0)] {
+            power_plant.add_generation(GenerationMetric::new(
+                0,
+                &Timestamp::new(seconds, 0),
+                price,
+                create_portfolio(1.0, 0.0),
+            ));
+        }
+
+        let generation = find_generation(
+            &power_plant,
+            &Timestamp::new(1800, 0),
+            SimulationStep::FifteenMinutes,
+        )
+        .unwrap();
+
+        assert_eq!(generation.sale_price_usd_per_mwh, 30.0);
+        assert_eq!(generation.portfolio, create_portfolio(1.0, 0.0));
+    }
+
+    #[test]
+    fn should_purchase_generation_stamped_at_end_of_interval() {
+        let timestamp = Timestamp::new(900, 0);
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 8.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let mut power_plant = PowerPlant::default();
+        let mut power_grid = PowerGrid::default();
+
+        for (seconds, price) in [(900, 10.0), (1800, 20.0), (2700, 30.0)] {
+            power_plant.add_generation(GenerationMetric::new(
+                0,
+                &Timestamp::new(seconds, 0),
+                price,
+                create_portfolio(4.0, 0.0),
+            ));
+        }
+        power_grid.add_power_plant(power_plant);
+
+        let (transactions, _) = AlwaysOn {
+            objective: DispatchObjective::MinimizeCost,
+        }
+        .dispatch(&create_interval(
+            &timestamp,
+            &electrolyzer,
+            &stack,
+            &emission_factors,
+            &ruleset,
+            &power_grid,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            transactions,
+            vec![EnergyTransaction {
+                simulation_id: 0,
+                electrolyzer_id: 0,
+                plant_id: 0,
+                timestamp,
+                price_usd: 40.0,
+                portfolio: create_portfolio(2.0, 0.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_sum_quarter_hours_ending_within_hourly_step() {
+        let mut power_plant = PowerPlant::default();
+
+        for (seconds, price) in [
+            (0, 5.0),
+            (900, 10.0),
+            (1800, 20.0),
+            (2700, 30.0),
+            (3600, 40.0),
+            (4500, 50.0),
+        ] {
+            power_plant.add_generation(GenerationMetric::new(
+                0,
+                &Timestamp::new(seconds, 0),
+                price,
+                create_portfolio(1.0, 0.0),
+            ));
+        }
+
+        let generation = find_generation(
+            &power_plant,
+            &Timestamp::new(0, 0),
+            SimulationStep::SixtyMinutes,
+        )
+        .unwrap();
+
+        assert_eq!(generation.sale_price_usd_per_mwh, 25.0);
+        assert_eq!(generation.portfolio, create_portfolio(4.0, 0.0));
+    }
+
+    #[test]
+    fn should_split_hourly_record_across_steps() {
+        let mut power_plant = PowerPlant::default();
+
+        for (seconds, price) in [(3600, 10.0), (7200, 20.0)] {
+            power_plant.add_generation(GenerationMetric::new(
+                0,
+                &Timestamp::new(seconds, 0),
+                price,
+                create_portfolio(12.0, 0.0),
+            ));
+        }
+
+        let generation = find_generation(
+            &power_plant,
+            &Timestamp::new(3300, 0),
+            SimulationStep::FiveMinutes,
+        )
+        .unwrap();
+
+        assert_eq!(generation.sale_price_usd_per_mwh, 10.0);
+        assert_eq!(generation.portfolio, create_portfolio(1.0, 0.0));
+    }
+
+    #[test]
+    fn should_buy_from_cheapest_plant_first() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 12.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let power_grid = create_power_grid(vec![
+            (0, timestamp, 30.0, create_portfolio(2.0, 0.0)),
+            (1, timestamp, 10.0, create_portfolio(2.0, 0.0)),
+        ]);
+        let strategy = AlwaysOn {
+            objective: DispatchObjective::MinimizeCost,
+        };
+
+        let (transactions, decision) = strategy
+            .dispatch(&create_interval(
+                &timestamp,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].plant_id, 1);
+        assert_eq!(transactions[0].price_usd, 20.0);
+        assert_eq!(transactions[1].plant_id, 0);
+        assert_eq!(transactions[1].price_usd, 30.0);
+        assert_eq!(decision.purchased_mwh, 3.0);
+        assert_eq!(decision.reason, DispatchReason::Dispatched);
+    }
+
+    #[test]
+    fn should_cap_purchases_at_plant_capacity() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 12.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let mut power_grid = create_power_grid(vec![
+            (0, timestamp, 30.0, create_portfolio(4.0, 0.0)),
+            (1, timestamp, 10.0, create_portfolio(4.0, 0.0)),
+        ]);
+        power_grid.power_plants[1].details = Some(PowerPlantDetails {
+            plant_id: 1,
+            capacity_mw: 4.0,
+            ..Default::default()
+        });
+
+        let (transactions, _) = AlwaysOn {
+            objective: DispatchObjective::MinimizeCost,
+        }
+        .dispatch(&create_interval(
+            &timestamp,
+            &electrolyzer,
+            &stack,
+            &emission_factors,
+            &ruleset,
+            &power_grid,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            transactions
+                .iter()
+                .map(|transaction| (
+                    transaction.plant_id,
+                    transaction.portfolio.total_electricity_mwh
+                ))
+                .collect::<Vec<(usize, f64)>>(),
+            vec![(1, 1.0), (0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn should_report_supply_limited_interval() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 40.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let power_grid = create_power_grid(vec![(0, timestamp, 10.0, create_portfolio(4.0, 0.0))]);
+        let strategy = AlwaysOn {
+            objective: DispatchObjective::MinimizeCost,
+        };
+
+        let (_, decision) = strategy
+            .dispatch(&create_interval(
+                &timestamp,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+
+        assert_eq!(decision.demand_mwh, 10.0);
+        assert_eq!(decision.purchased_mwh, 4.0);
+        assert_eq!(decision.reason, DispatchReason::SupplyLimited);
+    }
+
+    #[test]
+    fn should_prefer_clean_plant_net_of_credit() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 8.0;
+        electrolyzer.production = ConstantProduction {
+            conversion_rate: 20.0,
+        };
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let power_grid = create_power_grid(vec![
+            (0, timestamp, 10.0, create_portfolio(0.0, 4.0)),
+            (1, timestamp, 40.0, create_portfolio(4.0, 0.0)),
+        ]);
+        let strategy = AlwaysOn {
+            objective: DispatchObjective::MinimizeNetCost,
+        };
+
+        let (transactions, _) = strategy
+            .dispatch(&create_interval(
+                &timestamp,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].plant_id, 1);
+    }
+
+    #[test]
+    fn should_not_run_above_price_ceiling() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 8.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let power_grid = create_power_grid(vec![(0, timestamp, 50.0, create_portfolio(4.0, 0.0))]);
+        let strategy = PriceCeiling {
+            objective: DispatchObjective::MinimizeCost,
+            ceiling_usd_per_mwh: 30.0,
+        };
+
+        let (transactions, decision) = strategy
+            .dispatch(&create_interval(
+                &timestamp,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+
+        assert!(transactions.is_empty());
+        assert_eq!(decision.reason, DispatchReason::AbovePriceCeiling);
+    }
+
+    #[test]
+    fn should_partially_curtail_above_carbon_ceiling() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 16.0;
+        electrolyzer.production = ConstantProduction {
+            conversion_rate: 20.0,
+        };
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let power_grid = create_power_grid(vec![
+            (0, timestamp, 10.0, create_portfolio(0.0, 4.0)),
+            (1, timestamp, 40.0, create_portfolio(2.0, 0.0)),
+        ]);
+        let strategy = CarbonIntensityCeiling {
+            objective: DispatchObjective::MinimizeCost,
+            ceiling_co2_per_h2: 4.0,
+        };
+
+        let (transactions, decision) = strategy
+            .dispatch(&create_interval(
+                &timestamp,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].plant_id, 1);
+        assert_eq!(decision.purchased_mwh, 2.0);
+        assert_eq!(decision.reason, DispatchReason::PartiallyCurtailed);
+    }
+
+    #[test]
+    fn should_only_run_in_cheapest_hours() {
+        let cheap_hour = Timestamp::new(0, 0);
+        let expensive_hour = Timestamp::new(3600, 0);
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 8.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let mut power_grid = PowerGrid::default();
+        let mut power_plant = PowerPlant::default();
+        power_plant.add_generation(GenerationMetric::new(
+            0,
+            &Timestamp::new(cheap_hour.seconds + 900, 0),
+            10.0,
+            create_portfolio(8.0, 0.0),
+        ));
+        power_plant.add_generation(GenerationMetric::new(
+            0,
+            &Timestamp::new(expensive_hour.seconds + 900, 0),
+            90.0,
+            create_portfolio(8.0, 0.0),
+        ));
+        power_grid.add_power_plant(power_plant);
+        let strategy = CheapestHours::new(DispatchObjective::MinimizeCost, 1, &power_grid).unwrap();
+
+        let (_, cheap_decision) = strategy
+            .dispatch(&create_interval(
+                &cheap_hour,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+        let (_, expensive_decision) = strategy
+            .dispatch(&create_interval(
+                &expensive_hour,
+                &electrolyzer,
+                &stack,
+                &emission_factors,
+                &ruleset,
+                &power_grid,
+            ))
+            .unwrap();
+
+        assert_eq!(cheap_decision.reason, DispatchReason::Dispatched);
+        assert_eq!(
+            expensive_decision.reason,
+            DispatchReason::OutsideCheapestHours
+        );
+    }
+
+    #[test]
+    fn should_rank_cheapest_hours_by_central_day() {
+        // 23:00 on May 31 and midnight on June 1 in Central Daylight Time
+        let last_hour = Timestamp::new(1685592000, 0);
+        let first_hour = Timestamp::new(1685595600, 0);
+        let mut power_grid = PowerGrid::default();
+        let mut power_plant = PowerPlant::default();
+        power_plant.add_generation(GenerationMetric::new(
+            0,
+            &Timestamp::new(last_hour.seconds + 900, 0),
+            10.0,
+            create_portfolio(8.0, 0.0),
+        ));
+        power_plant.add_generation(GenerationMetric::new(
+            0,
+            &Timestamp::new(first_hour.seconds + 900, 0),
+            90.0,
+            create_portfolio(8.0, 0.0),
+        ));
+        power_grid.add_power_plant(power_plant);
+
+        let strategy = CheapestHours::new(DispatchObjective::MinimizeCost, 1, &power_grid).unwrap();
+
+        assert_eq!(
+            strategy.cheapest_hours,
+            HashSet::from([last_hour.seconds, first_hour.seconds])
+        );
+    }
+
+    #[test]
+    fn should_true_up_day_ahead_schedule_at_real_time_price() {
+        let timestamp = Timestamp::default();
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 8.0;
+        let stack = StackState::default();
+        let emission_factors = create_emission_factors();
+        let ruleset = TaxCredit45VRuleset::final_rules();
+        let mut generation = GenerationMetric::new(
+            0,
+            &Timestamp::new(timestamp.seconds + 900, 0),
+            50.0,
+            create_portfolio(4.0, 0.0),
+        );
+        generation.real_time_price_usd_per_mwh = Some(20.0);
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(PowerPlant::new(0, vec![generation]));
+        let interval = create_interval(
+            &timestamp,
+            &electrolyzer,
+            &stack,
+            &emission_factors,
+            &ruleset,
+            &power_grid,
+        );
+
+        let (dispatched, _) = AlwaysOn {
+            objective: DispatchObjective::MinimizeCost,
+        }
+        .dispatch(&interval)
+        .unwrap();
+        let (curtailed, _) = PriceCeiling {
+            objective: DispatchObjective::MinimizeCost,
+            ceiling_usd_per_mwh: 30.0,
+        }
+        .dispatch(&interval)
+        .unwrap();
+
+        assert_eq!(dispatched[0].price_usd, 100.0);
+        assert_eq!(dispatched[0].portfolio.total_electricity_mwh, 2.0);
+        assert_eq!(curtailed[0].price_usd, 60.0);
+        assert_eq!(curtailed[0].portfolio.total_electricity_mwh, 0.0);
+    }
+}
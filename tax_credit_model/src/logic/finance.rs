@@ -0,0 +1,477 @@
+use crate::schema::{
+    electrolyzer::Electrolyzer,
+    errors::{Error, Result},
+    finance::{CashFlowYear, FinancialConfiguration, FinancialReport},
+    policy::CREDIT_PERIOD_YEARS,
+};
+
+use chrono::Datelike;
+
+use super::{
+    credit_window::summarize_credit_years,
+    degradation::{StackState, HOURS_PER_YEAR},
+    simulation::SimulationState,
+};
+
+const IRR_ITERATIONS: usize = 200;
+
+// A year of operation. Simulated years carry the stack replacements the
+// simulation recorded, extrapolated years have theirs projected from wear
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub struct AnnualOperation {
+    pub kg_hydrogen: f64,
+    pub hours_operated: f64,
+    pub energy_usd: f64,
+    pub tax_credit_usd: f64,
+    pub replacement_usd: f64,
+}
+
+impl AnnualOperation {
+    // Each calendar year of a credit window simulation as it was simulated, or
+    // a single simulated window scaled up to a full year
+    pub fn simulated_years(state: &SimulationState) -> Result<Vec<Self>> {
+        if state.credit_window_years.is_empty() {
+            return Ok(vec![Self {
+                replacement_usd: state
+                    .stack_replacements
+                    .iter()
+                    .map(|replacement| replacement.cost_usd)
+                    .sum(),
+                ..Self::from_simulation(state)
+            }]);
+        }
+
+        summarize_credit_years(state)?
+            .into_iter()
+            .map(|credit_year| {
+                let mut replacement_usd = 0.0;
+
+                for replacement in &state.stack_replacements {
+                    if replacement.replacement_timestamp.to_utc_date_time()?.year()
+                        == credit_year.year
+                    {
+                        replacement_usd += replacement.cost_usd;
+                    }
+                }
+
+                Ok(Self {
+                    kg_hydrogen: credit_year.kg_hydrogen,
+                    hours_operated: credit_year.hours_operated,
+                    energy_usd: credit_year.energy_usd,
+                    tax_credit_usd: credit_year.tax_credit_usd,
+                    replacement_usd,
+                })
+            })
+            .collect()
+    }
+
+    // Totals from the simulated window scaled up to a full year of operation,
+    // which is repeated for the years after the simulation
+    pub fn from_simulation(state: &SimulationState) -> Self {
+        let simulated_hours = state.dispatch_decisions.len() as f64 * state.simulation_step.hours();
+
+        if simulated_hours <= 0.0 {
+            return Self::default();
+        }
+
+        let scale = HOURS_PER_YEAR / simulated_hours;
+        let operating_intervals = state
+            .hydrogen_productions
+            .iter()
+            .filter(|production| production.kg_hydrogen > 0.0)
+            .count();
+
+        Self {
+            kg_hydrogen: scale
+                * state
+                    .hydrogen_productions
+                    .iter()
+                    .map(|production| production.kg_hydrogen)
+                    .sum::<f64>(),
+            hours_operated: scale * operating_intervals as f64 * state.simulation_step.hours(),
+            energy_usd: scale
+                * state
+                    .transactions
+                    .iter()
+                    .map(|transaction| transaction.price_usd)
+                    .sum::<f64>(),
+            tax_credit_usd: scale
+                * state
+                    .tax_credit
+                    .iter()
+                    .map(|tax_credit| tax_credit.total_usd)
+                    .sum::<f64>(),
+            replacement_usd: 0.0,
+        }
+    }
+}
+
+pub fn create_financial_report(
+    electrolyzer: &Electrolyzer,
+    state: &SimulationState,
+    configuration: &FinancialConfiguration,
+) -> Result<FinancialReport> {
+    if configuration.project_life_years == 0 {
+        return Err(Error::invalid_argument(
+            "Project life must be at least one year",
+        ));
+    }
+
+    if configuration.discount_rate <= -100.0 {
+        return Err(Error::invalid_argument(
+            "Discount rate must be greater than -100%",
+        ));
+    }
+
+    let cash_flows = project_cash_flows(
+        electrolyzer,
+        &AnnualOperation::simulated_years(state)?,
+        &AnnualOperation::from_simulation(state),
+        state.stack.operating_hours,
+        configuration,
+    );
+    let discount_rate = configuration.discount_rate / 100.0;
+
+    Ok(FinancialReport {
+        levelized_cost_usd_per_kg: levelized_cost(&cash_flows, discount_rate, false),
+        levelized_cost_net_of_credit_usd_per_kg: levelized_cost(&cash_flows, discount_rate, true),
+        net_present_value_usd: net_present_value(&cash_flows, discount_rate),
+        internal_rate_of_return: internal_rate_of_return(&cash_flows),
+        payback_years: payback_years(&cash_flows),
+        cash_flows,
+    })
+}
+
+// Year zero carries the capital cost and the simulated years follow as they
+// were simulated. Every year after them repeats the extrapolated operation,
+// with stack replacements falling in the year the stack, which starts with the
+// hours it ended the simulation on, wears out and 45V income ending after the
+// credit period
+pub fn project_cash_flows(
+    electrolyzer: &Electrolyzer,
+    simulated_years: &[AnnualOperation],
+    extrapolated: &AnnualOperation,
+    stack_operating_hours: f64,
+    configuration: &FinancialConfiguration,
+) -> Vec<CashFlowYear> {
+    let stack_life_hours =
+        StackState::hours_until_replacement(electrolyzer).filter(|hours| *hours > 0.0);
+    let mut stack_hours = stack_operating_hours;
+    let mut cash_flows = vec![CashFlowYear {
+        year: 0,
+        capex_usd: electrolyzer.capex,
+        net_usd: -electrolyzer.capex,
+        cumulative_usd: -electrolyzer.capex,
+        ..Default::default()
+    }];
+    let mut cumulative_usd = -electrolyzer.capex;
+
+    for year in 1..=configuration.project_life_years {
+        let operation = match simulated_years.get(year as usize - 1) {
+            Some(simulated_year) => *simulated_year,
+            None => {
+                let hours_after = stack_hours + extrapolated.hours_operated;
+                let replacements = stack_life_hours.map_or(0.0, |stack_life_hours| {
+                    (hours_after / stack_life_hours).floor()
+                        - (stack_hours / stack_life_hours).floor()
+                });
+                stack_hours = hours_after;
+
+                AnnualOperation {
+                    tax_credit_usd: if year <= CREDIT_PERIOD_YEARS {
+                        extrapolated.tax_credit_usd
+                    } else {
+                        0.0
+                    },
+                    replacement_usd: replacements * electrolyzer.replacement_cost,
+                    ..*extrapolated
+                }
+            }
+        };
+        let mut cash_flow = CashFlowYear {
+            year,
+            kg_hydrogen: operation.kg_hydrogen,
+            opex_usd: electrolyzer.opex * operation.hours_operated,
+            energy_usd: operation.energy_usd,
+            replacement_usd: operation.replacement_usd,
+            tax_credit_usd: operation.tax_credit_usd,
+            hydrogen_revenue_usd: operation.kg_hydrogen * configuration.hydrogen_price_usd_per_kg,
+            ..Default::default()
+        };
+        cash_flow.net_usd =
+            cash_flow.hydrogen_revenue_usd + cash_flow.tax_credit_usd - cash_flow.costs_usd();
+        cumulative_usd += cash_flow.net_usd;
+        cash_flow.cumulative_usd = cumulative_usd;
+        cash_flows.push(cash_flow);
+    }
+
+    cash_flows
+}
+
+fn discount_factor(discount_rate: f64, year: u32) -> f64 {
+    (1.0 + discount_rate).powi(year as i32)
+}
+
+fn levelized_cost(
+    cash_flows: &[CashFlowYear],
+    discount_rate: f64,
+    net_of_credit: bool,
+) -> Option<f64> {
+    let (costs_usd, kg_hydrogen) =
+        cash_flows
+            .iter()
+            .fold((0.0, 0.0), |(costs_usd, kg_hydrogen), cash_flow| {
+                let discount_factor = discount_factor(discount_rate, cash_flow.year);
+                let mut cost_usd = cash_flow.costs_usd();
+
+                if net_of_credit {
+                    cost_usd -= cash_flow.tax_credit_usd;
+                }
+
+                (
+                    costs_usd + cost_usd / discount_factor,
+                    kg_hydrogen + cash_flow.kg_hydrogen / discount_factor,
+                )
+            });
+
+    (kg_hydrogen > 0.0).then_some(costs_usd / kg_hydrogen)
+}
+
+fn net_present_value(cash_flows: &[CashFlowYear], discount_rate: f64) -> f64 {
+    cash_flows
+        .iter()
+        .map(|cash_flow| cash_flow.net_usd / discount_factor(discount_rate, cash_flow.year))
+        .sum()
+}
+
+// Bisects for the rate where the net present value crosses zero, which only
+// exists when the project's cash flows change sign
+fn internal_rate_of_return(cash_flows: &[CashFlowYear]) -> Option<f64> {
+    let (mut low, mut high) = (-0.99, 10.0);
+    let low_value = net_present_value(cash_flows, low);
+
+    if low_value.signum() == net_present_value(cash_flows, high).signum() {
+        return None;
+    }
+
+    for _ in 0..IRR_ITERATIONS {
+        let middle = (low + high) / 2.0;
+
+        if net_present_value(cash_flows, middle).signum() == low_value.signum() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    Some((low + high) / 2.0)
+}
+
+fn payback_years(cash_flows: &[CashFlowYear]) -> Option<f64> {
+    let mut previous_cumulative_usd = 0.0;
+
+    for cash_flow in cash_flows {
+        if cash_flow.cumulative_usd >= 0.0 {
+            if cash_flow.year == 0 || cash_flow.net_usd <= 0.0 {
+                return Some(cash_flow.year as f64);
+            }
+
+            return Some(cash_flow.year as f64 - 1.0 - previous_cumulative_usd / cash_flow.net_usd);
+        }
+
+        previous_cumulative_usd = cash_flow.cumulative_usd;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{electrolyzer::Electrolyzer, finance::FinancialConfiguration};
+
+    use super::{
+        internal_rate_of_return, levelized_cost, net_present_value, payback_years,
+        project_cash_flows, AnnualOperation,
+    };
+
+    fn create_electrolyzer() -> Electrolyzer {
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capex = 1000.0;
+        electrolyzer.opex = 1.0;
+
+        electrolyzer
+    }
+
+    fn create_configuration(project_life_years: u32) -> FinancialConfiguration {
+        FinancialConfiguration {
+            discount_rate: 0.0,
+            project_life_years,
+            hydrogen_price_usd_per_kg: 2.0,
+        }
+    }
+
+    #[test]
+    fn should_levelize_cost_over_hydrogen_produced() {
+        let operation = AnnualOperation {
+            kg_hydrogen: 100.0,
+            hours_operated: 100.0,
+            energy_usd: 100.0,
+            tax_credit_usd: 0.0,
+            replacement_usd: 0.0,
+        };
+
+        let cash_flows = project_cash_flows(
+            &create_electrolyzer(),
+            &[],
+            &operation,
+            0.0,
+            &create_configuration(10),
+        );
+
+        assert_eq!(levelized_cost(&cash_flows, 0.0, false), Some(3.0));
+    }
+
+    #[test]
+    fn should_stop_tax_credit_after_credit_period() {
+        let operation = AnnualOperation {
+            tax_credit_usd: 50.0,
+            ..Default::default()
+        };
+
+        let cash_flows = project_cash_flows(
+            &create_electrolyzer(),
+            &[],
+            &operation,
+            0.0,
+            &create_configuration(12),
+        );
+
+        assert_eq!(cash_flows[10].tax_credit_usd, 50.0);
+        assert_eq!(cash_flows[11].tax_credit_usd, 0.0);
+    }
+
+    #[test]
+    fn should_replace_stack_in_year_it_wears_out() {
+        let mut electrolyzer = create_electrolyzer();
+        electrolyzer.degradation_rate = 10.0;
+        electrolyzer.replacement_threshold = 90.0;
+        electrolyzer.replacement_cost = 500.0;
+        let operation = AnnualOperation {
+            hours_operated: 4380.0,
+            ..Default::default()
+        };
+
+        let cash_flows = project_cash_flows(
+            &electrolyzer,
+            &[],
+            &operation,
+            0.0,
+            &create_configuration(4),
+        );
+
+        assert_eq!(
+            cash_flows
+                .iter()
+                .map(|cash_flow| cash_flow.replacement_usd)
+                .collect::<Vec<f64>>(),
+            vec![0.0, 0.0, 500.0, 0.0, 500.0]
+        );
+    }
+
+    #[test]
+    fn should_extrapolate_only_after_simulated_years() {
+        let mut electrolyzer = create_electrolyzer();
+        electrolyzer.degradation_rate = 10.0;
+        electrolyzer.replacement_threshold = 90.0;
+        electrolyzer.replacement_cost = 500.0;
+        let simulated_years = [
+            AnnualOperation {
+                kg_hydrogen: 50.0,
+                tax_credit_usd: 30.0,
+                replacement_usd: 500.0,
+                ..Default::default()
+            },
+            AnnualOperation {
+                kg_hydrogen: 100.0,
+                tax_credit_usd: 60.0,
+                ..Default::default()
+            },
+        ];
+        let extrapolated = AnnualOperation {
+            kg_hydrogen: 80.0,
+            hours_operated: 4380.0,
+            tax_credit_usd: 40.0,
+            ..Default::default()
+        };
+
+        let cash_flows = project_cash_flows(
+            &electrolyzer,
+            &simulated_years,
+            &extrapolated,
+            4380.0,
+            &create_configuration(12),
+        );
+
+        assert_eq!(
+            cash_flows
+                .iter()
+                .map(|cash_flow| (
+                    cash_flow.kg_hydrogen,
+                    cash_flow.tax_credit_usd,
+                    cash_flow.replacement_usd
+                ))
+                .collect::<Vec<(f64, f64, f64)>>(),
+            vec![
+                (0.0, 0.0, 0.0),
+                (50.0, 30.0, 500.0),
+                (100.0, 60.0, 0.0),
+                (80.0, 40.0, 500.0),
+                (80.0, 40.0, 0.0),
+                (80.0, 40.0, 500.0),
+                (80.0, 40.0, 0.0),
+                (80.0, 40.0, 500.0),
+                (80.0, 40.0, 0.0),
+                (80.0, 40.0, 500.0),
+                (80.0, 40.0, 0.0),
+                (80.0, 0.0, 500.0),
+                (80.0, 0.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_payback_and_return() {
+        let operation = AnnualOperation {
+            kg_hydrogen: 300.0,
+            ..Default::default()
+        };
+
+        let cash_flows = project_cash_flows(
+            &create_electrolyzer(),
+            &[],
+            &operation,
+            0.0,
+            &create_configuration(5),
+        );
+
+        assert_eq!(net_present_value(&cash_flows, 0.0), 2000.0);
+        assert_eq!(payback_years(&cash_flows), Some(1.0 + 400.0 / 600.0));
+        assert!(internal_rate_of_return(&cash_flows).unwrap() > 0.5);
+    }
+
+    #[test]
+    fn should_not_report_return_without_income() {
+        let cash_flows = project_cash_flows(
+            &create_electrolyzer(),
+            &[],
+            &AnnualOperation::default(),
+            0.0,
+            &create_configuration(5),
+        );
+
+        assert_eq!(internal_rate_of_return(&cash_flows), None);
+        assert_eq!(payback_years(&cash_flows), None);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::schema::{
+    emissions::EmissionFactorSet,
+    plant::GRID_MIX_PLANT_ID,
+    simulation::{EnergySource, EnergySourcePortfolio, GenerationMetric, MarginalEmissionsSource},
+};
+
+// Fuels that can respond to a change in load, cheapest to run first. Storage
+// and unknown sources are left out as their fuel cannot be told
+const MERIT_ORDER: [EnergySource; 10] = [
+    EnergySource::Solar,
+    EnergySource::Wind,
+    EnergySource::Hydropower,
+    EnergySource::Geothermal,
+    EnergySource::Nuclear,
+    EnergySource::Biomass,
+    EnergySource::Coal,
+    EnergySource::NaturalGas,
+    EnergySource::Hydrocarbons,
+    EnergySource::Petroleum,
+];
+
+// Marginal rates describe the grid rather than the plant energy is bought from,
+// so the grid mix is used whenever the interval has one
+pub fn marginal_emission_rate(
+    generations: &[GenerationMetric],
+    emission_factors: &EmissionFactorSet,
+    source: MarginalEmissionsSource,
+) -> f64 {
+    let Some(generation) = generations
+        .iter()
+        .find(|generation| generation.plant_id == GRID_MIX_PLANT_ID)
+        .or(generations.first())
+    else {
+        return 0.0;
+    };
+
+    match (source, generation.marginal_emission_rate_kg_co2e_per_mwh) {
+        (MarginalEmissionsSource::ImportedSeries, Some(rate)) => rate,
+        _ => price_setting_fuel(generation).map_or_else(
+            || average_emission_rate(&generation.portfolio, emission_factors),
+            |fuel| {
+                emission_factors
+                    .factors
+                    .factor(&fuel)
+                    .total_kg_co2e_per_mwh()
+            },
+        ),
+    }
+}
+
+// The most expensive fuel running sets a positive price. At or below zero the
+// grid is curtailing, so the cheapest fuel running is what extra load absorbs
+pub fn price_setting_fuel(generation: &GenerationMetric) -> Option<EnergySource> {
+    let mut running = MERIT_ORDER
+        .iter()
+        .filter(|fuel| generation.portfolio.energy_mwh(fuel) > 0.0)
+        .cloned();
+
+    if generation.sale_price_usd_per_mwh <= 0.0 {
+        running.next()
+    } else {
+        running.next_back()
+    }
+}
+
+fn average_emission_rate(
+    portfolio: &EnergySourcePortfolio,
+    emission_factors: &EmissionFactorSet,
+) -> f64 {
+    if portfolio.total_electricity_mwh <= 0.0 {
+        return 0.0;
+    }
+
+    emission_factors.emitted_kg(portfolio) / portfolio.total_electricity_mwh
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        emissions::{EmissionFactor, EmissionFactorSet, EmissionFactors},
+        simulation::{
+            EnergySource, EnergySourcePortfolio, GenerationMetric, MarginalEmissionsSource,
+        },
+        time::Timestamp,
+    };
+
+    use super::{marginal_emission_rate, price_setting_fuel};
+
+    fn create_generation(price: f64) -> GenerationMetric {
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.add_energy(&EnergySource::Wind, 50.0);
+        portfolio.add_energy(&EnergySource::Coal, 30.0);
+        portfolio.add_energy(&EnergySource::NaturalGas, 20.0);
+        portfolio.add_energy(&EnergySource::Unknown, 10.0);
+
+        GenerationMetric::new(0, &Timestamp::default(), price, portfolio)
+    }
+
+    #[test]
+    fn should_pick_price_setting_fuel() {
+        assert_eq!(
+            price_setting_fuel(&create_generation(40.0)),
+            Some(EnergySource::NaturalGas)
+        );
+        assert_eq!(
+            price_setting_fuel(&create_generation(-5.0)),
+            Some(EnergySource::Wind)
+        );
+    }
+
+    #[test]
+    fn should_prefer_imported_rate_when_selected() {
+        let mut emission_factors = EmissionFactorSet::default();
+        emission_factors.factors = EmissionFactors {
+            natural_gas: EmissionFactor::new(400.0, 50.0),
+            ..Default::default()
+        };
+        let mut imported = create_generation(40.0);
+        imported.marginal_emission_rate_kg_co2e_per_mwh = Some(600.0);
+
+        assert_eq!(
+            marginal_emission_rate(
+                &[imported.clone()],
+                &emission_factors,
+                MarginalEmissionsSource::PriceSetting
+            ),
+            450.0
+        );
+        assert_eq!(
+            marginal_emission_rate(
+                &[imported],
+                &emission_factors,
+                MarginalEmissionsSource::ImportedSeries
+            ),
+            600.0
+        );
+        assert_eq!(
+            marginal_emission_rate(
+                &[create_generation(40.0)],
+                &emission_factors,
+                MarginalEmissionsSource::ImportedSeries
+            ),
+            450.0
+        );
+    }
+}
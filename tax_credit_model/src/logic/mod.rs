@@ -1 +1,9 @@
+pub mod accounting;
+pub mod certificates;
+pub mod credit_window;
+pub mod degradation;
+pub mod dispatch;
+pub mod finance;
+pub mod marginal_emissions;
+pub mod resampling;
 pub mod simulation;
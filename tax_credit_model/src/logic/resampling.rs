@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use crate::schema::{
+    ercot::{Settlement, SettlementPointLocation},
+    simulation::{EnergySourcePortfolio, GapFillPolicy, GenerationMetric, PowerGrid},
+    time::Timestamp,
+};
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+pub fn fill_power_grid(power_grid: &PowerGrid, policy: GapFillPolicy) -> PowerGrid {
+    let mut filled_grid = PowerGrid::new(power_grid.region.clone());
+
+    for power_plant in &power_grid.power_plants {
+        filled_grid.add_power_plant(
+            power_plant.with_generations(fill_gaps(power_plant.generations(), policy)),
+        );
+    }
+
+    filled_grid
+}
+
+// A series is recorded at the shortest spacing between its generations, which
+// is what gaps are measured against
+pub fn interval_seconds(generations: &[GenerationMetric]) -> Option<i64> {
+    generations
+        .windows(2)
+        .map(|pair| pair[1].time_generated.seconds - pair[0].time_generated.seconds)
+        .filter(|seconds| *seconds > 0)
+        .min()
+}
+
+// Adds a generation, flagged as filled, for every interval missing between the
+// first and last generations of a sorted series. The same hour of the previous
+// day falls back to the previous value when that day is missing too
+pub fn fill_gaps(generations: &[GenerationMetric], policy: GapFillPolicy) -> Vec<GenerationMetric> {
+    let Some(interval_seconds) = interval_seconds(generations) else {
+        return generations.to_vec();
+    };
+
+    if policy == GapFillPolicy::None {
+        return generations.to_vec();
+    }
+
+    let mut filled: Vec<GenerationMetric> = vec![];
+
+    for (index, generation) in generations.iter().enumerate() {
+        if let Some(before) = index.checked_sub(1).map(|index| &generations[index]) {
+            let mut seconds = before.time_generated.seconds + interval_seconds;
+
+            while seconds < generation.time_generated.seconds {
+                let timestamp = Timestamp::new(seconds, 0);
+                let previous_day = filled
+                    .binary_search_by_key(&(seconds - SECONDS_PER_DAY), |filled| {
+                        filled.time_generated.seconds
+                    })
+                    .ok()
+                    .map(|index| &filled[index]);
+                let fill = match (policy, previous_day) {
+                    (GapFillPolicy::Linear, _) => {
+                        GenerationMetric::interpolate(before, generation, &timestamp)
+                    }
+                    (GapFillPolicy::SameHourPreviousDay, Some(previous_day)) => GenerationMetric {
+                        time_generated: timestamp,
+                        filled: true,
+                        ..previous_day.clone()
+                    },
+                    _ => GenerationMetric {
+                        time_generated: timestamp,
+                        filled: true,
+                        ..before.clone()
+                    },
+                };
+
+                filled.push(fill);
+                seconds += interval_seconds;
+            }
+        }
+
+        filled.push(generation.clone());
+    }
+
+    filled
+}
+
+// Each resampled generation starts on a multiple of the interval and sums the
+// share of energy from every generation overlapping it, with prices averaged
+// over the time they cover. Generations last one interval of the series
+pub fn resample(generations: &[GenerationMetric], interval_seconds: i64) -> Vec<GenerationMetric> {
+    let Some(series_seconds) = self::interval_seconds(generations)
+        .or((generations.len() == 1).then_some(interval_seconds))
+    else {
+        return vec![];
+    };
+
+    resample_series(generations, series_seconds, interval_seconds)
+}
+
+// Resamples a series whose generations are known to last series_seconds, which
+// a series with gaps or a single generation cannot be relied on to show
+pub fn resample_series(
+    generations: &[GenerationMetric],
+    series_seconds: i64,
+    interval_seconds: i64,
+) -> Vec<GenerationMetric> {
+    let (Some(first), Some(last)) = (generations.first(), generations.last()) else {
+        return vec![];
+    };
+
+    if interval_seconds <= 0 || series_seconds <= 0 {
+        return vec![];
+    }
+
+    let end = last.time_generated.seconds + series_seconds;
+    let mut resampled = vec![];
+    let mut first_overlap = 0;
+    let mut start = first.time_generated.seconds.div_euclid(interval_seconds) * interval_seconds;
+
+    while start < end {
+        let interval_end = start + interval_seconds;
+
+        while first_overlap < generations.len()
+            && generations[first_overlap].time_generated.seconds + series_seconds <= start
+        {
+            first_overlap += 1;
+        }
+
+        let mut generation = GenerationMetric::new(
+            first.plant_id,
+            &Timestamp::new(start, 0),
+            0.0,
+            EnergySourcePortfolio::default(),
+        );
+        let mut price_seconds = 0.0;
+        let mut real_time_price_seconds = Some(0.0);
+        let mut marginal_emission_rate_seconds = Some(0.0);
+        let mut settlement_point_prices = HashMap::new();
+        let mut day_ahead_settlement_point_prices = HashMap::new();
+        let mut covered_seconds = 0;
+
+        for overlapping in &generations[first_overlap..] {
+            let overlap_start = overlapping.time_generated.seconds;
+
+            if overlap_start >= interval_end {
+                break;
+            }
+
+            let overlap_seconds =
+                interval_end.min(overlap_start + series_seconds) - start.max(overlap_start);
+
+            if overlap_seconds <= 0 {
+                continue;
+            }
+
+            generation.portfolio = EnergySourcePortfolio::merge(
+                &generation.portfolio,
+                &EnergySourcePortfolio::scale(
+                    &overlapping.portfolio,
+                    overlap_seconds as f64 / series_seconds as f64,
+                ),
+            );
+            price_seconds += overlapping.sale_price_usd_per_mwh * overlap_seconds as f64;
+            real_time_price_seconds = real_time_price_seconds
+                .zip(overlapping.real_time_price_usd_per_mwh)
+                .map(|(total, price)| total + price * overlap_seconds as f64);
+            marginal_emission_rate_seconds = marginal_emission_rate_seconds
+                .zip(overlapping.marginal_emission_rate_kg_co2e_per_mwh)
+                .map(|(total, rate)| total + rate * overlap_seconds as f64);
+            add_price_seconds(
+                &mut settlement_point_prices,
+                &overlapping.settlement_point_prices,
+                overlap_seconds,
+            );
+            add_price_seconds(
+                &mut day_ahead_settlement_point_prices,
+                &overlapping.day_ahead_settlement_point_prices,
+                overlap_seconds,
+            );
+            covered_seconds += overlap_seconds;
+            generation.filled |= overlapping.filled;
+            generation.region = overlapping.region.clone();
+
+            if overlapping.settlement == Settlement::Initial {
+                generation.settlement = Settlement::Initial;
+            }
+        }
+
+        if covered_seconds > 0 {
+            generation.sale_price_usd_per_mwh = price_seconds / covered_seconds as f64;
+            generation.real_time_price_usd_per_mwh =
+                real_time_price_seconds.map(|total| total / covered_seconds as f64);
+            generation.marginal_emission_rate_kg_co2e_per_mwh =
+                marginal_emission_rate_seconds.map(|total| total / covered_seconds as f64);
+            generation.settlement_point_prices = average_prices(settlement_point_prices);
+            generation.day_ahead_settlement_point_prices =
+                average_prices(day_ahead_settlement_point_prices);
+            resampled.push(generation);
+        }
+
+        start = interval_end;
+    }
+
+    resampled
+}
+
+fn add_price_seconds(
+    totals: &mut HashMap<SettlementPointLocation, (f64, i64)>,
+    prices: &HashMap<SettlementPointLocation, f64>,
+    seconds: i64,
+) {
+    for (location, price) in prices {
+        let total = totals.entry(*location).or_insert((0.0, 0));
+        total.0 += price * seconds as f64;
+        total.1 += seconds;
+    }
+}
+
+fn average_prices(
+    totals: HashMap<SettlementPointLocation, (f64, i64)>,
+) -> HashMap<SettlementPointLocation, f64> {
+    totals
+        .into_iter()
+        .map(|(location, (price_seconds, seconds))| (location, price_seconds / seconds as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        simulation::{EnergySourcePortfolio, GapFillPolicy, GenerationMetric},
+        time::Timestamp,
+    };
+
+    use super::{fill_gaps, resample};
+
+    fn create_generation(seconds: i64, price: f64) -> GenerationMetric {
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.solar_mwh = price;
+        portfolio.total_electricity_mwh = price;
+
+        GenerationMetric::new(0, &Timestamp::new(seconds, 0), price, portfolio)
+    }
+
+    fn prices(generations: &[GenerationMetric]) -> Vec<(i64, f64, bool)> {
+        generations
+            .iter()
+            .map(|generation| {
+                (
+                    generation.time_generated.seconds,
+                    generation.sale_price_usd_per_mwh,
+                    generation.filled,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_fill_gaps_linearly() {
+        let generations = vec![
+            create_generation(0, 10.0),
+            create_generation(900, 20.0),
+            create_generation(3600, 50.0),
+        ];
+
+        assert_eq!(
+            prices(&fill_gaps(&generations, GapFillPolicy::Linear)),
+            vec![
+                (0, 10.0, false),
+                (900, 20.0, false),
+                (1800, 30.0, true),
+                (2700, 40.0, true),
+                (3600, 50.0, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_fill_gaps_with_previous_value() {
+        let generations = vec![
+            create_generation(0, 10.0),
+            create_generation(900, 15.0),
+            create_generation(2700, 40.0),
+        ];
+
+        assert_eq!(
+            prices(&fill_gaps(&generations, GapFillPolicy::PreviousValue)),
+            vec![
+                (0, 10.0, false),
+                (900, 15.0, false),
+                (1800, 15.0, true),
+                (2700, 40.0, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_fill_gaps_from_same_hour_previous_day() {
+        let generations = (0..=48)
+            .filter(|hour| *hour != 26 && *hour != 27)
+            .map(|hour| create_generation(hour * 3600, hour as f64))
+            .collect::<Vec<GenerationMetric>>();
+
+        let filled = fill_gaps(&generations, GapFillPolicy::SameHourPreviousDay);
+
+        assert_eq!(
+            prices(&filled[25..29]),
+            vec![
+                (90000, 25.0, false),
+                (93600, 2.0, true),
+                (97200, 3.0, true),
+                (100800, 28.0, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_leave_gaps_without_policy() {
+        let generations = vec![
+            create_generation(0, 10.0),
+            create_generation(900, 20.0),
+            create_generation(3600, 50.0),
+        ];
+
+        assert_eq!(fill_gaps(&generations, GapFillPolicy::None), generations);
+    }
+
+    #[test]
+    fn should_resample_to_longer_and_shorter_intervals() {
+        let generations = (0..8)
+            .map(|interval| create_generation(interval * 900, interval as f64))
+            .collect::<Vec<GenerationMetric>>();
+
+        let hourly = resample(&generations, 3600);
+        let back = resample(&hourly, 1800);
+
+        assert_eq!(
+            hourly
+                .iter()
+                .map(|generation| (
+                    generation.sale_price_usd_per_mwh,
+                    generation.portfolio.solar_mwh
+                ))
+                .collect::<Vec<(f64, f64)>>(),
+            vec![(1.5, 6.0), (5.5, 22.0)]
+        );
+        assert_eq!(
+            back.iter()
+                .map(|generation| (
+                    generation.time_generated.seconds,
+                    generation.portfolio.solar_mwh
+                ))
+                .collect::<Vec<(i64, f64)>>(),
+            vec![(0, 3.0), (1800, 3.0), (3600, 11.0), (5400, 11.0)]
+        );
+    }
+}
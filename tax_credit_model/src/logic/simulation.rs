@@ -1,30 +1,52 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::{
-    components::{histogram::HistogramResponse, time_series::TimeSeriesChartResponse},
+    components::{
+        credit_window::CreditWindowPanel, finance::FinancialReportPanel,
+        histogram::HistogramResponse, plant::PlantPurchasePanel,
+        time_series::TimeSeriesChartResponse,
+    },
     persistance::simulation::SimulationClient,
     schema::{
-        electrolyzer::{Electrolyzer, ElectrolyzerId},
+        certificate::{CertificateLedger, EnergyAttributeCertificate},
+        electrolyzer::{Electrolyzer, ElectrolyzerId, ProductionType},
+        emissions::EmissionFactorSet,
         endpoints::Endpoint,
         errors::{Error, Result},
+        finance::FinancialConfiguration,
+        grid::GridRegion,
         histogram::{Histogram, HistogramDataset, Labels},
+        plant::{PlantPurchaseSummary, GRID_MIX_PLANT_NAME},
+        policy::TaxCredit45VRuleset,
         simulation::{
-            EmissionEvent, EnergySourcePortfolio, EnergyTransaction, HydrogenProductionEvent,
-            PowerGrid, PowerPlant, SimulationId, SimulationResult, TaxCredit45V, TaxCredit45VTier,
-            TaxCreditSummary,
+            AccountingMode, CertificateMatching, CreditWindowYear, DispatchDecision,
+            DispatchReason, DispatchStrategyConfiguration, EmissionEvent, EnergySourcePortfolio,
+            EnergyTransaction, ExecuteSimulationRequest, GapFillPolicy, HydrogenProductionEvent,
+            MarginalEmissionsSource, PowerGrid, PowerPlantId, ProcurementMode, SimulationId,
+            SimulationResult, SimulationStatus, SimulationStep, StackEfficiencyEvent,
+            StackReplacementEvent, TaxCredit45V, TaxCredit45VTier, TaxCreditSummary,
         },
-        time::{DateTimeRange, Timestamp},
+        time::{DisplayTimeZone, TimeRange, Timestamp, DATE_TIME_LOCAL_FORMAT},
         time_series::{ChartColor, TimeSeries, TimeSeriesChart, TimeSeriesEntry},
     },
 };
-use chrono::{Datelike, Duration, Timelike};
+use chrono::{Datelike, Duration};
 use serde::{Deserialize, Serialize};
 
-// https://ourworldindata.org/grapher/carbon-dioxide-emissions-factor
-const COAL_MWH_TO_CO2: f64 = 353.88;
-const NATURAL_GAS_MWH_TO_CO2: f64 = 201.96;
-const PETROLEUM_MWH_TO_CO2: f64 = 266.76;
-const BIOMASS_MWH_TO_CO2: f64 = 530.82;
+use super::{
+    accounting::{assign_tax_credits, summarize_tax_credits},
+    certificates::{retire_certificates, uncertified_portfolio},
+    credit_window::{
+        plan_credit_window, project_power_grid, source_time_range, summarize_credit_years,
+    },
+    degradation::{create_stack_efficiency_event, operate_stack, StackState},
+    dispatch::{create_dispatch_strategy, find_generations, DispatchInterval},
+    finance::create_financial_report,
+    marginal_emissions::marginal_emission_rate,
+    resampling::fill_power_grid,
+};
+
+const PROGRESS_REPORT_INTERVALS: usize = 96;
 
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub struct SimulationState {
@@ -35,75 +57,167 @@ pub struct SimulationState {
     pub transactions: Vec<EnergyTransaction>,
     pub tax_credit: Vec<TaxCredit45V>,
     pub tax_credit_summary: TaxCreditSummary,
+    pub stack: StackState,
+    pub stack_efficiencies: Vec<StackEfficiencyEvent>,
+    pub stack_replacements: Vec<StackReplacementEvent>,
+    pub replacement_cost_usd: f64,
+    pub dispatch_decisions: Vec<DispatchDecision>,
+    pub dispatch_strategy: DispatchStrategyConfiguration,
+    pub financial_configuration: FinancialConfiguration,
+    pub accounting_mode: AccountingMode,
+    pub emission_factor_set: EmissionFactorSet,
+    pub tax_credit_ruleset: TaxCredit45VRuleset,
+    pub credit_window_years: Vec<CreditWindowYear>,
+    pub simulation_step: SimulationStep,
+    pub display_time_zone: DisplayTimeZone,
+    #[serde(default)]
+    pub procurement_mode: ProcurementMode,
+    #[serde(default)]
+    pub gap_fill_policy: GapFillPolicy,
+    #[serde(default)]
+    pub grid_region: GridRegion,
+    #[serde(default)]
+    pub marginal_emissions_source: MarginalEmissionsSource,
+    #[serde(default)]
+    pub marginal_emissions: Vec<EmissionEvent>,
+    #[serde(default)]
+    pub certificate_matching: CertificateMatching,
+    #[serde(default)]
+    pub certificate_ledger: CertificateLedger,
+    pub status: SimulationStatus,
+}
+
+// Everything a simulation is run with, gathered from the request and the
+// stores it names
+pub struct SimulationContext<'a> {
+    pub power_grid: &'a PowerGrid,
+    pub electrolyzer: &'a Electrolyzer,
+    pub emission_factors: &'a EmissionFactorSet,
+    pub certificates: &'a [EnergyAttributeCertificate],
+    pub request: &'a ExecuteSimulationRequest,
 }
 
 pub fn simulate(
     simulation_id: SimulationId,
-    power_grid: &PowerGrid,
-    electrolyzer: &Electrolyzer,
-    time_range: &DateTimeRange,
-    simulation_client: &Box<dyn SimulationClient>,
+    context: &SimulationContext,
+    simulation_client: &dyn SimulationClient,
+    report_progress: &dyn Fn(f64) -> Result<()>,
 ) -> Result<SimulationResult> {
-    let time_range = time_range.parse("%Y-%m-%dT%H:%M")?;
-    let mut current_timestamp = time_range.start.to_utc_date_time()?;
-    let mut end_timestamp = time_range.end.to_utc_date_time()?;
-    let increment = Duration::minutes(15);
+    let SimulationContext {
+        power_grid,
+        electrolyzer,
+        emission_factors,
+        certificates,
+        request,
+    } = *context;
+    let mut real_time_fallbacks = BTreeMap::new();
     let mut state = simulation_client.get_simulation_state(&simulation_id)?;
     state.electrolyzer_id = electrolyzer.id;
+    state.dispatch_strategy = request.dispatch_strategy.clone();
+    state.financial_configuration = request.financial.clone();
+    state.accounting_mode = request.accounting_mode;
+    state.emission_factor_set = emission_factors.clone();
+    state.tax_credit_ruleset = TaxCredit45VRuleset::new(&request.tax_credit_policy);
+    state.simulation_step = request.simulation_step;
+    state.display_time_zone = request.display_time_zone;
+    state.procurement_mode = request.procurement_mode;
+    state.gap_fill_policy = request.gap_fill_policy;
+    state.grid_region = power_grid.region.clone();
+    state.marginal_emissions_source = request.marginal_emissions_source;
+    state.certificate_matching = request.certificate_matching;
 
-    if current_timestamp.timestamp() > end_timestamp.timestamp() {
-        return Err(Error::invalid_argument(
-            "Simulation start must be before end time",
-        ));
+    if request.certificate_matching == CertificateMatching::Hourly {
+        state.certificate_ledger = CertificateLedger::new(certificates.to_vec());
     }
 
-    if current_timestamp.minute() % 15 != 0 {
-        current_timestamp += Duration::minutes(15 - current_timestamp.minute() as i64 % 15);
-    }
+    let first_interval = state.hydrogen_productions.len();
 
-    if end_timestamp.minute() % 15 != 0 {
-        end_timestamp += Duration::minutes(15 - end_timestamp.minute() as i64 % 15);
-    }
+    if request.credit_window.enabled {
+        state.credit_window_years = plan_credit_window(power_grid, &request.credit_window)?;
+        let mut progress = SimulationProgress::new(
+            state
+                .credit_window_years
+                .iter()
+                .map(|window_year| {
+                    count_intervals(&window_year.start, &window_year.end, state.simulation_step)
+                })
+                .sum(),
+            report_progress,
+        );
 
-    while current_timestamp < end_timestamp {
-        let mut transactions = make_optimal_transactions(
-            simulation_id,
-            &Timestamp::from(current_timestamp),
-            electrolyzer,
-            power_grid,
-        )?;
-        let portfolio = create_energy_source_portfolio(&transactions);
-        let emission_event = create_emission_event(
-            simulation_id,
-            &Timestamp::from(current_timestamp),
-            electrolyzer,
-            &portfolio,
+        for window_year in state.credit_window_years.clone() {
+            let year_grid = project_power_grid(
+                &price_power_grid(
+                    power_grid,
+                    electrolyzer,
+                    request,
+                    &source_time_range(&window_year)?,
+                    &mut real_time_fallbacks,
+                )?,
+                &window_year,
+            )?;
+            let time_range = TimeRange {
+                start: window_year.start,
+                end: window_year.end,
+            };
+
+            simulate_time_range(
+                &year_grid,
+                electrolyzer,
+                emission_factors,
+                request,
+                &time_range,
+                &mut state,
+                &mut progress,
+            )?;
+        }
+    } else {
+        let time_range = request
+            .simulation_time_range
+            .parse(DATE_TIME_LOCAL_FORMAT, request.display_time_zone)?;
+        let mut progress = SimulationProgress::new(
+            count_intervals(&time_range.start, &time_range.end, state.simulation_step),
+            report_progress,
         );
-        let hydrogen_production_event = create_hydrogen_production_event(
-            simulation_id,
-            &Timestamp::from(current_timestamp),
+
+        simulate_time_range(
+            &price_power_grid(
+                power_grid,
+                electrolyzer,
+                request,
+                &time_range,
+                &mut real_time_fallbacks,
+            )?,
             electrolyzer,
-            &portfolio,
+            emission_factors,
+            request,
+            &time_range,
+            &mut state,
+            &mut progress,
         )?;
-        let tax_credit = calculate_tax_credit(&emission_event, &hydrogen_production_event);
-
-        match tax_credit.tier {
-            TaxCredit45VTier::Max => state.tax_credit_summary.credit_hours_full += 0.25,
-            TaxCredit45VTier::Tier1 => state.tax_credit_summary.credit_hours_33 += 0.25,
-            TaxCredit45VTier::Tier2 => state.tax_credit_summary.credit_hours_25 += 0.25,
-            TaxCredit45VTier::Tier3 => state.tax_credit_summary.credit_hours_20 += 0.25,
-            TaxCredit45VTier::None => state.tax_credit_summary.credit_hours_none += 0.25,
-        }
-
-        state.transactions.append(&mut transactions);
-        state.emissions.push(emission_event);
-        state.hydrogen_productions.push(hydrogen_production_event);
-        state.tax_credit.push(tax_credit);
-
-        current_timestamp += increment;
     }
 
+    let mut tax_credits = assign_tax_credits(
+        &state.emissions[first_interval..],
+        &state.hydrogen_productions[first_interval..],
+        request.accounting_mode,
+        &state.tax_credit_ruleset,
+    )?;
+    summarize_tax_credits(
+        &mut state.tax_credit_summary,
+        &tax_credits,
+        state.simulation_step.hours(),
+    );
+    state.tax_credit.append(&mut tax_credits);
+    report_progress(1.0)?;
+
+    // The job runner owns the status, a cancel may have landed since the last report
+    state.status = simulation_client
+        .get_simulation_state(&simulation_id)?
+        .status;
     simulation_client.update(&state)?;
+    let financial_report = create_financial_report(electrolyzer, &state, &request.financial)?;
+    let credit_years = summarize_credit_years(&state)?;
     let mut energy_costs_time_series = TimeSeries {
         color: ChartColor::Blue,
         label: String::from("Energy Cost"),
@@ -120,7 +234,9 @@ pub fn simulate(
                 aggregation
             })
             .iter()
-            .map(|(key, value)| TimeSeriesEntry::render(*value, key, ChartColor::Blue))
+            .map(|(key, value)| {
+                TimeSeriesEntry::render(*value, key, ChartColor::Blue, state.display_time_zone)
+            })
             .collect::<Result<Vec<TimeSeriesEntry>>>()?,
     };
     energy_costs_time_series
@@ -129,7 +245,46 @@ pub fn simulate(
 
     Ok(SimulationResult {
         tax_credit_summary: state.tax_credit_summary.clone(),
+        dispatch_strategy: state.dispatch_strategy.to_string(),
+        accounting_mode: state.accounting_mode.to_string(),
+        procurement_mode: state.procurement_mode.to_string(),
+        gap_fill_policy: state.gap_fill_policy.to_string(),
+        grid_region: state.grid_region.to_string(),
+        marginal_emissions_source: state.marginal_emissions_source.to_string(),
+        certificate_matching: state.certificate_matching.to_string(),
+        retired_certificate_mwh: state.certificate_ledger.retired_mwh(),
+        average_kg_co2e_per_kg_hydrogen: emissions_intensity(
+            &state.emissions,
+            &state.hydrogen_productions,
+        ),
+        marginal_kg_co2e_per_kg_hydrogen: emissions_intensity(
+            &state.marginal_emissions,
+            &state.hydrogen_productions,
+        ),
+        gap_filled_intervals: state
+            .dispatch_decisions
+            .iter()
+            .filter(|decision| decision.gap_filled)
+            .count(),
+        real_time_fallback_intervals: real_time_fallbacks.len(),
+        real_time_fallback_reasons: real_time_fallbacks
+            .into_values()
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect(),
+        emission_factor_set: state.emission_factor_set.to_string(),
+        tax_credit_ruleset: state.tax_credit_ruleset.to_string(),
         emissions: produce_emissions_graph(&state)?,
+        stack_efficiency: produce_stack_efficiency_graph(&state)?,
+        dispatch_histogram: produce_dispatch_histogram(&state),
+        stack_replacement_count: state.stack_replacements.len(),
+        replacement_cost_usd: state.replacement_cost_usd,
+        financial_report: FinancialReportPanel::render(financial_report, &request.financial),
+        credit_years: CreditWindowPanel::render(credit_years),
+        plant_purchases: PlantPurchasePanel::render(summarize_plant_purchases(
+            &state.transactions,
+            power_grid,
+        )),
         hydrogen_productions: TimeSeriesChartResponse::render(
             TimeSeriesChart::render(
                 "Hydrogen Production Over Time",
@@ -143,6 +298,7 @@ pub fn simulate(
                             production.kg_hydrogen,
                             &production.production_timestamp,
                             ChartColor::Blue,
+                            state.display_time_zone,
                         )
                     },
                 )?],
@@ -181,80 +337,307 @@ pub fn simulate(
     })
 }
 
+// Only the grid data the time range is simulated with is priced, so a month
+// outside it may be missing prices
+fn price_power_grid(
+    power_grid: &PowerGrid,
+    electrolyzer: &Electrolyzer,
+    request: &ExecuteSimulationRequest,
+    time_range: &TimeRange,
+    real_time_fallbacks: &mut BTreeMap<Timestamp, String>,
+) -> Result<PowerGrid> {
+    Ok(fill_power_grid(
+        &power_grid.priced_at(
+            electrolyzer.settlement_point,
+            request.procurement_mode,
+            time_range,
+            real_time_fallbacks,
+        )?,
+        request.gap_fill_policy,
+    ))
+}
+
+// Reports once per simulated day so a long run can be followed and cancelled
+// without the reporting dominating the run
+struct SimulationProgress<'a> {
+    completed_intervals: usize,
+    total_intervals: usize,
+    report: &'a dyn Fn(f64) -> Result<()>,
+}
+
+impl<'a> SimulationProgress<'a> {
+    fn new(total_intervals: usize, report: &'a dyn Fn(f64) -> Result<()>) -> Self {
+        Self {
+            completed_intervals: 0,
+            total_intervals,
+            report,
+        }
+    }
+
+    fn advance(&mut self) -> Result<()> {
+        self.completed_intervals += 1;
+
+        if !self
+            .completed_intervals
+            .is_multiple_of(PROGRESS_REPORT_INTERVALS)
+        {
+            return Ok(());
+        }
+
+        (self.report)(self.completed_intervals as f64 / self.total_intervals.max(1) as f64)
+    }
+}
+
+fn count_intervals(start: &Timestamp, end: &Timestamp, step: SimulationStep) -> usize {
+    ((end.seconds - start.seconds).max(0) as usize).div_ceil(step.seconds() as usize)
+}
+
+// Rounds up to the next step boundary so every interval lines up with the
+// settlement intervals of the grid data
+fn align_to_step(seconds: i64, step: SimulationStep) -> i64 {
+    let remainder = seconds.rem_euclid(step.seconds());
+
+    if remainder == 0 {
+        seconds
+    } else {
+        seconds + step.seconds() - remainder
+    }
+}
+
+fn simulate_time_range(
+    power_grid: &PowerGrid,
+    electrolyzer: &Electrolyzer,
+    emission_factors: &EmissionFactorSet,
+    request: &ExecuteSimulationRequest,
+    time_range: &TimeRange,
+    state: &mut SimulationState,
+    progress: &mut SimulationProgress,
+) -> Result<()> {
+    let simulation_id = state.id;
+    let step = request.simulation_step;
+    let interval_hours = step.hours();
+    let increment = Duration::minutes(step.minutes());
+    let dispatch_strategy = create_dispatch_strategy(&request.dispatch_strategy, power_grid)?;
+
+    if time_range.start.seconds > time_range.end.seconds {
+        return Err(Error::invalid_argument(
+            "Simulation start must be before end time",
+        ));
+    }
+
+    let mut current_timestamp =
+        Timestamp::new(align_to_step(time_range.start.seconds, step), 0).to_utc_date_time()?;
+    let end_timestamp =
+        Timestamp::new(align_to_step(time_range.end.seconds, step), 0).to_utc_date_time()?;
+
+    while current_timestamp < end_timestamp {
+        let generations = find_generations(power_grid, &Timestamp::from(current_timestamp), step)?;
+        let gap_filled = generations.iter().any(|generation| generation.filled);
+        let marginal_emission_rate = marginal_emission_rate(
+            &generations,
+            emission_factors,
+            request.marginal_emissions_source,
+        );
+        let (mut transactions, mut dispatch_decision) =
+            dispatch_strategy.dispatch(&DispatchInterval {
+                simulation_id,
+                timestamp: Timestamp::from(current_timestamp),
+                interval_hours,
+                electrolyzer,
+                stack: &state.stack,
+                emission_factors,
+                ruleset: &state.tax_credit_ruleset,
+                power_grid,
+                generations,
+            })?;
+        dispatch_decision.gap_filled = gap_filled;
+        let portfolio = create_energy_source_portfolio(&transactions);
+        let retired_mwh = match request.certificate_matching {
+            CertificateMatching::None => 0.0,
+            CertificateMatching::Hourly => retire_certificates(
+                &mut state.certificate_ledger,
+                simulation_id,
+                &Timestamp::from(current_timestamp),
+                &power_grid.region,
+                portfolio.total_electricity_mwh,
+            ),
+        };
+        let emission_event = create_emission_event(
+            simulation_id,
+            &Timestamp::from(current_timestamp),
+            electrolyzer,
+            emission_factors,
+            power_grid,
+            &transactions,
+            retired_mwh,
+        );
+        // Certificates change what is claimed rather than what the extra load
+        // causes, so marginal emissions cover all the energy bought
+        let marginal_emission_event = EmissionEvent {
+            amount_emitted_kg: portfolio.total_electricity_mwh * marginal_emission_rate,
+            ..emission_event.clone()
+        };
+        let hydrogen_production_event = create_hydrogen_production_event(
+            simulation_id,
+            &Timestamp::from(current_timestamp),
+            electrolyzer,
+            &state.stack,
+            &portfolio,
+            interval_hours,
+        )?;
+        let stack_efficiency_event = create_stack_efficiency_event(
+            simulation_id,
+            &Timestamp::from(current_timestamp),
+            electrolyzer,
+            &state.stack,
+        );
+        let hours_operated = if hydrogen_production_event.kg_hydrogen > 0.0 {
+            interval_hours
+        } else {
+            0.0
+        };
+        let stack_replacement = operate_stack(
+            simulation_id,
+            &Timestamp::from(current_timestamp),
+            electrolyzer,
+            &mut state.stack,
+            hours_operated,
+        );
+
+        state.transactions.append(&mut transactions);
+        state.emissions.push(emission_event);
+        state.marginal_emissions.push(marginal_emission_event);
+        state.hydrogen_productions.push(hydrogen_production_event);
+        state.stack_efficiencies.push(stack_efficiency_event);
+        state.dispatch_decisions.push(dispatch_decision);
+
+        if let Some(stack_replacement) = stack_replacement {
+            state.replacement_cost_usd += stack_replacement.cost_usd;
+            state.stack_replacements.push(stack_replacement);
+        }
+
+        current_timestamp += increment;
+        progress.advance()?;
+    }
+
+    Ok(())
+}
+
 fn produce_emissions_graph(state: &SimulationState) -> Result<TimeSeriesChartResponse> {
     Ok(TimeSeriesChartResponse::render(
         TimeSeriesChart::render(
             "Emissions Over Time",
             Labels::render("Simulation Date", "kg (CO2)"),
-            vec![TimeSeries::render(
-                "CO2 Emissions",
-                ChartColor::Blue,
-                state.emissions.iter().zip(&state.tax_credit).collect(),
-                |(emission, tax_credit)| {
-                    TimeSeriesEntry::render(
-                        emission.amount_emitted_kg,
-                        &emission.emission_timestamp,
-                        match tax_credit.tier {
-                            TaxCredit45VTier::Max => ChartColor::Green,
-                            TaxCredit45VTier::Tier1 => ChartColor::Chartreuse,
-                            TaxCredit45VTier::Tier2 => ChartColor::Yellow,
-                            TaxCredit45VTier::Tier3 => ChartColor::Orange,
-                            TaxCredit45VTier::None => ChartColor::Red,
-                        },
-                    )
-                },
-            )?],
+            vec![
+                TimeSeries::render(
+                    "CO2 Emissions",
+                    ChartColor::Blue,
+                    state.emissions.iter().zip(&state.tax_credit).collect(),
+                    |(emission, tax_credit)| {
+                        TimeSeriesEntry::render(
+                            emission.amount_emitted_kg,
+                            &emission.emission_timestamp,
+                            match tax_credit.tier {
+                                TaxCredit45VTier::Max => ChartColor::Green,
+                                TaxCredit45VTier::Tier1 => ChartColor::Chartreuse,
+                                TaxCredit45VTier::Tier2 => ChartColor::Yellow,
+                                TaxCredit45VTier::Tier3 => ChartColor::Orange,
+                                TaxCredit45VTier::None => ChartColor::Red,
+                            },
+                            state.display_time_zone,
+                        )
+                    },
+                )?,
+                TimeSeries::render(
+                    "Marginal CO2 Emissions",
+                    ChartColor::Blue,
+                    state.marginal_emissions.iter().collect(),
+                    |emission| {
+                        TimeSeriesEntry::render(
+                            emission.amount_emitted_kg,
+                            &emission.emission_timestamp,
+                            ChartColor::Blue,
+                            state.display_time_zone,
+                        )
+                    },
+                )?,
+            ],
         ),
         Endpoint::FetchEmissions,
         HashMap::from([("simulation_id", state.id.to_string())]),
     ))
 }
 
-fn make_optimal_transactions(
-    simulation_id: SimulationId,
-    timestamp: &Timestamp,
-    electrolyzer: &Electrolyzer,
-    power_grid: &PowerGrid,
-) -> Result<Vec<EnergyTransaction>> {
-    power_grid
-        .power_plants
-        .iter()
-        .map(|power_plant| purchase(simulation_id, electrolyzer, power_plant, 2.0, timestamp))
-        .collect()
+fn produce_stack_efficiency_graph(state: &SimulationState) -> Result<TimeSeriesChartResponse> {
+    Ok(TimeSeriesChartResponse::render(
+        TimeSeriesChart::render(
+            "Stack Efficiency Over Time",
+            Labels::render("Simulation Date", "Efficiency (%)"),
+            vec![
+                TimeSeries::render(
+                    "Stack Efficiency",
+                    ChartColor::Blue,
+                    state.stack_efficiencies.iter().collect(),
+                    |efficiency| {
+                        TimeSeriesEntry::render(
+                            efficiency.efficiency * 100.0,
+                            &efficiency.timestamp,
+                            ChartColor::Blue,
+                            state.display_time_zone,
+                        )
+                    },
+                )?,
+                TimeSeries::render(
+                    "Stack Replacements",
+                    ChartColor::Red,
+                    state.stack_replacements.iter().collect(),
+                    |replacement| {
+                        TimeSeriesEntry::render(
+                            replacement.efficiency_at_replacement * 100.0,
+                            &replacement.replacement_timestamp,
+                            ChartColor::Red,
+                            state.display_time_zone,
+                        )
+                    },
+                )?,
+            ],
+        ),
+        Endpoint::FetchStackEfficiency,
+        HashMap::from([("simulation_id", state.id.to_string())]),
+    ))
 }
 
-fn purchase(
-    simulation_id: SimulationId,
-    electrolyzer: &Electrolyzer,
-    power_plant: &PowerPlant,
-    amount_mwh: f64,
-    timestamp: &Timestamp,
-) -> Result<EnergyTransaction> {
-    let purchase_datetime = timestamp.to_utc_date_time()?;
-    let generation = power_plant
-        .generations
+fn produce_dispatch_histogram(state: &SimulationState) -> HistogramResponse {
+    let reasons = DispatchReason::all();
+    let hours_by_reason = state.dispatch_decisions.iter().fold(
+        HashMap::new(),
+        |mut aggregation: HashMap<DispatchReason, f64>, decision| {
+            *aggregation.entry(decision.reason).or_default() += state.simulation_step.hours();
+
+            aggregation
+        },
+    );
+    let keys = reasons
         .iter()
-        .find(|generation| {
-            generation.time_generated.to_utc_date_time().map_or_else(
-                |_| false,
-                |generation_datetime| {
-                    purchase_datetime.year() == generation_datetime.year()
-                        && purchase_datetime.month() == generation_datetime.month()
-                        && purchase_datetime.day() == generation_datetime.day()
-                        && purchase_datetime.hour() == generation_datetime.hour()
-                },
-            )
-        })
-        .ok_or_else(|| Error::not_found(&format!("Generation not found for timestep")))?;
+        .map(|reason| reason.to_string())
+        .collect::<Vec<String>>();
 
-    Ok(EnergyTransaction {
-        simulation_id,
-        electrolyzer_id: electrolyzer.id,
-        plant_id: generation.plant_id,
-        timestamp: timestamp.clone(),
-        price_usd: generation.sale_price_usd_per_mwh * amount_mwh,
-        portfolio: EnergySourcePortfolio::scale_to_amount(&generation.portfolio, amount_mwh)?,
-    })
+    HistogramResponse::render(
+        Endpoint::FetchDispatchHistogram,
+        HashMap::from([("simulation_id", state.id.to_string())]),
+        Histogram::render(
+            "Dispatch Decisions",
+            Labels::render("Decision", "Hours"),
+            keys.iter().map(|key| key.as_str()).collect(),
+            vec![HistogramDataset::render(
+                "Dispatch Breakdown",
+                reasons
+                    .iter()
+                    .map(|reason| hours_by_reason.get(reason).copied().unwrap_or_default())
+                    .collect(),
+            )],
+        ),
+    )
 }
 
 fn create_energy_source_portfolio(transactions: &Vec<EnergyTransaction>) -> EnergySourcePortfolio {
@@ -264,17 +647,90 @@ fn create_energy_source_portfolio(transactions: &Vec<EnergyTransaction>) -> Ener
     )
 }
 
+// Energy bought from the grid mix has no single fuel, registered plants are
+// listed with the fuel they run on
+fn summarize_plant_purchases(
+    transactions: &[EnergyTransaction],
+    power_grid: &PowerGrid,
+) -> Vec<PlantPurchaseSummary> {
+    let mut summaries: BTreeMap<PowerPlantId, PlantPurchaseSummary> = BTreeMap::new();
+
+    for transaction in transactions {
+        let summary = summaries.entry(transaction.plant_id).or_insert_with(|| {
+            let power_plant = power_grid
+                .power_plants
+                .iter()
+                .find(|power_plant| power_plant.plant_id == transaction.plant_id);
+
+            PlantPurchaseSummary {
+                plant_id: transaction.plant_id,
+                name: power_plant
+                    .map_or(GRID_MIX_PLANT_NAME, |power_plant| power_plant.name())
+                    .to_string(),
+                fuel_type: power_plant
+                    .and_then(|power_plant| power_plant.details.as_ref())
+                    .map_or(String::from("Mixed"), |details| {
+                        details.fuel_type.to_string()
+                    }),
+                ..Default::default()
+            }
+        });
+        summary.purchased_mwh += transaction.portfolio.total_electricity_mwh;
+        summary.energy_usd += transaction.price_usd;
+    }
+
+    summaries.into_values().collect()
+}
+
+fn emissions_intensity(
+    emissions: &[EmissionEvent],
+    hydrogen_productions: &[HydrogenProductionEvent],
+) -> f64 {
+    let kg_hydrogen: f64 = hydrogen_productions
+        .iter()
+        .map(|production| production.kg_hydrogen)
+        .sum();
+
+    if kg_hydrogen <= 0.0 {
+        return 0.0;
+    }
+
+    emissions
+        .iter()
+        .map(|emission| emission.amount_emitted_kg)
+        .sum::<f64>()
+        / kg_hydrogen
+}
+
+// Each purchase emits at its plant's factor, with the retired certificates
+// claiming the same share of every purchase as zero-emission
 fn create_emission_event(
     simulation_id: SimulationId,
     timestamp: &Timestamp,
     electrolyzer: &Electrolyzer,
-    portfolio: &EnergySourcePortfolio,
+    emission_factors: &EmissionFactorSet,
+    power_grid: &PowerGrid,
+    transactions: &[EnergyTransaction],
+    retired_mwh: f64,
 ) -> EmissionEvent {
-    let mut amount_emitted_kg = 0.0;
-    amount_emitted_kg += portfolio.natural_gas_mwh * NATURAL_GAS_MWH_TO_CO2;
-    amount_emitted_kg += portfolio.coal_mwh * COAL_MWH_TO_CO2;
-    amount_emitted_kg += portfolio.petroleum_mwh * PETROLEUM_MWH_TO_CO2;
-    amount_emitted_kg += portfolio.biomass_mwh * BIOMASS_MWH_TO_CO2;
+    let purchased_mwh: f64 = transactions
+        .iter()
+        .map(|transaction| transaction.portfolio.total_electricity_mwh)
+        .sum();
+    let amount_emitted_kg = transactions
+        .iter()
+        .map(|transaction| {
+            let retired_share_mwh = match purchased_mwh > 0.0 {
+                true => retired_mwh * transaction.portfolio.total_electricity_mwh / purchased_mwh,
+                false => 0.0,
+            };
+
+            emission_factors.purchase_emitted_kg(
+                power_grid.plant_details(transaction.plant_id),
+                &uncertified_portfolio(&transaction.portfolio, retired_share_mwh),
+            )
+        })
+        .sum();
 
     EmissionEvent {
         simulation_id,
@@ -288,50 +744,86 @@ fn create_hydrogen_production_event(
     simulation_id: SimulationId,
     timestamp: &Timestamp,
     electrolyzer: &Electrolyzer,
+    stack: &StackState,
     portfolio: &EnergySourcePortfolio,
+    interval_hours: f64,
 ) -> Result<HydrogenProductionEvent> {
+    let kg_hydrogen = match electrolyzer.production_type {
+        ProductionType::Constant => {
+            f64::min(
+                portfolio.total_electricity_mwh,
+                electrolyzer.capacity_mw * interval_hours,
+            ) * electrolyzer.production.conversion_rate
+        }
+        ProductionType::Variable => variable_hydrogen_production(
+            electrolyzer,
+            portfolio.total_electricity_mwh,
+            interval_hours,
+        ),
+    };
+
     Ok(HydrogenProductionEvent {
         simulation_id,
         electrolyzer_id: electrolyzer.id,
         production_timestamp: timestamp.clone(),
-        kg_hydrogen: f64::min(portfolio.total_electricity_mwh, electrolyzer.capacity_mw)
-            * electrolyzer.production.conversion_rate,
+        kg_hydrogen: kg_hydrogen * stack.efficiency,
     })
 }
 
-fn calculate_tax_credit(
-    emission: &EmissionEvent,
-    hydrogen_production: &HydrogenProductionEvent,
-) -> TaxCredit45V {
-    let mut tier = TaxCredit45VTier::None;
-    let co2_per_h2 = emission.amount_emitted_kg / hydrogen_production.kg_hydrogen;
+// Runs below the minimum stable load trip the electrolyzer, so the energy
+// bought in that interval produces nothing
+fn variable_hydrogen_production(
+    electrolyzer: &Electrolyzer,
+    energy_mwh: f64,
+    interval_hours: f64,
+) -> f64 {
+    let interval_capacity_mwh = electrolyzer.capacity_mw * interval_hours;
 
-    if 2.5 <= co2_per_h2 && co2_per_h2 < 4.0 {
-        tier = TaxCredit45VTier::Tier3;
-    } else if 1.5 <= co2_per_h2 && co2_per_h2 < 2.5 {
-        tier = TaxCredit45VTier::Tier2;
-    } else if 0.45 <= co2_per_h2 && co2_per_h2 < 1.5 {
-        tier = TaxCredit45VTier::Tier1;
-    } else if co2_per_h2 < 0.45 {
-        tier = TaxCredit45VTier::Max
+    if interval_capacity_mwh <= 0.0 {
+        return 0.0;
     }
 
-    let value = tier.value();
+    let energy_mwh = f64::min(energy_mwh, interval_capacity_mwh);
+    let load_fraction = energy_mwh / interval_capacity_mwh;
+    let variable_production = &electrolyzer.variable_production;
 
-    TaxCredit45V {
-        tier,
-        total_usd: value * hydrogen_production.kg_hydrogen,
+    if load_fraction <= 0.0 || load_fraction < variable_production.minimum_stable_load {
+        return 0.0;
     }
+
+    energy_mwh * variable_production.conversion_rate_at(load_fraction)
+}
+
+pub fn calculate_tax_credit(
+    emission: &EmissionEvent,
+    hydrogen_production: &HydrogenProductionEvent,
+    ruleset: &TaxCredit45VRuleset,
+) -> Result<TaxCredit45V> {
+    let co2_per_h2 = emission.amount_emitted_kg / hydrogen_production.kg_hydrogen;
+    let tier = ruleset.tier(co2_per_h2);
+    let year = hydrogen_production
+        .production_timestamp
+        .to_utc_date_time()?
+        .year();
+
+    Ok(TaxCredit45V {
+        tier,
+        total_usd: ruleset.credit_usd_per_kg(tier, year) * hydrogen_production.kg_hydrogen,
+    })
 }
 
 #[cfg(test)]
 mod test {
     use pretty_assertions::assert_eq;
 
+    use crate::logic::degradation::StackState;
     use crate::schema::{
-        electrolyzer::{ConstantProduction, Electrolyzer, ProductionType},
+        electrolyzer::{ConstantProduction, Electrolyzer, ProductionType, VariableProduction},
+        emissions::{EmissionFactor, EmissionFactorSet},
+        plant::PowerPlantDetails,
+        policy::TaxCredit45VRuleset,
         simulation::{
-            EmissionEvent, EnergySourcePortfolio, EnergyTransaction, GenerationMetric,
+            EmissionEvent, EnergySource, EnergySourcePortfolio, EnergyTransaction,
             HydrogenProductionEvent, PowerGrid, PowerPlant, TaxCredit45V, TaxCredit45VTier,
         },
         time::Timestamp,
@@ -339,72 +831,10 @@ mod test {
 
     use super::{
         calculate_tax_credit, create_emission_event, create_energy_source_portfolio,
-        create_hydrogen_production_event, make_optimal_transactions, NATURAL_GAS_MWH_TO_CO2,
+        create_hydrogen_production_event, summarize_plant_purchases,
     };
 
-    // Note that this is it's own piece of work and should be refactored out
-    // Keeping here for now to get working integration test
-    #[test]
-    fn should_make_optimal_transaction() {
-        let simulation_id = 0;
-        let timestamp = Timestamp::default();
-        let electrolyzer = Electrolyzer::default();
-        let mut power_grid = PowerGrid::default();
-        let mut power_plant = PowerPlant::default();
-        let mut generation_portfolio = EnergySourcePortfolio::default();
-        generation_portfolio.total_electricity_mwh = 4.0;
-        generation_portfolio.natural_gas_mwh = 4.0;
-        let mut transaction_portfolio = EnergySourcePortfolio::default();
-        transaction_portfolio.total_electricity_mwh = 2.0;
-        transaction_portfolio.natural_gas_mwh = 2.0;
-        power_plant.add_generation(GenerationMetric::new(
-            0,
-            &timestamp,
-            1.0,
-            generation_portfolio,
-        ));
-        power_grid.add_power_plant(power_plant);
-        let expected_transactions = vec![EnergyTransaction {
-            simulation_id: 0,
-            electrolyzer_id: 0,
-            plant_id: 0,
-            timestamp: Timestamp::new(timestamp.seconds, timestamp.nanos),
-            price_usd: 2.0,
-            portfolio: transaction_portfolio,
-        }];
-
-        let transactions =
-            make_optimal_transactions(simulation_id, &timestamp, &electrolyzer, &power_grid)
-                .unwrap();
-
-        assert_eq!(transactions, expected_transactions);
-    }
-
-    // TODO: Refactor this as well like the rest of transaction optimization
-    // also should return a result instead of panicing
-    #[test]
-    #[should_panic]
-    fn should_fail_to_make_transaction_missing_generation() {
-        let simulation_id = 0;
-        let timestamp = Timestamp::default();
-        let electrolyzer = Electrolyzer::default();
-        let mut power_grid = PowerGrid::default();
-        let mut power_plant = PowerPlant::default();
-        let mut generation_portfolio = EnergySourcePortfolio::default();
-        generation_portfolio.total_electricity_mwh = 4.0;
-        generation_portfolio.natural_gas_mwh = 2.0;
-        power_plant.add_generation(GenerationMetric::new(
-            0,
-            &Timestamp::new(timestamp.seconds, timestamp.nanos),
-            1.0,
-            generation_portfolio,
-        ));
-        power_grid.add_power_plant(power_plant);
-        let future_timestamp = Timestamp::new(timestamp.seconds + 3600, timestamp.nanos);
-
-        make_optimal_transactions(simulation_id, &future_timestamp, &electrolyzer, &power_grid)
-            .unwrap();
-    }
+    const NATURAL_GAS_MWH_TO_CO2: f64 = 201.96;
 
     #[test]
     fn should_calculate_energy_portfolio() {
@@ -439,21 +869,102 @@ mod test {
         assert_eq!(portfolio, expected_portfolio);
     }
 
+    #[test]
+    fn should_summarize_purchases_by_plant() {
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(PowerPlant::new(0, vec![]));
+        let mut wind_farm = PowerPlant::new(3, vec![]);
+        wind_farm.details = Some(PowerPlantDetails {
+            plant_id: 3,
+            name: String::from("Wind Farm"),
+            fuel_type: EnergySource::Wind,
+            ..Default::default()
+        });
+        power_grid.add_power_plant(wind_farm);
+        let transactions = [(3, 2.0, 10.0), (0, 1.0, 30.0), (3, 1.0, 5.0)]
+            .iter()
+            .map(|(plant_id, mwh, price_usd)| {
+                let mut portfolio = EnergySourcePortfolio::default();
+                portfolio.total_electricity_mwh = *mwh;
+
+                EnergyTransaction {
+                    simulation_id: 0,
+                    electrolyzer_id: 0,
+                    plant_id: *plant_id,
+                    timestamp: Timestamp::default(),
+                    price_usd: *price_usd,
+                    portfolio,
+                }
+            })
+            .collect::<Vec<EnergyTransaction>>();
+
+        let summaries = summarize_plant_purchases(&transactions, &power_grid);
+
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| (
+                    summary.name.as_str(),
+                    summary.fuel_type.as_str(),
+                    summary.purchased_mwh,
+                    summary.energy_usd
+                ))
+                .collect::<Vec<(&str, &str, f64, f64)>>(),
+            vec![
+                ("Grid mix", "Mixed", 1.0, 30.0),
+                ("Wind Farm", "Wind", 3.0, 15.0)
+            ]
+        );
+    }
+
     #[test]
     fn should_create_emission_event() {
         let simulation_id = 0;
         let electrolyzer = Electrolyzer::default();
         let timestamp = Timestamp::default();
-        let mut portfolio = EnergySourcePortfolio::default();
-        portfolio.total_electricity_mwh = 4.0;
-        portfolio.natural_gas_mwh = 2.0;
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(PowerPlant::new(0, vec![]));
+        let mut gas_plant = PowerPlant::new(7, vec![]);
+        gas_plant.details = Some(PowerPlantDetails {
+            plant_id: 7,
+            fuel_type: EnergySource::NaturalGas,
+            emission_factor_kg_co2e_per_mwh: 10.0,
+            ..Default::default()
+        });
+        power_grid.add_power_plant(gas_plant);
+        let transactions = [0, 7]
+            .into_iter()
+            .map(|plant_id| {
+                let mut portfolio = EnergySourcePortfolio::default();
+                portfolio.add_energy(&EnergySource::NaturalGas, 2.0);
+
+                EnergyTransaction {
+                    simulation_id,
+                    electrolyzer_id: 0,
+                    plant_id,
+                    timestamp,
+                    price_usd: 0.0,
+                    portfolio,
+                }
+            })
+            .collect::<Vec<EnergyTransaction>>();
         let mut expected_emission_event = EmissionEvent::default();
         expected_emission_event.emission_timestamp =
             Timestamp::new(timestamp.seconds, timestamp.nanos);
-        expected_emission_event.amount_emitted_kg = 2.0 * NATURAL_GAS_MWH_TO_CO2;
+        expected_emission_event.amount_emitted_kg = NATURAL_GAS_MWH_TO_CO2 + 10.0;
 
-        let emission_event =
-            create_emission_event(simulation_id, &timestamp, &electrolyzer, &portfolio);
+        let mut emission_factors = EmissionFactorSet::default();
+        emission_factors.factors.natural_gas = EmissionFactor::new(NATURAL_GAS_MWH_TO_CO2, 0.0);
+
+        let emission_event = create_emission_event(
+            simulation_id,
+            &timestamp,
+            &electrolyzer,
+            &emission_factors,
+            &power_grid,
+            &transactions,
+            2.0,
+        );
 
         assert_eq!(emission_event, expected_emission_event);
     }
@@ -462,7 +973,7 @@ mod test {
     fn should_create_hydrogen_production_event() {
         let simulation_id = 0;
         let mut electrolyzer = Electrolyzer::default();
-        electrolyzer.capacity_mw = 10.0;
+        electrolyzer.capacity_mw = 40.0;
         electrolyzer.production_type = ProductionType::Constant;
         electrolyzer.production = ConstantProduction {
             conversion_rate: 2.0,
@@ -475,9 +986,15 @@ mod test {
         expected_hydrogen_production_event.production_timestamp = timestamp.clone();
         expected_hydrogen_production_event.kg_hydrogen = 8.0;
 
-        let hydrogen_production_event =
-            create_hydrogen_production_event(simulation_id, &timestamp, &electrolyzer, &portfolio)
-                .expect("Should create hydrogen production event");
+        let hydrogen_production_event = create_hydrogen_production_event(
+            simulation_id,
+            &timestamp,
+            &electrolyzer,
+            &StackState::default(),
+            &portfolio,
+            0.25,
+        )
+        .expect("Should create hydrogen production event");
 
         assert_eq!(
             hydrogen_production_event,
@@ -489,7 +1006,7 @@ mod test {
     fn should_create_hydrogen_production_event_at_max_capacity() {
         let simulation_id = 0;
         let mut electrolyzer = Electrolyzer::default();
-        electrolyzer.capacity_mw = 10.0;
+        electrolyzer.capacity_mw = 40.0;
         electrolyzer.production_type = ProductionType::Constant;
         electrolyzer.production = ConstantProduction {
             conversion_rate: 2.0,
@@ -502,9 +1019,15 @@ mod test {
         expected_hydrogen_production_event.production_timestamp = timestamp.clone();
         expected_hydrogen_production_event.kg_hydrogen = 20.0;
 
-        let hydrogen_production_event =
-            create_hydrogen_production_event(simulation_id, &timestamp, &electrolyzer, &portfolio)
-                .expect("Should create hydrogen production event");
+        let hydrogen_production_event = create_hydrogen_production_event(
+            simulation_id,
+            &timestamp,
+            &electrolyzer,
+            &StackState::default(),
+            &portfolio,
+            0.25,
+        )
+        .expect("Should create hydrogen production event");
 
         assert_eq!(
             hydrogen_production_event,
@@ -524,7 +1047,12 @@ mod test {
         expected_tax_credit.total_usd = 300.0;
         expected_tax_credit.tier = TaxCredit45VTier::Max;
 
-        let tax_credit = calculate_tax_credit(&emission_event, &hydrogen_production_event);
+        let tax_credit = calculate_tax_credit(
+            &emission_event,
+            &hydrogen_production_event,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
 
         assert_eq!(tax_credit, expected_tax_credit);
     }
@@ -541,7 +1069,12 @@ mod test {
         expected_tax_credit.total_usd = 801.6;
         expected_tax_credit.tier = TaxCredit45VTier::Tier1;
 
-        let tax_credit = calculate_tax_credit(&emission_event, &hydrogen_production_event);
+        let tax_credit = calculate_tax_credit(
+            &emission_event,
+            &hydrogen_production_event,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
 
         assert_eq!(tax_credit, expected_tax_credit);
     }
@@ -558,7 +1091,12 @@ mod test {
         expected_tax_credit.total_usd = 225.0;
         expected_tax_credit.tier = TaxCredit45VTier::Tier2;
 
-        let tax_credit = calculate_tax_credit(&emission_event, &hydrogen_production_event);
+        let tax_credit = calculate_tax_credit(
+            &emission_event,
+            &hydrogen_production_event,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
 
         assert_eq!(tax_credit, expected_tax_credit);
     }
@@ -572,12 +1110,72 @@ mod test {
         hydrogen_production_event.production_timestamp = Timestamp::default();
         hydrogen_production_event.kg_hydrogen = 500.0;
         let mut expected_tax_credit = TaxCredit45V::default();
-        expected_tax_credit.total_usd =
-            hydrogen_production_event.kg_hydrogen * TaxCredit45VTier::Tier3.value();
+        expected_tax_credit.total_usd = hydrogen_production_event.kg_hydrogen * 3.0 * 0.2;
         expected_tax_credit.tier = TaxCredit45VTier::Tier3;
 
-        let tax_credit = calculate_tax_credit(&emission_event, &hydrogen_production_event);
+        let tax_credit = calculate_tax_credit(
+            &emission_event,
+            &hydrogen_production_event,
+            &TaxCredit45VRuleset::final_rules(),
+        )
+        .unwrap();
 
         assert_eq!(tax_credit.tier, expected_tax_credit.tier);
     }
+
+    #[test]
+    fn should_create_hydrogen_production_event_on_efficiency_curve() {
+        let simulation_id = 0;
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 16.0;
+        electrolyzer.production_type = ProductionType::Variable;
+        electrolyzer.variable_production = VariableProduction::new("0.25:40, 1.0:50", 0.1).unwrap();
+        let timestamp = Timestamp::default();
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.total_electricity_mwh = 1.0;
+        portfolio.natural_gas_mwh = 1.0;
+        let mut expected_hydrogen_production_event = HydrogenProductionEvent::default();
+        expected_hydrogen_production_event.production_timestamp = timestamp.clone();
+        expected_hydrogen_production_event.kg_hydrogen = 40.0;
+
+        let hydrogen_production_event = create_hydrogen_production_event(
+            simulation_id,
+            &timestamp,
+            &electrolyzer,
+            &StackState::default(),
+            &portfolio,
+            0.25,
+        )
+        .expect("Should create hydrogen production event");
+
+        assert_eq!(
+            hydrogen_production_event,
+            expected_hydrogen_production_event
+        );
+    }
+
+    #[test]
+    fn should_not_produce_hydrogen_below_minimum_stable_load() {
+        let simulation_id = 0;
+        let mut electrolyzer = Electrolyzer::default();
+        electrolyzer.capacity_mw = 16.0;
+        electrolyzer.production_type = ProductionType::Variable;
+        electrolyzer.variable_production = VariableProduction::new("0.25:40, 1.0:50", 0.2).unwrap();
+        let timestamp = Timestamp::default();
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.total_electricity_mwh = 0.5;
+        portfolio.natural_gas_mwh = 0.5;
+
+        let hydrogen_production_event = create_hydrogen_production_event(
+            simulation_id,
+            &timestamp,
+            &electrolyzer,
+            &StackState::default(),
+            &portfolio,
+            0.25,
+        )
+        .expect("Should create hydrogen production event");
+
+        assert_eq!(hydrogen_production_event.kg_hydrogen, 0.0);
+    }
 }
@@ -1,11 +1,14 @@
-use std::process::exit;
+use std::{process::exit, sync::Arc};
 
 use rocket::fs::relative;
 use tax_credit_model_server::{
     data_retriever::fill_generations,
+    jobs::simulation_runner::SimulationJobRunner,
     persistance::{
+        certificates::DiskCertificateClient, data_quality::DiskDataQualityReportClient,
         electrolyzer::InMemoryElectrolyzerPersistanceClient,
-        generation::DiskGenerationPersistanceClient, grid::InMemoryGridClient,
+        emission_factors::DiskEmissionFactorClient, generation::DiskGenerationPersistanceClient,
+        grid::InMemoryGridClient, plant_registry::DiskPlantRegistryClient,
         simulation::InMemorySimulationClient,
         simulation_selection::InMemorySimulationSelectionClient, user::InMemoryUserClient,
     },
@@ -15,17 +18,32 @@ use tax_credit_model_server::{
 #[macro_use]
 extern crate rocket;
 
+const DEFAULT_SIMULATION_WORKERS: usize = 2;
+
 #[launch]
 pub async fn rocket() -> _ {
     let assets_directory =
         std::env::var("ASSETS_DIRECTORY").unwrap_or_else(|_| relative!("assets").to_string());
     let data_directory =
         std::env::var("DATA_DIRECTORY").unwrap_or_else(|_| relative!("../data").to_string());
-    let configuration = ServerConfiguration::new(&data_directory, &assets_directory);
+    let simulation_workers = std::env::var("SIMULATION_WORKERS")
+        .ok()
+        .and_then(|workers| workers.parse().ok())
+        .unwrap_or(DEFAULT_SIMULATION_WORKERS);
+    let mut configuration = ServerConfiguration::new(&data_directory, &assets_directory);
+
+    if let Ok(data_quality_policy) = std::env::var("DATA_QUALITY_POLICY") {
+        configuration.data_quality_policy = data_quality_policy.parse().unwrap_or_else(|x| {
+            eprintln!("{}", x);
+            exit(1);
+        });
+    }
+
+    let simulation_client = Arc::new(InMemorySimulationClient::new());
     let dependencies = Dependencies {
         grid_client: Box::new(InMemoryGridClient::new()),
         electrolyzer_client: Box::new(InMemoryElectrolyzerPersistanceClient::new()),
-        simulation_client: Box::new(InMemorySimulationClient::new()),
+        simulation_client: Box::new(simulation_client.clone()),
         generation_client: Box::new(
             DiskGenerationPersistanceClient::new(&format!(
                 "{}/{}/{}",
@@ -38,6 +56,41 @@ pub async fn rocket() -> _ {
         ),
         user_client: Box::new(InMemoryUserClient::new()),
         simulation_selection_client: Box::new(InMemorySimulationSelectionClient::new()),
+        emission_factor_client: Box::new(
+            DiskEmissionFactorClient::new(&format!("{}/{}", data_directory, "emission_factors"))
+                .unwrap_or_else(|x| {
+                    eprintln!("{}", x);
+                    exit(1);
+                }),
+        ),
+        data_quality_report_client: Box::new(
+            DiskDataQualityReportClient::new(&format!(
+                "{}/{}/{}",
+                data_directory, "generations", "data_quality_report.json"
+            ))
+            .unwrap_or_else(|x| {
+                eprintln!("{}", x);
+                exit(1);
+            }),
+        ),
+        plant_registry_client: Box::new(
+            DiskPlantRegistryClient::new(&format!(
+                "{}/{}/{}",
+                data_directory, "plants", "registry.toml"
+            ))
+            .unwrap_or_else(|x| {
+                eprintln!("{}", x);
+                exit(1);
+            }),
+        ),
+        certificate_client: Box::new(
+            DiskCertificateClient::new(&format!("{}/{}", data_directory, "certificates"))
+                .unwrap_or_else(|x| {
+                    eprintln!("{}", x);
+                    exit(1);
+                }),
+        ),
+        simulation_runner: SimulationJobRunner::new(simulation_workers, simulation_client),
     };
 
     fill_generations(configuration.clone(), &dependencies);
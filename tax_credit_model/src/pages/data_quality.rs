@@ -0,0 +1,70 @@
+use askama::Template;
+
+use crate::schema::data_quality::{
+    DataQualityIssueKind, DataQualityPolicy, DataQualityReport, MonthlyDataQualityReport,
+};
+
+const MAX_LISTED_ISSUES: usize = 50;
+
+#[derive(Template, Debug)]
+#[template(path = "pages/data_quality.html")]
+pub struct DataQualityPage {
+    pub policy: DataQualityPolicy,
+    pub issue_kinds: Vec<DataQualityIssueKind>,
+    pub months: Vec<DataQualityMonth>,
+    pub skipped_files: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct DataQualityMonth {
+    pub month: String,
+    pub intervals: usize,
+    pub skipped_intervals: usize,
+    pub interpolated_intervals: usize,
+    pub issue_counts: Vec<usize>,
+    pub issues: Vec<String>,
+    pub unlisted_issues: usize,
+}
+
+impl DataQualityPage {
+    pub fn render(report: DataQualityReport) -> Self {
+        Self {
+            policy: report.policy,
+            issue_kinds: DataQualityIssueKind::all(),
+            months: report.months.iter().map(DataQualityMonth::render).collect(),
+            skipped_files: report.skipped_files,
+        }
+    }
+}
+
+impl DataQualityMonth {
+    fn render(report: &MonthlyDataQualityReport) -> Self {
+        Self {
+            month: report.month.clone(),
+            intervals: report.intervals,
+            skipped_intervals: report.skipped_intervals,
+            interpolated_intervals: report.interpolated_intervals,
+            issue_counts: DataQualityIssueKind::all()
+                .into_iter()
+                .map(|kind| report.count(kind))
+                .collect(),
+            issues: report
+                .issues
+                .iter()
+                .take(MAX_LISTED_ISSUES)
+                .map(|issue| {
+                    match issue
+                        .timestamp
+                        .and_then(|timestamp| timestamp.to_utc_date_time().ok())
+                    {
+                        Some(date) => {
+                            format!("{} at {}: {}", issue.kind, date.to_rfc3339(), issue.detail)
+                        }
+                        None => format!("{}: {}", issue.kind, issue.detail),
+                    }
+                })
+                .collect(),
+            unlisted_issues: report.issues.len().saturating_sub(MAX_LISTED_ISSUES),
+        }
+    }
+}
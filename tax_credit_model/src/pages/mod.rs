@@ -1,3 +1,4 @@
+pub mod data_quality;
 pub mod index;
 pub mod simulation;
 pub mod page;
@@ -0,0 +1,164 @@
+use std::{fs::File, io::Read, path::Path};
+
+use calamine::DataType;
+use zip::ZipArchive;
+
+use crate::schema::errors::{Error, Result};
+
+use super::excel::ExcelSheet;
+
+// Reads comma separated files, on their own or zipped the way ERCOT publishes
+// its MIS reports, into the same sheet and rows as an Excel workbook
+pub struct CsvFile {}
+
+impl CsvFile {
+    pub fn open(path: &str) -> Result<ExcelSheet> {
+        Ok(ExcelSheet::from_rows(CsvFile::read_file(path)?))
+    }
+
+    // Files are concatenated under the first file's header, dropping the
+    // header each following file repeats
+    pub fn open_all(paths: &[String]) -> Result<ExcelSheet> {
+        let mut rows: Vec<Vec<DataType>> = vec![];
+
+        for path in paths {
+            for row in CsvFile::read_file(path)? {
+                if rows.first() == Some(&row) {
+                    continue;
+                }
+
+                rows.push(row);
+            }
+        }
+
+        Ok(ExcelSheet::from_rows(rows))
+    }
+
+    fn read_file(path: &str) -> Result<Vec<Vec<DataType>>> {
+        let file = File::open(path).map_err(|err| Error::not_found(&err.to_string()))?;
+        let is_zip = Path::new(path)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"));
+
+        if !is_zip {
+            return CsvFile::read_records(file);
+        }
+
+        let mut archive =
+            ZipArchive::new(file).map_err(|err| Error::invalid_argument(&err.to_string()))?;
+        let mut rows: Vec<Vec<DataType>> = vec![];
+
+        for index in 0..archive.len() {
+            let entry = archive
+                .by_index(index)
+                .map_err(|err| Error::invalid_argument(&err.to_string()))?;
+
+            if !entry.name().to_lowercase().ends_with(".csv") {
+                continue;
+            }
+
+            for row in CsvFile::read_records(entry)? {
+                if rows.first() == Some(&row) {
+                    continue;
+                }
+
+                rows.push(row);
+            }
+        }
+
+        Ok(rows)
+    }
+
+    pub fn read_records<R: Read>(reader: R) -> Result<Vec<Vec<DataType>>> {
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader)
+            .records()
+            .map(|record| {
+                record
+                    .map(|record| record.iter().map(CsvFile::parse_cell).collect())
+                    .map_err(|err| Error::invalid_argument(&err.to_string()))
+            })
+            .collect()
+    }
+
+    fn parse_cell(value: &str) -> DataType {
+        let value = value.trim();
+
+        if value.is_empty() {
+            return DataType::Empty;
+        }
+
+        match value.parse::<f64>().or_else(|err| {
+            CsvFile::without_thousands_separators(value)
+                .ok_or(err)?
+                .parse()
+        }) {
+            Ok(number) if number.is_finite() => DataType::Float(number),
+            _ => DataType::String(String::from(value)),
+        }
+    }
+
+    // EIA writes numbers grouped by thousands, 12,345.6, which only parse once
+    // the separators are dropped
+    fn without_thousands_separators(value: &str) -> Option<String> {
+        let (whole, _) = value.split_once('.').unwrap_or((value, ""));
+        let mut groups = whole.trim_start_matches('-').split(',');
+        let first = groups.next()?;
+        let is_grouped = (1..=3).contains(&first.len())
+            && groups.clone().count() > 0
+            && groups.all(|group| group.len() == 3)
+            && whole
+                .trim_start_matches('-')
+                .chars()
+                .all(|character| character.is_ascii_digit() || character == ',');
+
+        is_grouped.then(|| value.replace(',', ""))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use calamine::DataType;
+    use pretty_assertions::assert_eq;
+
+    use crate::parsers::excel::ExcelSheet;
+
+    use super::CsvFile;
+
+    #[test]
+    fn should_read_rows_like_excel() {
+        let rows = CsvFile::read_records(
+            "DeliveryDate,DeliveryHour,SettlementPointName,SettlementPointPrice\n\
+             01/01/2023,1,HB_NORTH,-1.5\n\
+             01/01/2023,2,HB_WEST,\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        let sheet = ExcelSheet::from_rows(rows);
+        let mut sheet_rows = sheet.rows();
+        let header = sheet_rows.next().unwrap();
+        let first = sheet_rows.next().unwrap();
+        let second = sheet_rows.next().unwrap();
+
+        assert_eq!(header.get_string(0).unwrap(), "DeliveryDate");
+        assert_eq!(first.get_string(2).unwrap(), "HB_NORTH");
+        assert_eq!(first.get_float(1).unwrap(), 1.0);
+        assert_eq!(first.get_float(3).unwrap(), -1.5);
+        assert_eq!(
+            first.get_date(0).unwrap().to_string(),
+            "2023-01-01 00:00:00"
+        );
+        assert!(second.is_empty_cell(3).unwrap());
+        assert_eq!(
+            CsvFile::parse_cell(" 01:00 "),
+            DataType::String(String::from("01:00"))
+        );
+        assert_eq!(CsvFile::parse_cell("-12,345.5"), DataType::Float(-12345.5));
+        assert_eq!(
+            CsvFile::parse_cell("1,2"),
+            DataType::String(String::from("1,2"))
+        );
+    }
+}
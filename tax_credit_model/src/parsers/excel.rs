@@ -1,4 +1,4 @@
-use std::{fs::File, io::BufReader};
+use std::{collections::BTreeMap, fs::File, io::BufReader};
 
 use calamine::{open_workbook, DataType, Range, Reader, Rows, Xlsx, XlsxError};
 use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime};
@@ -14,11 +14,52 @@ impl ExcelSheet {
         ExcelSheet { sheet }
     }
 
+    pub fn from_rows(rows: Vec<Vec<DataType>>) -> ExcelSheet {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        if rows.is_empty() || width == 0 {
+            return ExcelSheet::new(Range::empty());
+        }
+
+        let mut sheet = Range::new((0, 0), (rows.len() as u32 - 1, width as u32 - 1));
+
+        for (row_index, row) in rows.into_iter().enumerate() {
+            for (column_index, cell) in row.into_iter().enumerate() {
+                sheet.set_value((row_index as u32, column_index as u32), cell);
+            }
+        }
+
+        ExcelSheet::new(sheet)
+    }
+
     pub fn rows(&self) -> ExcelRows {
         ExcelRows {
             rows: self.sheet.rows(),
         }
     }
+
+    // Splits the rows after the header by key, each part keeping the header
+    pub fn partition<K, F>(&self, key: F) -> Result<BTreeMap<K, ExcelSheet>>
+    where
+        K: Ord,
+        F: Fn(&ExcelRow) -> Result<K>,
+    {
+        let mut rows = self.sheet.rows();
+        let header = rows.next().map(|row| row.to_vec()).unwrap_or_default();
+        let mut partitions: BTreeMap<K, Vec<Vec<DataType>>> = BTreeMap::new();
+
+        for row in rows {
+            partitions
+                .entry(key(&ExcelRow::new(row))?)
+                .or_insert_with(|| vec![header.clone()])
+                .push(row.to_vec());
+        }
+
+        Ok(partitions
+            .into_iter()
+            .map(|(key, rows)| (key, ExcelSheet::from_rows(rows)))
+            .collect())
+    }
 }
 
 pub struct ExcelRows<'a> {
@@ -45,6 +86,10 @@ impl ExcelWorkbook {
         })
     }
 
+    pub fn sheet_names(&self) -> Vec<String> {
+        self.workbook.sheet_names().to_vec()
+    }
+
     pub fn get_sheet(&mut self, sheet_name: &str) -> Result<ExcelSheet> {
         let sheet = self
             .workbook
@@ -65,6 +110,42 @@ impl<'a> ExcelRow<'a> {
         ExcelRow { row }
     }
 
+    // Headers are compared ignoring case, spaces and underscores so the same
+    // column is found whether it is written Delivery Date or DeliveryDate
+    pub fn normalize_header(name: &str) -> String {
+        name.chars()
+            .filter(|character| character.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    }
+
+    pub fn find_column(&self, names: &[&str]) -> Result<usize> {
+        self.row
+            .iter()
+            .position(|cell| {
+                cell.get_string().is_some_and(|header| {
+                    names.iter().any(|name| {
+                        ExcelRow::normalize_header(name) == ExcelRow::normalize_header(header)
+                    })
+                })
+            })
+            .ok_or_else(|| Error::not_found(&format!("No {} column", names.join(" or "))))
+    }
+
+    // Every column holding text, for reading the headers of a sheet with a
+    // column per series
+    pub fn string_cells(&self) -> Vec<(usize, &str)> {
+        self.row
+            .iter()
+            .enumerate()
+            .filter_map(|(column, cell)| cell.get_string().map(|value| (column, value)))
+            .collect()
+    }
+
+    pub fn has_cell(&self, column: usize) -> bool {
+        column < self.row.len()
+    }
+
     pub fn is_empty_cell(&self, column: usize) -> Result<bool> {
         Ok(self
             .row
@@ -83,12 +164,15 @@ impl<'a> ExcelRow<'a> {
             .get(column)
             .ok_or_else(|| Error::not_found("Date column not found"))?;
 
-        if let calamine::DataType::DateTime(serial_number) = date {
-            Ok(excel_epoch
+        match date {
+            calamine::DataType::DateTime(serial_number) => Ok(excel_epoch
                 .checked_add_days(Days::new(serial_number.clone() as u64))
-                .ok_or_else(|| Error::invalid_argument("Invalid days to add"))?)
-        } else {
-            Err(Error::invalid_argument("Not a date time"))
+                .ok_or_else(|| Error::invalid_argument("Invalid days to add"))?),
+            calamine::DataType::String(date) => NaiveDate::parse_from_str(date, "%m/%d/%Y")
+                .or_else(|_| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+                .map(|date| date.and_time(NaiveTime::MIN))
+                .map_err(|err| Error::invalid_argument(&err.to_string())),
+            _ => Err(Error::invalid_argument("Not a date time")),
         }
     }
 
@@ -0,0 +1,158 @@
+use std::{collections::HashSet, io::Read, path::Path};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Deserialize;
+
+use crate::{
+    concurrency::mutex::Mutex,
+    file_systems::directory::Directory,
+    schema::{
+        certificate::EnergyAttributeCertificate,
+        errors::{Error, Result},
+        grid::GridRegion,
+        time::Timestamp,
+    },
+};
+
+const VINTAGE_HOUR_FORMAT: &str = "%Y-%m-%d %H:%M";
+const COMMISSIONING_DATE_FORMAT: &str = "%Y-%m-%d";
+
+pub trait CertificateClient: Send + Sync {
+    fn list_certificates(&self, region: &GridRegion) -> Result<Vec<EnergyAttributeCertificate>>;
+}
+
+#[derive(Deserialize)]
+struct CertificateRow {
+    certificate_id: String,
+    generator: String,
+    fuel_type: String,
+    vintage_hour: String,
+    region: String,
+    commissioning_date: String,
+    mwh: f64,
+}
+
+// Certificates are read once from every CSV file in the directory, with the
+// vintage hour given by its start in UTC. Without the directory there are no
+// certificates to retire
+pub struct DiskCertificateClient {
+    certificates: Mutex<Vec<EnergyAttributeCertificate>>,
+}
+
+impl DiskCertificateClient {
+    pub fn new(path: &str) -> Result<Self> {
+        let mut certificates = vec![];
+
+        if Path::new(path).is_dir() {
+            for file_path in Directory::new(path).list_files()? {
+                if !file_path.to_lowercase().ends_with(".csv") {
+                    continue;
+                }
+
+                let file = std::fs::File::open(&file_path)
+                    .map_err(|err| Error::not_found(&err.to_string()))?;
+                certificates.extend(
+                    read_certificates(file)
+                        .map_err(|err| Error::invalid_argument(&format!("{file_path}: {err}")))?,
+                );
+            }
+        }
+
+        let mut certificate_ids = HashSet::new();
+
+        for certificate in &certificates {
+            if !certificate_ids.insert(&certificate.certificate_id) {
+                return Err(Error::invalid_argument(&format!(
+                    "Certificate {} is listed more than once",
+                    certificate.certificate_id
+                )));
+            }
+        }
+
+        Ok(Self {
+            certificates: Mutex::new(certificates),
+        })
+    }
+}
+
+fn read_certificates(reader: impl Read) -> Result<Vec<EnergyAttributeCertificate>> {
+    csv::Reader::from_reader(reader)
+        .deserialize::<CertificateRow>()
+        .map(|row| parse_row(row.map_err(|err| Error::invalid_argument(&err.to_string()))?))
+        .collect()
+}
+
+fn parse_row(row: CertificateRow) -> Result<EnergyAttributeCertificate> {
+    let vintage = NaiveDateTime::parse_from_str(&row.vintage_hour, VINTAGE_HOUR_FORMAT)
+        .map_err(|err| Error::invalid_argument(&format!("{}: {}", row.vintage_hour, err)))?;
+    let commissioning_date =
+        NaiveDate::parse_from_str(&row.commissioning_date, COMMISSIONING_DATE_FORMAT)
+            .map_err(|err| {
+                Error::invalid_argument(&format!("{}: {}", row.commissioning_date, err))
+            })?
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| Error::invalid_argument("Invalid commissioning date"))?;
+
+    if commissioning_date > vintage {
+        return Err(Error::invalid_argument(&format!(
+            "Certificate {} was generated before {} was commissioned",
+            row.certificate_id, row.generator
+        )));
+    }
+
+    Ok(EnergyAttributeCertificate {
+        certificate_id: row.certificate_id,
+        generator: row.generator,
+        fuel_type: row.fuel_type.parse()?,
+        vintage: Timestamp::from(vintage.and_utc()),
+        region: row.region.parse()?,
+        commissioning_date: Timestamp::from(commissioning_date.and_utc()),
+        mwh: row.mwh,
+    })
+}
+
+impl CertificateClient for DiskCertificateClient {
+    fn list_certificates(&self, region: &GridRegion) -> Result<Vec<EnergyAttributeCertificate>> {
+        Ok(Mutex::lock(&self.certificates)?
+            .iter()
+            .filter(|certificate| &certificate.region == region)
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{grid::GridRegion, simulation::EnergySource, time::Timestamp};
+
+    use super::read_certificates;
+
+    #[test]
+    fn should_read_certificates() {
+        let certificates = read_certificates(
+            "certificate_id,generator,fuel_type,vintage_hour,region,commissioning_date,mwh\n\
+             GC-1,Roscoe Wind Farm,Wind,2023-06-01 05:00,ERCOT,2009-10-01,25.5\n"
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(certificates[0].generator, "Roscoe Wind Farm");
+        assert_eq!(certificates[0].fuel_type, EnergySource::Wind);
+        assert_eq!(certificates[0].region, GridRegion::Ercot);
+        assert_eq!(certificates[0].vintage, Timestamp::new(1685595600, 0));
+        assert_eq!(certificates[0].mwh, 25.5);
+    }
+
+    #[test]
+    fn should_reject_certificates_generated_before_commissioning() {
+        assert!(read_certificates(
+            "certificate_id,generator,fuel_type,vintage_hour,region,commissioning_date,mwh\n\
+             GC-1,New Solar,Solar,2023-06-01 05:00,ERCOT,2024-01-01,1\n"
+                .as_bytes(),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use serde_json::{from_slice, to_vec};
+
+use crate::{
+    file_systems::{
+        directory::Directory,
+        file::File,
+        permission::{CreateMode, Permissions},
+    },
+    schema::{
+        data_quality::DataQualityReport,
+        errors::{Error, Result},
+    },
+};
+
+pub trait DataQualityReportClient: Send + Sync {
+    fn get_report(&self) -> Result<DataQualityReport>;
+    fn save_report(&self, report: &DataQualityReport) -> Result<DataQualityReport>;
+}
+
+// The report from the last ingestion is kept as JSON next to the generations
+// it describes, so it is still shown once the generations are loaded from disk
+pub struct DiskDataQualityReportClient {
+    file: File,
+}
+
+impl DiskDataQualityReportClient {
+    pub fn new(path: &str) -> Result<Self> {
+        let file = File::new(path, &Permissions::appendable(CreateMode::CreateOrRead));
+        Directory::create_directory(File::directory_path(&file))?;
+        file.write_file(&[])?;
+
+        Ok(Self {
+            file: File::new(path, &Permissions::writeable(CreateMode::CreateOrRead)),
+        })
+    }
+}
+
+impl DataQualityReportClient for DiskDataQualityReportClient {
+    fn get_report(&self) -> Result<DataQualityReport> {
+        let data = File::new(self.file.path(), &Permissions::readable()).read_file()?;
+
+        if data.is_empty() {
+            return Ok(DataQualityReport::default());
+        }
+
+        from_slice(&data).map_err(|err| Error::invalid_argument(&err.to_string()))
+    }
+
+    fn save_report(&self, report: &DataQualityReport) -> Result<DataQualityReport> {
+        self.file.write_file(
+            &to_vec(report).map_err(|err| Error::invalid_argument(&err.to_string()))?,
+        )?;
+
+        Ok(report.clone())
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use crate::{
+    concurrency::mutex::Mutex,
+    file_systems::{directory::Directory, file::File, permission::Permissions},
+    schema::{
+        emissions::{EmissionFactorSet, EmissionFactorSetId},
+        errors::{Error, Result},
+    },
+};
+
+pub trait EmissionFactorClient: Send + Sync {
+    fn get_emission_factor_set(&self, id: &str) -> Result<EmissionFactorSet>;
+    fn list_emission_factor_sets(&self) -> Result<Vec<EmissionFactorSet>>;
+}
+
+// Factor sets are read once from the TOML and JSON files in the directory,
+// keyed by name and version so a simulation can pin an exact set
+pub struct DiskEmissionFactorClient {
+    emission_factor_sets: Mutex<HashMap<EmissionFactorSetId, EmissionFactorSet>>,
+}
+
+impl DiskEmissionFactorClient {
+    pub fn new(path: &str) -> Result<Self> {
+        let mut emission_factor_sets = HashMap::new();
+
+        for file_path in Directory::new(path).list_files()? {
+            let emission_factor_set = match file_path.rsplit_once('.') {
+                Some((_, "toml")) => parse_toml(&file_path)?,
+                Some((_, "json")) => parse_json(&file_path)?,
+                _ => continue,
+            };
+
+            if let Some(duplicate) =
+                emission_factor_sets.insert(emission_factor_set.id(), emission_factor_set)
+            {
+                return Err(Error::invalid_argument(&format!(
+                    "Emission factor set {} is defined more than once",
+                    duplicate.id()
+                )));
+            }
+        }
+
+        Ok(Self {
+            emission_factor_sets: Mutex::new(emission_factor_sets),
+        })
+    }
+}
+
+fn read_file(path: &str) -> Result<String> {
+    String::from_utf8(File::new(path, &Permissions::readable()).read_file()?)
+        .map_err(|err| Error::invalid_argument(&err.to_string()))
+}
+
+fn parse_toml(path: &str) -> Result<EmissionFactorSet> {
+    toml::from_str(&read_file(path)?)
+        .map_err(|err| Error::invalid_argument(&format!("{}: {}", path, err)))
+}
+
+fn parse_json(path: &str) -> Result<EmissionFactorSet> {
+    serde_json::from_str(&read_file(path)?)
+        .map_err(|err| Error::invalid_argument(&format!("{}: {}", path, err)))
+}
+
+impl EmissionFactorClient for DiskEmissionFactorClient {
+    fn get_emission_factor_set(&self, id: &str) -> Result<EmissionFactorSet> {
+        Mutex::lock(&self.emission_factor_sets)?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::not_found(&format!("No emission factor set named {}", id)))
+    }
+
+    fn list_emission_factor_sets(&self) -> Result<Vec<EmissionFactorSet>> {
+        let mut emission_factor_sets = Mutex::lock(&self.emission_factor_sets)?
+            .values()
+            .cloned()
+            .collect::<Vec<EmissionFactorSet>>();
+        emission_factor_sets.sort_by_key(|emission_factor_set| emission_factor_set.id());
+
+        Ok(emission_factor_sets)
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     schema::{
         errors::{Error, Result},
         simulation::GenerationMetric,
-        time::{DateTimeRange, TimeRange},
+        time::{DateTimeRange, DisplayTimeZone, TimeRange},
     },
 };
 
@@ -99,6 +99,6 @@ impl GenerationClient for DiskGenerationPersistanceClient {
 
                 time_range
             })
-            .to_datetime()?)
+            .to_datetime(DisplayTimeZone::default())?)
     }
 }
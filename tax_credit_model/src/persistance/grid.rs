@@ -4,55 +4,127 @@ use crate::{
     concurrency::mutex::Mutex,
     schema::{
         errors::{Error, Result},
+        grid::GridRegion,
+        plant::PowerPlantDetails,
         simulation::{GenerationMetric, PowerGrid, PowerPlant, PowerPlantId},
+        time::TimeRange,
     },
 };
 
 pub trait GridClient: Send + Sync {
-    fn get_power_grid(&self) -> Result<PowerGrid>;
+    fn get_power_grid(&self, region: &GridRegion) -> Result<PowerGrid>;
+    fn get_generations(
+        &self,
+        region: &GridRegion,
+        plant_id: PowerPlantId,
+        time_range: &TimeRange,
+    ) -> Result<Vec<GenerationMetric>>;
     fn add_generations(&self, generations: Vec<GenerationMetric>) -> Result<()>;
+    fn register_power_plants(&self, plants: Vec<PowerPlantDetails>) -> Result<()>;
+    fn list_regions(&self) -> Result<Vec<GridRegion>>;
 }
 
+// Generations are kept apart by the region they were generated in so each
+// region is simulated as its own grid, and within it by the plant that
+// generated them
 pub struct InMemoryGridClient {
-    generations_store: Mutex<HashMap<PowerPlantId, Vec<GenerationMetric>>>,
+    power_plant_store: Mutex<HashMap<GridRegion, HashMap<PowerPlantId, PowerPlant>>>,
 }
 
 impl InMemoryGridClient {
     pub fn new() -> Self {
         Self {
-            generations_store: Mutex::new(HashMap::new()),
+            power_plant_store: Mutex::new(HashMap::new()),
         }
     }
 }
 
 impl GridClient for InMemoryGridClient {
-    fn get_power_grid(&self) -> Result<PowerGrid> {
-        let plant_id = 0;
-        let generations = Mutex::lock(&self.generations_store)?
-            .get(&plant_id)
-            .ok_or_else(|| Error::not_found("No generations found"))?
-            .clone();
-        let power_plant = PowerPlant {
-            plant_id,
-            generations,
-        };
+    // Registered plants without any generations have nothing to sell and are
+    // left out of the grid
+    fn get_power_grid(&self, region: &GridRegion) -> Result<PowerGrid> {
+        let mut power_plants = Mutex::lock(&self.power_plant_store)?
+            .get(region)
+            .map(|power_plants| {
+                power_plants
+                    .values()
+                    .filter(|power_plant| !power_plant.generations().is_empty())
+                    .cloned()
+                    .collect::<Vec<PowerPlant>>()
+            })
+            .unwrap_or_default();
+
+        if power_plants.is_empty() {
+            return Err(Error::not_found(&format!(
+                "No {} generations found",
+                region
+            )));
+        }
+
+        power_plants.sort_by_key(|power_plant| power_plant.plant_id);
 
         Ok(PowerGrid {
-            power_plants: vec![power_plant],
+            region: region.clone(),
+            power_plants,
         })
     }
 
+    fn get_generations(
+        &self,
+        region: &GridRegion,
+        plant_id: PowerPlantId,
+        time_range: &TimeRange,
+    ) -> Result<Vec<GenerationMetric>> {
+        Ok(Mutex::lock(&self.power_plant_store)?
+            .get(region)
+            .and_then(|power_plants| power_plants.get(&plant_id))
+            .ok_or_else(|| Error::not_found("No generations found"))?
+            .generations_between(&time_range.start, &time_range.end)
+            .to_vec())
+    }
+
     fn add_generations(&self, generations: Vec<GenerationMetric>) -> Result<()> {
-        let mut store = Mutex::lock(&self.generations_store)?;
+        let mut store = Mutex::lock(&self.power_plant_store)?;
 
         for generation in generations {
-            if let Some(exisiting) = store.get_mut(&generation.plant_id) {
-                exisiting.push(generation);
-            } else {
-                store.insert(generation.plant_id, vec![generation]);
-            }
+            store
+                .entry(generation.region.clone())
+                .or_default()
+                .entry(generation.plant_id)
+                .or_insert_with(|| PowerPlant::new(generation.plant_id, vec![]))
+                .add_generation(generation);
+        }
+
+        Ok(())
+    }
+
+    fn register_power_plants(&self, plants: Vec<PowerPlantDetails>) -> Result<()> {
+        let mut store = Mutex::lock(&self.power_plant_store)?;
+
+        for plant in plants {
+            let power_plant = store
+                .entry(plant.region.clone())
+                .or_default()
+                .entry(plant.plant_id)
+                .or_insert_with(|| PowerPlant::new(plant.plant_id, vec![]));
+            power_plant.details = Some(plant);
         }
 
         Ok(())
     }
+
+    fn list_regions(&self) -> Result<Vec<GridRegion>> {
+        let mut regions = Mutex::lock(&self.power_plant_store)?
+            .iter()
+            .filter(|(_, power_plants)| {
+                power_plants
+                    .values()
+                    .any(|power_plant| !power_plant.generations().is_empty())
+            })
+            .map(|(region, _)| region.clone())
+            .collect::<Vec<GridRegion>>();
+        regions.sort();
+
+        Ok(regions)
+    }
 }
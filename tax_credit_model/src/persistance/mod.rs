@@ -1,7 +1,10 @@
+pub mod certificates;
+pub mod data_quality;
 pub mod electrolyzer;
+pub mod emission_factors;
 pub mod generation;
 pub mod grid;
+pub mod plant_registry;
 pub mod simulation;
 pub mod simulation_selection;
 pub mod user;
-
@@ -0,0 +1,156 @@
+use std::{collections::BTreeMap, path::Path};
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::{
+    concurrency::mutex::Mutex,
+    file_systems::{file::File, permission::Permissions},
+    schema::{
+        errors::{Error, Result},
+        plant::{PowerPlantDetails, GRID_MIX_PLANT_ID},
+        simulation::PowerPlantId,
+        time::Timestamp,
+    },
+};
+
+pub trait PlantRegistryClient: Send + Sync {
+    fn get_plant(&self, plant_id: PowerPlantId) -> Result<PowerPlantDetails>;
+    fn list_plants(&self) -> Result<Vec<PowerPlantDetails>>;
+}
+
+#[derive(Deserialize, Default)]
+struct PlantRegistryFile {
+    #[serde(default)]
+    plants: Vec<PlantRegistryEntry>,
+}
+
+// Names are written the way the grid data writes them, HB_WEST or ERCOT, so
+// they are parsed rather than deserialized
+#[derive(Deserialize)]
+struct PlantRegistryEntry {
+    plant_id: PowerPlantId,
+    name: String,
+    fuel_type: String,
+    capacity_mw: f64,
+    region: String,
+    location: String,
+    commercial_operation_date: String,
+    emission_factor_kg_co2e_per_mwh: f64,
+}
+
+// Plants are read once from a TOML file listing each plant under [[plants]].
+// Without the file the registry is empty and only the grid mix is simulated
+pub struct DiskPlantRegistryClient {
+    plants: Mutex<BTreeMap<PowerPlantId, PowerPlantDetails>>,
+}
+
+impl DiskPlantRegistryClient {
+    pub fn new(path: &str) -> Result<Self> {
+        let registry_file = match Path::new(path).is_file() {
+            true => toml::from_str(
+                &String::from_utf8(File::new(path, &Permissions::readable()).read_file()?)
+                    .map_err(|err| Error::invalid_argument(&err.to_string()))?,
+            )
+            .map_err(|err| Error::invalid_argument(&format!("{}: {}", path, err)))?,
+            false => PlantRegistryFile::default(),
+        };
+        let mut plants = BTreeMap::new();
+
+        for entry in registry_file.plants {
+            let plant = parse_entry(entry)?;
+
+            if plant.plant_id == GRID_MIX_PLANT_ID {
+                return Err(Error::invalid_argument(&format!(
+                    "Plant {} uses the grid mix's id {}",
+                    plant.name, GRID_MIX_PLANT_ID
+                )));
+            }
+
+            if let Some(duplicate) = plants.insert(plant.plant_id, plant) {
+                return Err(Error::invalid_argument(&format!(
+                    "Plant {} is registered more than once",
+                    duplicate.plant_id
+                )));
+            }
+        }
+
+        Ok(Self {
+            plants: Mutex::new(plants),
+        })
+    }
+}
+
+fn parse_entry(entry: PlantRegistryEntry) -> Result<PowerPlantDetails> {
+    let commercial_operation_date =
+        NaiveDate::parse_from_str(&entry.commercial_operation_date, "%Y-%m-%d")
+            .map_err(|err| {
+                Error::invalid_argument(&format!("{}: {}", entry.commercial_operation_date, err))
+            })?
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| Error::invalid_argument("Invalid commercial operation date"))?;
+
+    Ok(PowerPlantDetails {
+        plant_id: entry.plant_id,
+        name: entry.name,
+        fuel_type: entry.fuel_type.parse()?,
+        capacity_mw: entry.capacity_mw,
+        region: entry.region.parse()?,
+        location: entry.location.parse()?,
+        commercial_operation_date: Timestamp::from(commercial_operation_date.and_utc()),
+        emission_factor_kg_co2e_per_mwh: entry.emission_factor_kg_co2e_per_mwh,
+    })
+}
+
+impl PlantRegistryClient for DiskPlantRegistryClient {
+    fn get_plant(&self, plant_id: PowerPlantId) -> Result<PowerPlantDetails> {
+        Mutex::lock(&self.plants)?
+            .get(&plant_id)
+            .cloned()
+            .ok_or_else(|| Error::not_found(&format!("No plant {}", plant_id)))
+    }
+
+    fn list_plants(&self) -> Result<Vec<PowerPlantDetails>> {
+        Ok(Mutex::lock(&self.plants)?.values().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::{
+        ercot::SettlementPointLocation, grid::GridRegion, simulation::EnergySource, time::Timestamp,
+    };
+
+    use super::{parse_entry, PlantRegistryFile};
+
+    #[test]
+    fn should_parse_registry_entries() {
+        let registry_file: PlantRegistryFile = toml::from_str(
+            r#"
+            [[plants]]
+            plant_id = 1
+            name = "Roscoe Wind Farm"
+            fuel_type = "Wind"
+            capacity_mw = 781.5
+            region = "ERCOT"
+            location = "HB_WEST"
+            commercial_operation_date = "2009-10-01"
+            emission_factor_kg_co2e_per_mwh = 11.0
+            "#,
+        )
+        .unwrap();
+
+        let plant = parse_entry(registry_file.plants.into_iter().next().unwrap()).unwrap();
+
+        assert_eq!(plant.name, "Roscoe Wind Farm");
+        assert_eq!(plant.fuel_type, EnergySource::Wind);
+        assert_eq!(plant.region, GridRegion::Ercot);
+        assert_eq!(plant.location, SettlementPointLocation::WestHub);
+        assert_eq!(
+            plant.commercial_operation_date,
+            Timestamp::new(1254355200, 0)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     concurrency::mutex::Mutex,
@@ -19,6 +19,28 @@ pub trait SimulationClient: Send + Sync {
     fn update(&self, simulation_state: &SimulationState) -> Result<SimulationState>;
 }
 
+// Lets the request handlers and the simulation job workers share one store
+impl<T: SimulationClient + ?Sized> SimulationClient for Arc<T> {
+    fn get_simulation_state(&self, simulation_id: &SimulationId) -> Result<SimulationState> {
+        self.as_ref().get_simulation_state(simulation_id)
+    }
+
+    fn create_simulation_state(
+        &self,
+        simulation_state: &SimulationState,
+    ) -> Result<SimulationState> {
+        self.as_ref().create_simulation_state(simulation_state)
+    }
+
+    fn list_simulations(&self) -> Result<Vec<SimulationState>> {
+        self.as_ref().list_simulations()
+    }
+
+    fn update(&self, simulation_state: &SimulationState) -> Result<SimulationState> {
+        self.as_ref().update(simulation_state)
+    }
+}
+
 pub struct InMemorySimulationClient {
     simulation_store: Mutex<HashMap<SimulationId, SimulationState>>,
     id: Mutex<SimulationId>,
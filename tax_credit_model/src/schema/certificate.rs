@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    grid::GridRegion,
+    simulation::{EnergySource, SimulationId},
+    time::Timestamp,
+};
+
+pub type CertificateId = String;
+
+// An energy attribute certificate for clean energy generated during its vintage
+// hour, which starts at the vintage timestamp
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct EnergyAttributeCertificate {
+    pub certificate_id: CertificateId,
+    pub generator: String,
+    pub fuel_type: EnergySource,
+    pub vintage: Timestamp,
+    pub region: GridRegion,
+    pub commissioning_date: Timestamp,
+    pub mwh: f64,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct CertificateRetirement {
+    pub simulation_id: SimulationId,
+    pub certificate_id: CertificateId,
+    pub timestamp: Timestamp,
+    pub retired_mwh: f64,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct LedgerEntry {
+    pub certificate: EnergyAttributeCertificate,
+    pub remaining_mwh: f64,
+}
+
+// The certificates a simulation can claim, drawn down as they are retired
+// against the energy it consumes
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct CertificateLedger {
+    pub entries: Vec<LedgerEntry>,
+    pub retirements: Vec<CertificateRetirement>,
+}
+
+impl CertificateLedger {
+    pub fn new(certificates: Vec<EnergyAttributeCertificate>) -> Self {
+        let mut entries = certificates
+            .into_iter()
+            .map(|certificate| LedgerEntry {
+                remaining_mwh: certificate.mwh,
+                certificate,
+            })
+            .collect::<Vec<LedgerEntry>>();
+        entries.sort_by(|a, b| {
+            a.certificate
+                .vintage
+                .cmp(&b.certificate.vintage)
+                .then_with(|| {
+                    a.certificate
+                        .certificate_id
+                        .cmp(&b.certificate.certificate_id)
+                })
+        });
+
+        Self {
+            entries,
+            retirements: vec![],
+        }
+    }
+
+    pub fn retired_mwh(&self) -> f64 {
+        self.retirements
+            .iter()
+            .map(|retirement| retirement.retired_mwh)
+            .sum()
+    }
+}
@@ -0,0 +1,146 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::{errors::Error, time::Timestamp};
+
+// What ingestion does with intervals that have data quality issues. Skipping
+// drops them, interpolating rebuilds them and any gaps from their neighbours
+// and failing stops the server before anything is written
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum DataQualityPolicy {
+    #[default]
+    Skip,
+    Interpolate,
+    Fail,
+}
+
+impl DataQualityPolicy {
+    pub fn all() -> Vec<DataQualityPolicy> {
+        vec![Self::Skip, Self::Interpolate, Self::Fail]
+    }
+}
+
+impl std::fmt::Display for DataQualityPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "Skip"),
+            Self::Interpolate => write!(f, "Interpolate"),
+            Self::Fail => write!(f, "Fail"),
+        }
+    }
+}
+
+impl FromStr for DataQualityPolicy {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "Skip" => Ok(DataQualityPolicy::Skip),
+            "Interpolate" => Ok(DataQualityPolicy::Interpolate),
+            "Fail" => Ok(DataQualityPolicy::Fail),
+            _ => Err(Error::invalid_argument(value)),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum DataQualityIssueKind {
+    Gap,
+    Duplicate,
+    NegativePrice,
+    PriceSpike,
+    ZeroPortfolio,
+    UnparseableRow,
+    InitialSettlement,
+    BlankCell,
+    MissingPrice,
+}
+
+impl DataQualityIssueKind {
+    pub fn all() -> Vec<DataQualityIssueKind> {
+        vec![
+            Self::Gap,
+            Self::Duplicate,
+            Self::NegativePrice,
+            Self::PriceSpike,
+            Self::ZeroPortfolio,
+            Self::UnparseableRow,
+            Self::InitialSettlement,
+            Self::BlankCell,
+            Self::MissingPrice,
+        ]
+    }
+}
+
+impl std::fmt::Display for DataQualityIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gap => write!(f, "Gap"),
+            Self::Duplicate => write!(f, "Duplicate"),
+            Self::NegativePrice => write!(f, "NegativePrice"),
+            Self::PriceSpike => write!(f, "PriceSpike"),
+            Self::ZeroPortfolio => write!(f, "ZeroPortfolio"),
+            Self::UnparseableRow => write!(f, "UnparseableRow"),
+            Self::InitialSettlement => write!(f, "InitialSettlement"),
+            Self::BlankCell => write!(f, "BlankCell"),
+            Self::MissingPrice => write!(f, "MissingPrice"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct DataQualityIssue {
+    pub kind: DataQualityIssueKind,
+    pub timestamp: Option<Timestamp>,
+    pub detail: String,
+}
+
+impl DataQualityIssue {
+    pub fn new(kind: DataQualityIssueKind, timestamp: Option<Timestamp>, detail: &str) -> Self {
+        Self {
+            kind,
+            timestamp,
+            detail: String::from(detail),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct MonthlyDataQualityReport {
+    pub month: String,
+    pub intervals: usize,
+    pub skipped_intervals: usize,
+    pub interpolated_intervals: usize,
+    pub issues: Vec<DataQualityIssue>,
+}
+
+impl MonthlyDataQualityReport {
+    pub fn count(&self, kind: DataQualityIssueKind) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.kind == kind)
+            .count()
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct DataQualityReport {
+    pub policy: DataQualityPolicy,
+    pub months: Vec<MonthlyDataQualityReport>,
+    #[serde(default)]
+    pub skipped_files: Vec<String>,
+}
+
+impl DataQualityReport {
+    // INITIAL settlement data is still used as is, so it is reported but does
+    // not stop ingestion
+    pub fn has_issues(&self) -> bool {
+        self.months.iter().any(|month| {
+            month
+                .issues
+                .iter()
+                .any(|issue| issue.kind != DataQualityIssueKind::InitialSettlement)
+        })
+    }
+}
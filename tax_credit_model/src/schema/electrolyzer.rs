@@ -3,6 +3,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::components::badge::Badge;
 
+use super::{
+    ercot::SettlementPointLocation,
+    errors::{Error, Result},
+};
+
 pub type ElectrolyzerId = usize;
 
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
@@ -10,6 +15,111 @@ pub struct ConstantProduction {
     pub conversion_rate: f64,
 }
 
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub struct EfficiencyCurvePoint {
+    pub load_fraction: f64,
+    pub conversion_rate: f64,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct VariableProduction {
+    pub efficiency_curve: Vec<EfficiencyCurvePoint>,
+    pub minimum_stable_load: f64,
+}
+
+impl VariableProduction {
+    pub fn new(efficiency_curve: &str, minimum_stable_load: f64) -> Result<Self> {
+        if !(0.0..1.0).contains(&minimum_stable_load) {
+            return Err(Error::invalid_argument(
+                "Minimum stable load must be between 0 and 1",
+            ));
+        }
+
+        let mut points = efficiency_curve
+            .split(',')
+            .filter(|point| !point.trim().is_empty())
+            .map(EfficiencyCurvePoint::parse)
+            .collect::<Result<Vec<EfficiencyCurvePoint>>>()?;
+
+        if points.is_empty() {
+            return Err(Error::invalid_argument(
+                "Efficiency curve must have at least one point",
+            ));
+        }
+
+        points.sort_by(|a, b| a.load_fraction.total_cmp(&b.load_fraction));
+
+        Ok(Self {
+            efficiency_curve: points,
+            minimum_stable_load,
+        })
+    }
+
+    // Linearly interpolates between the two nearest points on the curve and
+    // holds the end points flat outside of the curve's range
+    pub fn conversion_rate_at(&self, load_fraction: f64) -> f64 {
+        let (first, last) = match (self.efficiency_curve.first(), self.efficiency_curve.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+
+        if load_fraction <= first.load_fraction {
+            return first.conversion_rate;
+        }
+
+        if load_fraction >= last.load_fraction {
+            return last.conversion_rate;
+        }
+
+        self.efficiency_curve
+            .windows(2)
+            .find(|segment| load_fraction <= segment[1].load_fraction)
+            .map_or(last.conversion_rate, |segment| {
+                let (low, high) = (segment[0], segment[1]);
+                let weight =
+                    (load_fraction - low.load_fraction) / (high.load_fraction - low.load_fraction);
+
+                low.conversion_rate + weight * (high.conversion_rate - low.conversion_rate)
+            })
+    }
+
+    pub fn describe_curve(&self) -> String {
+        self.efficiency_curve
+            .iter()
+            .map(|point| format!("{}:{}", point.load_fraction, point.conversion_rate))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl EfficiencyCurvePoint {
+    pub fn parse(value: &str) -> Result<Self> {
+        let (load_fraction, conversion_rate) = value
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| Error::invalid_argument("Curve points must be load:rate pairs"))?;
+        let load_fraction: f64 = load_fraction
+            .trim()
+            .parse()
+            .map_err(|_| Error::invalid_argument(load_fraction))?;
+        let conversion_rate: f64 = conversion_rate
+            .trim()
+            .parse()
+            .map_err(|_| Error::invalid_argument(conversion_rate))?;
+
+        if load_fraction <= 0.0 || load_fraction > 1.0 {
+            return Err(Error::invalid_argument(
+                "Curve load fractions must be greater than 0 and at most 1",
+            ));
+        }
+
+        Ok(Self {
+            load_fraction,
+            conversion_rate,
+        })
+    }
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub struct Electrolyzer {
     pub id: ElectrolyzerId,
@@ -18,12 +128,13 @@ pub struct Electrolyzer {
     pub capacity_mw: f64,
     pub production_type: ProductionType,
     pub production: ConstantProduction,
+    pub variable_production: VariableProduction,
     pub capex: f64,
     pub opex: f64,
     pub replacement_cost: f64,
     pub name: String,
-    pub state: String,
-    pub city: String,
+    #[serde(default)]
+    pub settlement_point: SettlementPointLocation,
 }
 
 impl Electrolyzer {
@@ -37,6 +148,7 @@ impl Electrolyzer {
         production_rate: f64,
         capex: f64,
         opex: f64,
+        settlement_point: SettlementPointLocation,
     ) -> Electrolyzer {
         Electrolyzer {
             id,
@@ -49,12 +161,16 @@ impl Electrolyzer {
             production: ConstantProduction {
                 conversion_rate: production_rate,
             },
+            variable_production: VariableProduction::default(),
             capex,
             opex,
-            city: String::from("Huston"),
-            state: String::from("TX"),
+            settlement_point,
         }
     }
+
+    pub fn is_variable_production(&self) -> bool {
+        self.production_type == ProductionType::Variable
+    }
 }
 
 #[derive(FromForm, Debug, Default)]
@@ -62,13 +178,11 @@ pub struct SelectElectrolyzerHandlerRequest {
     pub electrolyzer_id: ElectrolyzerId,
 }
 
-
 #[derive(FromForm)]
 pub struct GetElectrolyzerRequest {
     pub electrolyzer_id: ElectrolyzerId,
 }
 
-
 #[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
 pub enum ProductionType {
     #[default]
@@ -76,10 +190,21 @@ pub enum ProductionType {
     Variable,
 }
 
-#[derive(FromForm, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+impl std::fmt::Display for ProductionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Constant => write!(f, "Constant"),
+            Self::Variable => write!(f, "Variable"),
+        }
+    }
+}
+
+#[derive(FromForm, Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub struct CreateProductionRequest {
     pub production_type: ProductionType,
     pub conversion_rate_constant: Option<f64>,
+    pub efficiency_curve: Option<String>,
+    pub minimum_stable_load: Option<f64>,
 }
 
 #[derive(FromForm, Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
@@ -92,6 +217,8 @@ pub struct CreateElectrolyzerRequest {
     pub opex: f64,
     pub replacement_cost: f64,
     pub name: String,
+    #[field(default_with = Some(SettlementPointLocation::HubAverage))]
+    pub settlement_point: SettlementPointLocation,
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
@@ -107,3 +234,44 @@ pub struct SearchElectrolyzersRequest {
     pub query: String,
 }
 
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{EfficiencyCurvePoint, VariableProduction};
+
+    #[test]
+    fn should_parse_unordered_efficiency_curve() {
+        let expected_curve = vec![
+            EfficiencyCurvePoint {
+                load_fraction: 0.2,
+                conversion_rate: 40.0,
+            },
+            EfficiencyCurvePoint {
+                load_fraction: 1.0,
+                conversion_rate: 50.0,
+            },
+        ];
+
+        let production = VariableProduction::new("1.0:50, 0.2:40", 0.1).unwrap();
+
+        assert_eq!(production.efficiency_curve, expected_curve);
+    }
+
+    #[test]
+    fn should_reject_load_fraction_above_capacity() {
+        let production = VariableProduction::new("1.5:50", 0.1);
+
+        assert!(production.is_err());
+    }
+
+    #[test]
+    fn should_interpolate_between_curve_points() {
+        let production = VariableProduction::new("0.2:40, 0.6:48, 1.0:44", 0.0).unwrap();
+
+        assert_eq!(production.conversion_rate_at(0.1), 40.0);
+        assert_eq!(production.conversion_rate_at(0.4), 44.0);
+        assert_eq!(production.conversion_rate_at(0.8), 46.0);
+        assert_eq!(production.conversion_rate_at(1.0), 44.0);
+    }
+}
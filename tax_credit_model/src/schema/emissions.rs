@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    plant::PowerPlantDetails,
+    simulation::{EnergySource, EnergySourcePortfolio},
+};
+
+pub type EmissionFactorSetId = String;
+
+pub const DEFAULT_EMISSION_FACTOR_SET_ID: &str = "owid-combustion@2023.1";
+
+// Combustion covers what leaves the stack, upstream covers lifecycle emissions
+// such as fuel extraction and methane leakage
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub struct EmissionFactor {
+    pub combustion_kg_co2e_per_mwh: f64,
+    #[serde(default)]
+    pub upstream_kg_co2e_per_mwh: f64,
+}
+
+impl EmissionFactor {
+    pub fn new(combustion_kg_co2e_per_mwh: f64, upstream_kg_co2e_per_mwh: f64) -> Self {
+        Self {
+            combustion_kg_co2e_per_mwh,
+            upstream_kg_co2e_per_mwh,
+        }
+    }
+
+    pub fn total_kg_co2e_per_mwh(&self) -> f64 {
+        self.combustion_kg_co2e_per_mwh + self.upstream_kg_co2e_per_mwh
+    }
+}
+
+// Every energy source is a required field so a set that leaves a source out
+// fails to load instead of silently treating it as emission free
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct EmissionFactors {
+    pub petroleum: EmissionFactor,
+    pub hydrocarbons: EmissionFactor,
+    pub natural_gas: EmissionFactor,
+    pub coal: EmissionFactor,
+    pub nuclear: EmissionFactor,
+    pub solar: EmissionFactor,
+    pub geothermal: EmissionFactor,
+    pub wind: EmissionFactor,
+    pub biomass: EmissionFactor,
+    pub hydropower: EmissionFactor,
+    pub wholesale_storage_load: EmissionFactor,
+    pub unknown: EmissionFactor,
+}
+
+impl EmissionFactors {
+    pub fn factor(&self, source: &EnergySource) -> &EmissionFactor {
+        match source {
+            EnergySource::Petroleum => &self.petroleum,
+            EnergySource::Hydrocarbons => &self.hydrocarbons,
+            EnergySource::NaturalGas => &self.natural_gas,
+            EnergySource::Coal => &self.coal,
+            EnergySource::Nuclear => &self.nuclear,
+            EnergySource::Solar => &self.solar,
+            EnergySource::Geothermal => &self.geothermal,
+            EnergySource::Wind => &self.wind,
+            EnergySource::Biomass => &self.biomass,
+            EnergySource::Hydropower => &self.hydropower,
+            EnergySource::WholesaleStorageLoad => &self.wholesale_storage_load,
+            EnergySource::Unknown => &self.unknown,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct EmissionFactorSet {
+    pub name: String,
+    pub version: String,
+    pub source: String,
+    pub factors: EmissionFactors,
+}
+
+impl EmissionFactorSet {
+    pub fn id(&self) -> EmissionFactorSetId {
+        format!("{}@{}", self.name, self.version)
+    }
+
+    pub fn emitted_kg(&self, portfolio: &EnergySourcePortfolio) -> f64 {
+        [
+            (EnergySource::Petroleum, portfolio.petroleum_mwh),
+            (EnergySource::Hydrocarbons, portfolio.hydrocarbons_mwh),
+            (EnergySource::NaturalGas, portfolio.natural_gas_mwh),
+            (EnergySource::Coal, portfolio.coal_mwh),
+            (EnergySource::Nuclear, portfolio.nuclear_mwh),
+            (EnergySource::Solar, portfolio.solar_mwh),
+            (EnergySource::Geothermal, portfolio.geothermal_mwh),
+            (EnergySource::Wind, portfolio.wind_mwh),
+            (EnergySource::Biomass, portfolio.biomass_mwh),
+            (EnergySource::Hydropower, portfolio.hydropower_mwh),
+            (
+                EnergySource::WholesaleStorageLoad,
+                portfolio.wholesale_storage_load,
+            ),
+            (EnergySource::Unknown, portfolio.unknown_mwh),
+        ]
+        .iter()
+        .map(|(source, amount_mwh)| {
+            amount_mwh * self.factors.factor(source).total_kg_co2e_per_mwh()
+        })
+        .sum()
+    }
+
+    // Energy bought from a registered plant emits at the plant's own factor
+    // rather than the factors of its fuel
+    pub fn purchase_emitted_kg(
+        &self,
+        plant: Option<&PowerPlantDetails>,
+        portfolio: &EnergySourcePortfolio,
+    ) -> f64 {
+        match plant {
+            Some(plant) => plant.emission_factor_kg_co2e_per_mwh * portfolio.total_electricity_mwh,
+            None => self.emitted_kg(portfolio),
+        }
+    }
+}
+
+impl std::fmt::Display for EmissionFactorSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} v{} ({})", self.name, self.version, self.source)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::simulation::EnergySourcePortfolio;
+
+    use super::{EmissionFactor, EmissionFactorSet, EmissionFactors};
+
+    #[test]
+    fn should_include_upstream_emissions() {
+        let mut emission_factors = EmissionFactorSet::default();
+        emission_factors.factors = EmissionFactors {
+            natural_gas: EmissionFactor::new(400.0, 100.0),
+            ..Default::default()
+        };
+        let mut portfolio = EnergySourcePortfolio::default();
+        portfolio.total_electricity_mwh = 3.0;
+        portfolio.natural_gas_mwh = 2.0;
+        portfolio.wind_mwh = 1.0;
+
+        assert_eq!(emission_factors.emitted_kg(&portfolio), 1000.0);
+    }
+
+    #[test]
+    fn should_reject_set_missing_energy_source() {
+        let emission_factors = toml::from_str::<EmissionFactorSet>(
+            r#"
+            name = "incomplete"
+            version = "1"
+            source = "none"
+
+            [factors.coal]
+            combustion_kg_co2e_per_mwh = 1.0
+            "#,
+        );
+
+        assert!(emission_factors.is_err());
+    }
+}
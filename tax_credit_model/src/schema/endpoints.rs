@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
 pub enum Endpoint {
     #[default]
     None,
@@ -11,7 +11,10 @@ pub enum Endpoint {
     FetchEnergyCosts,
     FetchHourlyHistogram,
     FetchEmissions,
+    FetchStackEfficiency,
+    FetchDispatchHistogram,
     CreateElectrolyzer,
+    DataQualityPage,
     SelectElectrolyzer,
     SelectSimulation,
     SearchElectrolyzers,
@@ -19,6 +22,8 @@ pub enum Endpoint {
     GetCreateElectrolyzerForm,
     ElectrolyzerSelector,
     ExecuteSimulation,
+    FetchSimulationProgress,
+    CancelSimulation,
     GetElectrolyzer,
     GetSelectedElectrolyzer,
     GetSelectedSimulation,
@@ -45,6 +50,9 @@ impl Display for Endpoint {
             Self::GetSelectedSimulation => write!(f, "/get_selected_simulation"),
             Self::ListSimulations => write!(f, "/list_simulations"),
             Self::ExecuteSimulation => write!(f, "/execute_simulation"),
+            Self::FetchSimulationProgress => write!(f, "/simulation_progress"),
+            Self::CancelSimulation => write!(f, "/cancel_simulation"),
+            Self::DataQualityPage => write!(f, "/admin/data_quality"),
             _ => write!(f, ""),
         }
     }
@@ -1,14 +1,28 @@
 use std::str::FromStr;
 
+use rocket::FromFormField;
+use serde::{Deserialize, Serialize};
+
 use super::{errors::Error, simulation::EnergySource, time::Timestamp};
 
-#[derive(Default, Debug, PartialEq, Clone, Copy)]
+// Fuel mix data is first published from initial settlement and replaced once
+// the final settlement is run
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Settlement {
     #[default]
     Final,
     Initial,
 }
 
+impl std::fmt::Display for Settlement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Final => write!(f, "FINAL"),
+            Self::Initial => write!(f, "INITIAL"),
+        }
+    }
+}
+
 impl FromStr for Settlement {
     type Err = Error;
 
@@ -21,7 +35,7 @@ impl FromStr for Settlement {
     }
 }
 
-#[derive(Default, Debug, PartialEq)]
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
 pub enum RepeatedHourFlag {
     #[default]
     Repeated,
@@ -40,26 +54,89 @@ impl FromStr for RepeatedHourFlag {
     }
 }
 
-#[derive(Default, Debug, PartialEq)]
+// The hub or load zone an electrolyzer buys its energy at, written as the
+// ERCOT settlement point name
+#[derive(
+    FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Eq, Hash, Clone, Copy,
+)]
 pub enum SettlementPointLocation {
-    #[default]
+    #[field(value = "HB_NORTH")]
     NorthHub,
+    #[field(value = "HB_SOUTH")]
     SouthHub,
+    #[field(value = "HB_HOUSTON")]
     HustonHub,
+    #[field(value = "HB_WEST")]
     WestHub,
+    #[field(value = "HB_PAN")]
     PanhandleHub,
+    #[field(value = "HB_BUSAVG")]
     HubBusAverage,
+    #[default]
+    #[field(value = "HB_HUBAVG")]
     HubAverage,
+    #[field(value = "LZ_AEN")]
     AustinEnergyLoadingZone,
+    #[field(value = "LZ_CPS")]
     CPSEnergyLoadingZone,
+    #[field(value = "LZ_HOUSTON")]
     HustonLoadingZone,
+    #[field(value = "LZ_LCRA")]
     LowerColoradoRiverAuthorityLoadingZone,
+    #[field(value = "LZ_RAYBN")]
     RayburnElectricCooperativeLoadingZone,
+    #[field(value = "LZ_NORTH")]
     NorthLoadingZone,
+    #[field(value = "LZ_SOUTH")]
     SouthLoadingZone,
+    #[field(value = "LZ_WEST")]
     WestLoadingZone,
 }
 
+impl SettlementPointLocation {
+    pub fn all() -> Vec<SettlementPointLocation> {
+        vec![
+            Self::NorthHub,
+            Self::SouthHub,
+            Self::HustonHub,
+            Self::WestHub,
+            Self::PanhandleHub,
+            Self::HubBusAverage,
+            Self::HubAverage,
+            Self::AustinEnergyLoadingZone,
+            Self::CPSEnergyLoadingZone,
+            Self::HustonLoadingZone,
+            Self::LowerColoradoRiverAuthorityLoadingZone,
+            Self::RayburnElectricCooperativeLoadingZone,
+            Self::NorthLoadingZone,
+            Self::SouthLoadingZone,
+            Self::WestLoadingZone,
+        ]
+    }
+}
+
+impl std::fmt::Display for SettlementPointLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NorthHub => write!(f, "HB_NORTH"),
+            Self::SouthHub => write!(f, "HB_SOUTH"),
+            Self::HustonHub => write!(f, "HB_HOUSTON"),
+            Self::WestHub => write!(f, "HB_WEST"),
+            Self::PanhandleHub => write!(f, "HB_PAN"),
+            Self::HubBusAverage => write!(f, "HB_BUSAVG"),
+            Self::HubAverage => write!(f, "HB_HUBAVG"),
+            Self::AustinEnergyLoadingZone => write!(f, "LZ_AEN"),
+            Self::CPSEnergyLoadingZone => write!(f, "LZ_CPS"),
+            Self::HustonLoadingZone => write!(f, "LZ_HOUSTON"),
+            Self::LowerColoradoRiverAuthorityLoadingZone => write!(f, "LZ_LCRA"),
+            Self::RayburnElectricCooperativeLoadingZone => write!(f, "LZ_RAYBN"),
+            Self::NorthLoadingZone => write!(f, "LZ_NORTH"),
+            Self::SouthLoadingZone => write!(f, "LZ_SOUTH"),
+            Self::WestLoadingZone => write!(f, "LZ_WEST"),
+        }
+    }
+}
+
 impl FromStr for SettlementPointLocation {
     type Err = Error;
 
@@ -113,6 +190,7 @@ impl FromStr for SettlementPointType {
 #[derive(Default, Debug, PartialEq)]
 pub struct ErcotFuelMix {
     pub date: Timestamp,
+    pub fuel_type: String,
     pub fuel_source: EnergySource,
     pub settlement: Settlement,
     pub electricity_produced: f64,
@@ -126,3 +204,13 @@ pub struct ErcotRTMPrice {
     pub settlement_point_type: SettlementPointType,
     pub settlement_point_price: f64,
 }
+
+// Day-ahead prices are hourly, so each is repeated over the quarter hour
+// intervals of its hour
+#[derive(Default, Debug, PartialEq)]
+pub struct ErcotDAMPrice {
+    pub delivery_timestamp: Timestamp,
+    pub repeated_hour_flag: RepeatedHourFlag,
+    pub settlement_point_location: SettlementPointLocation,
+    pub settlement_point_price: f64,
+}
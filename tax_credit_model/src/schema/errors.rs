@@ -10,6 +10,7 @@ pub enum Error {
     InvalidArgument(String),
     Unauthenticated(String),
     Unknown(String),
+    Cancelled(String),
 }
 
 impl From<Error> for Status {
@@ -19,6 +20,7 @@ impl From<Error> for Status {
             Error::Unimplemented(_) => Status::NotImplemented,
             Error::InvalidArgument(_) => Status::BadRequest,
             Error::Unauthenticated(_) => Status::Unauthorized,
+            Error::Cancelled(_) => Status::Conflict,
             Error::Unknown(_) | Error::Poisoned(_) => Status::InternalServerError,
         }
     }
@@ -49,6 +51,10 @@ impl Error {
     pub fn unauthenticated(value: &str) -> Error {
         Error::Unauthenticated(String::from(value))
     }
+
+    pub fn cancelled(value: &str) -> Error {
+        Error::Cancelled(String::from(value))
+    }
 }
 
 // TODO: using errors convert to string to make easier
@@ -61,6 +67,7 @@ impl std::fmt::Display for Error {
             Self::InvalidArgument(value) => write!(f, "Invalid Argument: {}", value),
             Self::Unauthenticated(value) => write!(f, "Unauthenticated: {}", value),
             Self::Unknown(value) => write!(f, "Unknown: {}", value),
+            Self::Cancelled(value) => write!(f, "Cancelled: {}", value),
         }
     }
 }
@@ -0,0 +1,52 @@
+use rocket::FromForm;
+use serde::{Deserialize, Serialize};
+
+#[derive(FromForm, Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct FinancialConfiguration {
+    #[field(default_with = Some(8.0))]
+    pub discount_rate: f64,
+    #[field(default_with = Some(20))]
+    pub project_life_years: u32,
+    #[field(default_with = Some(0.0))]
+    pub hydrogen_price_usd_per_kg: f64,
+}
+
+impl Default for FinancialConfiguration {
+    fn default() -> Self {
+        Self {
+            discount_rate: 8.0,
+            project_life_years: 20,
+            hydrogen_price_usd_per_kg: 0.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct CashFlowYear {
+    pub year: u32,
+    pub kg_hydrogen: f64,
+    pub capex_usd: f64,
+    pub opex_usd: f64,
+    pub energy_usd: f64,
+    pub replacement_usd: f64,
+    pub tax_credit_usd: f64,
+    pub hydrogen_revenue_usd: f64,
+    pub net_usd: f64,
+    pub cumulative_usd: f64,
+}
+
+impl CashFlowYear {
+    pub fn costs_usd(&self) -> f64 {
+        self.capex_usd + self.opex_usd + self.energy_usd + self.replacement_usd
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct FinancialReport {
+    pub levelized_cost_usd_per_kg: Option<f64>,
+    pub levelized_cost_net_of_credit_usd_per_kg: Option<f64>,
+    pub net_present_value_usd: f64,
+    pub internal_rate_of_return: Option<f64>,
+    pub payback_years: Option<f64>,
+    pub cash_flows: Vec<CashFlowYear>,
+}
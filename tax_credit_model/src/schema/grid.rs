@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use rocket::form::{self, FromFormField, ValueField};
+use serde::{Deserialize, Serialize};
+
+use super::errors::Error;
+
+const BALANCING_AUTHORITY_PREFIX: &str = "EIA-930 ";
+
+// The ISO whose market a power grid's generations and prices come from, or a
+// balancing authority without one whose hourly generation EIA-930 reports
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+pub enum GridRegion {
+    #[default]
+    Ercot,
+    Caiso,
+    Pjm,
+    Spp,
+    BalancingAuthority(String),
+}
+
+impl GridRegion {
+    pub fn all() -> Vec<GridRegion> {
+        vec![Self::Ercot, Self::Caiso, Self::Pjm, Self::Spp]
+    }
+
+    // Each region's files are kept in its own directory under the data
+    // directory, balancing authorities by their EIA code under eia930
+    pub fn directory(&self) -> String {
+        match self {
+            Self::BalancingAuthority(code) => format!("eia930/{}", code),
+            _ => self.to_string().to_lowercase(),
+        }
+    }
+}
+
+impl std::fmt::Display for GridRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ercot => write!(f, "ERCOT"),
+            Self::Caiso => write!(f, "CAISO"),
+            Self::Pjm => write!(f, "PJM"),
+            Self::Spp => write!(f, "SPP"),
+            Self::BalancingAuthority(code) => write!(f, "{}{}", BALANCING_AUTHORITY_PREFIX, code),
+        }
+    }
+}
+
+impl FromStr for GridRegion {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ERCOT" => Ok(GridRegion::Ercot),
+            "CAISO" => Ok(GridRegion::Caiso),
+            "PJM" => Ok(GridRegion::Pjm),
+            "SPP" => Ok(GridRegion::Spp),
+            _ => match value.strip_prefix(BALANCING_AUTHORITY_PREFIX) {
+                Some(code)
+                    if !code.is_empty()
+                        && code
+                            .chars()
+                            .all(|character| character.is_ascii_alphanumeric()) =>
+                {
+                    Ok(GridRegion::BalancingAuthority(String::from(code)))
+                }
+                _ => Err(Error::invalid_argument(value)),
+            },
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromFormField<'r> for GridRegion {
+    fn from_value(field: ValueField<'r>) -> form::Result<'r, Self> {
+        Ok(field
+            .value
+            .parse()
+            .map_err(|_| form::Error::validation("Unknown grid region"))?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::GridRegion;
+
+    #[test]
+    fn should_parse_what_is_displayed() {
+        let regions = vec![
+            GridRegion::Ercot,
+            GridRegion::Pjm,
+            GridRegion::BalancingAuthority(String::from("PACE")),
+        ];
+
+        assert_eq!(
+            regions
+                .iter()
+                .map(|region| region.to_string().parse::<GridRegion>().unwrap())
+                .collect::<Vec<GridRegion>>(),
+            regions
+        );
+        assert_eq!(
+            GridRegion::BalancingAuthority(String::from("PACE")).directory(),
+            "eia930/PACE"
+        );
+        assert!("EIA-930 ../PJM".parse::<GridRegion>().is_err());
+    }
+}
@@ -1,8 +1,15 @@
+pub mod certificate;
+pub mod data_quality;
 pub mod electrolyzer;
+pub mod emissions;
 pub mod endpoints;
 pub mod ercot;
 pub mod errors;
+pub mod finance;
+pub mod grid;
 pub mod histogram;
+pub mod plant;
+pub mod policy;
 pub mod simulation;
 pub mod time;
 pub mod time_series;
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ercot::SettlementPointLocation,
+    grid::GridRegion,
+    simulation::{EnergySource, PowerPlantId},
+    time::Timestamp,
+};
+
+// Generations read from a region's fuel mix describe the whole grid rather than
+// a registered plant and are kept as this plant
+pub const GRID_MIX_PLANT_ID: PowerPlantId = 0;
+pub const GRID_MIX_PLANT_NAME: &str = "Grid mix";
+
+// A resource in the plant registry that energy can be bought from and
+// attributed to
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct PowerPlantDetails {
+    pub plant_id: PowerPlantId,
+    pub name: String,
+    pub fuel_type: EnergySource,
+    pub capacity_mw: f64,
+    pub region: GridRegion,
+    pub location: SettlementPointLocation,
+    pub commercial_operation_date: Timestamp,
+    pub emission_factor_kg_co2e_per_mwh: f64,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct PlantPurchaseSummary {
+    pub plant_id: PowerPlantId,
+    pub name: String,
+    pub fuel_type: String,
+    pub purchased_mwh: f64,
+    pub energy_usd: f64,
+}
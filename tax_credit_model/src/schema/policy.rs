@@ -0,0 +1,273 @@
+use rocket::{FromForm, FromFormField};
+use serde::{Deserialize, Serialize};
+
+use super::simulation::{AccountingMode, TaxCredit45VTier};
+
+const BASE_CREDIT_USD_PER_KG: f64 = 0.60;
+const PREVAILING_WAGE_MULTIPLIER: f64 = 5.0;
+const INFLATION_BASE_YEAR: i32 = 2022;
+
+pub const CREDIT_PERIOD_YEARS: u32 = 10;
+
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum TaxCredit45VRulesetVersion {
+    #[default]
+    Final,
+    Proposed,
+}
+
+impl TaxCredit45VRulesetVersion {
+    pub fn all() -> Vec<TaxCredit45VRulesetVersion> {
+        vec![Self::Final, Self::Proposed]
+    }
+}
+
+impl std::fmt::Display for TaxCredit45VRulesetVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Final => write!(f, "Final"),
+            Self::Proposed => write!(f, "Proposed"),
+        }
+    }
+}
+
+#[derive(FromForm, Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct TaxCreditPolicyConfiguration {
+    #[field(default_with = Some(TaxCredit45VRulesetVersion::Final))]
+    pub version: TaxCredit45VRulesetVersion,
+    pub prevailing_wage: bool,
+    pub apprenticeship: bool,
+    #[field(default_with = Some(0.0))]
+    pub inflation_rate: f64,
+}
+
+impl Default for TaxCreditPolicyConfiguration {
+    fn default() -> Self {
+        Self {
+            version: TaxCredit45VRulesetVersion::Final,
+            prevailing_wage: true,
+            apprenticeship: true,
+            inflation_rate: 0.0,
+        }
+    }
+}
+
+// Intensities below the upper bound, in kg CO2e / kg H2, earn the fraction of
+// the applicable credit
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub struct TaxCredit45VThreshold {
+    pub tier: TaxCredit45VTier,
+    pub upper_bound_co2_per_h2: f64,
+    pub credit_fraction: f64,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct TaxCredit45VRuleset {
+    pub version: TaxCredit45VRulesetVersion,
+    pub citation: String,
+    pub thresholds: Vec<TaxCredit45VThreshold>,
+    pub base_credit_usd_per_kg: f64,
+    pub prevailing_wage_multiplier: f64,
+    pub prevailing_wage: bool,
+    #[serde(default)]
+    pub apprenticeship: bool,
+    pub inflation_base_year: i32,
+    pub inflation_rate: f64,
+    pub hourly_matching_start_year: i32,
+}
+
+impl TaxCredit45VRuleset {
+    pub fn new(configuration: &TaxCreditPolicyConfiguration) -> Self {
+        let mut ruleset = match configuration.version {
+            TaxCredit45VRulesetVersion::Final => Self::final_rules(),
+            TaxCredit45VRulesetVersion::Proposed => Self::proposed_rules(),
+        };
+        ruleset.prevailing_wage = configuration.prevailing_wage;
+        ruleset.apprenticeship = configuration.apprenticeship;
+        ruleset.inflation_rate = configuration.inflation_rate;
+
+        ruleset
+    }
+
+    // Final regulations, which delay hourly matching until 2030
+    pub fn final_rules() -> Self {
+        Self {
+            version: TaxCredit45VRulesetVersion::Final,
+            citation: String::from("26 CFR 1.45V, T.D. 10023 (January 2025)"),
+            hourly_matching_start_year: 2030,
+            ..Self::statutory()
+        }
+    }
+
+    // Proposed regulations, which required hourly matching from 2028
+    pub fn proposed_rules() -> Self {
+        Self {
+            version: TaxCredit45VRulesetVersion::Proposed,
+            citation: String::from("Proposed 26 CFR 1.45V, REG-117631-23 (December 2023)"),
+            hourly_matching_start_year: 2028,
+            ..Self::statutory()
+        }
+    }
+
+    // The thresholds and amounts come from IRC 45V(b) and are shared by both
+    // rule makings
+    fn statutory() -> Self {
+        Self {
+            version: TaxCredit45VRulesetVersion::default(),
+            citation: String::new(),
+            thresholds: vec![
+                TaxCredit45VThreshold {
+                    tier: TaxCredit45VTier::Max,
+                    upper_bound_co2_per_h2: 0.45,
+                    credit_fraction: 1.0,
+                },
+                TaxCredit45VThreshold {
+                    tier: TaxCredit45VTier::Tier1,
+                    upper_bound_co2_per_h2: 1.5,
+                    credit_fraction: 0.334,
+                },
+                TaxCredit45VThreshold {
+                    tier: TaxCredit45VTier::Tier2,
+                    upper_bound_co2_per_h2: 2.5,
+                    credit_fraction: 0.25,
+                },
+                TaxCredit45VThreshold {
+                    tier: TaxCredit45VTier::Tier3,
+                    upper_bound_co2_per_h2: 4.0,
+                    credit_fraction: 0.2,
+                },
+            ],
+            base_credit_usd_per_kg: BASE_CREDIT_USD_PER_KG,
+            prevailing_wage_multiplier: PREVAILING_WAGE_MULTIPLIER,
+            prevailing_wage: true,
+            apprenticeship: true,
+            inflation_base_year: INFLATION_BASE_YEAR,
+            inflation_rate: 0.0,
+            hourly_matching_start_year: 0,
+        }
+    }
+
+    // Emissions may be matched over the year until hourly matching starts,
+    // after which an annual window is narrowed to the hour
+    pub fn accounting_mode(&self, requested: AccountingMode, year: i32) -> AccountingMode {
+        match requested {
+            AccountingMode::Annual if year >= self.hourly_matching_start_year => {
+                AccountingMode::Hourly
+            }
+            _ => requested,
+        }
+    }
+
+    pub fn tier(&self, co2_per_h2: f64) -> TaxCredit45VTier {
+        self.thresholds
+            .iter()
+            .find(|threshold| co2_per_h2 < threshold.upper_bound_co2_per_h2)
+            .map_or(TaxCredit45VTier::None, |threshold| threshold.tier)
+    }
+
+    // The base amount is inflation adjusted and rounded to the nearest tenth of
+    // a cent before the tier fraction and the multiplier, which needs both the
+    // prevailing wage and apprenticeship requirements met, apply
+    pub fn credit_usd_per_kg(&self, tier: TaxCredit45VTier, year: i32) -> f64 {
+        let credit_fraction = self
+            .thresholds
+            .iter()
+            .find(|threshold| threshold.tier == tier)
+            .map_or(0.0, |threshold| threshold.credit_fraction);
+        let years_adjusted = i32::max(year - self.inflation_base_year, 0);
+        let adjusted_credit =
+            self.base_credit_usd_per_kg * (1.0 + self.inflation_rate / 100.0).powi(years_adjusted);
+        let adjusted_credit = (adjusted_credit * 1000.0).round() / 1000.0;
+        let multiplier = if self.prevailing_wage && self.apprenticeship {
+            self.prevailing_wage_multiplier
+        } else {
+            1.0
+        };
+
+        adjusted_credit * multiplier * credit_fraction
+    }
+
+    pub fn max_credit_usd_per_kg(&self, year: i32) -> f64 {
+        self.credit_usd_per_kg(TaxCredit45VTier::Max, year)
+    }
+}
+
+impl std::fmt::Display for TaxCredit45VRuleset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} rules ({}), {} prevailing wage, {} apprenticeship, {}% yearly inflation, hourly matching from {}",
+            self.version,
+            self.citation,
+            if self.prevailing_wage {
+                "with"
+            } else {
+                "without"
+            },
+            if self.apprenticeship {
+                "with"
+            } else {
+                "without"
+            },
+            self.inflation_rate,
+            self.hourly_matching_start_year
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::schema::simulation::TaxCredit45VTier;
+
+    use super::{TaxCredit45VRuleset, TaxCredit45VRulesetVersion, TaxCreditPolicyConfiguration};
+
+    #[test]
+    fn should_assign_tiers_at_statutory_thresholds() {
+        let ruleset = TaxCredit45VRuleset::final_rules();
+
+        assert_eq!(ruleset.tier(0.44), TaxCredit45VTier::Max);
+        assert_eq!(ruleset.tier(0.45), TaxCredit45VTier::Tier1);
+        assert_eq!(ruleset.tier(2.0), TaxCredit45VTier::Tier2);
+        assert_eq!(ruleset.tier(3.99), TaxCredit45VTier::Tier3);
+        assert_eq!(ruleset.tier(4.0), TaxCredit45VTier::None);
+        assert_eq!(ruleset.tier(f64::NAN), TaxCredit45VTier::None);
+    }
+
+    #[test]
+    fn should_only_apply_multiplier_with_prevailing_wage_and_apprenticeship() {
+        let ruleset = TaxCredit45VRuleset::new(&TaxCreditPolicyConfiguration {
+            version: TaxCredit45VRulesetVersion::Proposed,
+            prevailing_wage: false,
+            apprenticeship: true,
+            inflation_rate: 0.0,
+        });
+        let without_apprenticeship = TaxCredit45VRuleset::new(&TaxCreditPolicyConfiguration {
+            version: TaxCredit45VRulesetVersion::Final,
+            prevailing_wage: true,
+            apprenticeship: false,
+            inflation_rate: 0.0,
+        });
+
+        assert_eq!(ruleset.max_credit_usd_per_kg(2023), 0.6);
+        assert_eq!(without_apprenticeship.max_credit_usd_per_kg(2023), 0.6);
+        assert_eq!(
+            TaxCredit45VRuleset::final_rules().max_credit_usd_per_kg(2023),
+            3.0
+        );
+    }
+
+    #[test]
+    fn should_adjust_credit_for_inflation_after_base_year() {
+        let ruleset = TaxCredit45VRuleset::new(&TaxCreditPolicyConfiguration {
+            version: TaxCredit45VRulesetVersion::Final,
+            prevailing_wage: false,
+            apprenticeship: false,
+            inflation_rate: 10.0,
+        });
+
+        assert_eq!(ruleset.max_credit_usd_per_kg(2020), 0.6);
+        assert_eq!(ruleset.max_credit_usd_per_kg(2024), 0.726);
+    }
+}
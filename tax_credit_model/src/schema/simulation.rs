@@ -1,14 +1,26 @@
-use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
-use rocket::FromForm;
+use rocket::{FromForm, FromFormField};
 use serde::{Deserialize, Serialize};
 
-use crate::components::{histogram::HistogramResponse, time_series::TimeSeriesChartResponse};
+use crate::components::{
+    credit_window::CreditWindowPanel, finance::FinancialReportPanel, histogram::HistogramResponse,
+    plant::PlantPurchasePanel, time_series::TimeSeriesChartResponse,
+};
 
 use super::{
     electrolyzer::ElectrolyzerId,
+    emissions::{EmissionFactorSetId, DEFAULT_EMISSION_FACTOR_SET_ID},
+    ercot::{Settlement, SettlementPointLocation},
     errors::{Error, Result},
-    time::{DateTimeRange, Timestamp},
+    finance::FinancialConfiguration,
+    grid::GridRegion,
+    plant::{PowerPlantDetails, GRID_MIX_PLANT_NAME},
+    policy::TaxCreditPolicyConfiguration,
+    time::{DateTimeRange, DisplayTimeZone, TimeRange, Timestamp},
 };
 
 pub type SimulationId = usize;
@@ -18,6 +30,28 @@ pub type PowerPlantId = usize;
 pub struct ExecuteSimulationRequest {
     pub electrolyzer_id: ElectrolyzerId,
     pub simulation_time_range: DateTimeRange,
+    pub dispatch_strategy: DispatchStrategyConfiguration,
+    pub financial: FinancialConfiguration,
+    #[field(default_with = Some(AccountingMode::Interval))]
+    pub accounting_mode: AccountingMode,
+    #[field(default_with = Some(String::from(DEFAULT_EMISSION_FACTOR_SET_ID)))]
+    pub emission_factor_set_id: EmissionFactorSetId,
+    pub tax_credit_policy: TaxCreditPolicyConfiguration,
+    pub credit_window: CreditWindowConfiguration,
+    #[field(default_with = Some(SimulationStep::FifteenMinutes))]
+    pub simulation_step: SimulationStep,
+    #[field(default_with = Some(DisplayTimeZone::CentralPrevailingTime))]
+    pub display_time_zone: DisplayTimeZone,
+    #[field(default_with = Some(ProcurementMode::RealTime))]
+    pub procurement_mode: ProcurementMode,
+    #[field(default_with = Some(GapFillPolicy::None))]
+    pub gap_fill_policy: GapFillPolicy,
+    #[field(default_with = Some(GridRegion::Ercot))]
+    pub grid_region: GridRegion,
+    #[field(default_with = Some(MarginalEmissionsSource::PriceSetting))]
+    pub marginal_emissions_source: MarginalEmissionsSource,
+    #[field(default_with = Some(CertificateMatching::None))]
+    pub certificate_matching: CertificateMatching,
 }
 
 impl ExecuteSimulationRequest {
@@ -25,24 +59,450 @@ impl ExecuteSimulationRequest {
         Self {
             electrolyzer_id,
             simulation_time_range,
+            dispatch_strategy: DispatchStrategyConfiguration::default(),
+            financial: FinancialConfiguration::default(),
+            accounting_mode: AccountingMode::default(),
+            emission_factor_set_id: String::from(DEFAULT_EMISSION_FACTOR_SET_ID),
+            tax_credit_policy: TaxCreditPolicyConfiguration::default(),
+            credit_window: CreditWindowConfiguration::default(),
+            simulation_step: SimulationStep::default(),
+            display_time_zone: DisplayTimeZone::default(),
+            procurement_mode: ProcurementMode::default(),
+            gap_fill_policy: GapFillPolicy::default(),
+            grid_region: GridRegion::default(),
+            marginal_emissions_source: MarginalEmissionsSource::default(),
+            certificate_matching: CertificateMatching::default(),
+        }
+    }
+}
+
+// The length of each simulated interval. Grid data at a different resolution
+// is split or summed onto the step before it is dispatched
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum SimulationStep {
+    FiveMinutes,
+    #[default]
+    FifteenMinutes,
+    SixtyMinutes,
+}
+
+impl SimulationStep {
+    pub fn all() -> Vec<SimulationStep> {
+        vec![Self::FiveMinutes, Self::FifteenMinutes, Self::SixtyMinutes]
+    }
+
+    pub fn minutes(&self) -> i64 {
+        match self {
+            Self::FiveMinutes => 5,
+            Self::FifteenMinutes => 15,
+            Self::SixtyMinutes => 60,
+        }
+    }
+
+    pub fn seconds(&self) -> i64 {
+        self.minutes() * 60
+    }
+
+    pub fn hours(&self) -> f64 {
+        self.minutes() as f64 / 60.0
+    }
+}
+
+impl std::fmt::Display for SimulationStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FiveMinutes => write!(f, "FiveMinutes"),
+            Self::FifteenMinutes => write!(f, "FifteenMinutes"),
+            Self::SixtyMinutes => write!(f, "SixtyMinutes"),
+        }
+    }
+}
+
+// The market energy is bought in. With a real-time true-up the electrolyzer's
+// full capacity is scheduled day-ahead and any energy it does not consume, or
+// consumes from a different plant, is settled at the real-time price
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum ProcurementMode {
+    #[default]
+    RealTime,
+    DayAhead,
+    DayAheadWithRealTimeTrueUp,
+}
+
+impl ProcurementMode {
+    pub fn all() -> Vec<ProcurementMode> {
+        vec![
+            Self::RealTime,
+            Self::DayAhead,
+            Self::DayAheadWithRealTimeTrueUp,
+        ]
+    }
+}
+
+impl std::fmt::Display for ProcurementMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RealTime => write!(f, "RealTime"),
+            Self::DayAhead => write!(f, "DayAhead"),
+            Self::DayAheadWithRealTimeTrueUp => write!(f, "DayAheadWithRealTimeTrueUp"),
+        }
+    }
+}
+
+// How intervals missing from a plant's generations are filled before a
+// simulation runs. Without a policy a missing interval fails the simulation
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum GapFillPolicy {
+    #[default]
+    None,
+    Linear,
+    PreviousValue,
+    SameHourPreviousDay,
+}
+
+impl GapFillPolicy {
+    pub fn all() -> Vec<GapFillPolicy> {
+        vec![
+            Self::None,
+            Self::Linear,
+            Self::PreviousValue,
+            Self::SameHourPreviousDay,
+        ]
+    }
+}
+
+impl std::fmt::Display for GapFillPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Linear => write!(f, "Linear"),
+            Self::PreviousValue => write!(f, "PreviousValue"),
+            Self::SameHourPreviousDay => write!(f, "SameHourPreviousDay"),
+        }
+    }
+}
+
+// The marginal emissions rate is either inferred from the fuel setting the price
+// or read from an imported series, which falls back to the price-setting fuel
+// for intervals the series does not cover
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum MarginalEmissionsSource {
+    #[default]
+    PriceSetting,
+    ImportedSeries,
+}
+
+impl MarginalEmissionsSource {
+    pub fn all() -> Vec<MarginalEmissionsSource> {
+        vec![Self::PriceSetting, Self::ImportedSeries]
+    }
+}
+
+impl std::fmt::Display for MarginalEmissionsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PriceSetting => write!(f, "PriceSetting"),
+            Self::ImportedSeries => write!(f, "ImportedSeries"),
+        }
+    }
+}
+
+// Energy attribute certificates are only claimed when matched hour by hour
+// against the energy consumed
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum CertificateMatching {
+    #[default]
+    None,
+    Hourly,
+}
+
+impl CertificateMatching {
+    pub fn all() -> Vec<CertificateMatching> {
+        vec![Self::None, Self::Hourly]
+    }
+}
+
+impl std::fmt::Display for CertificateMatching {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Hourly => write!(f, "Hourly"),
+        }
+    }
+}
+
+// Credit window years without grid data replay a complete historical year,
+// either as recorded or with its prices escalated to the simulated year
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum MissingYearSource {
+    #[default]
+    HistoricalYear,
+    ScaledSyntheticYear,
+}
+
+impl MissingYearSource {
+    pub fn all() -> Vec<MissingYearSource> {
+        vec![Self::HistoricalYear, Self::ScaledSyntheticYear]
+    }
+}
+
+impl std::fmt::Display for MissingYearSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HistoricalYear => write!(f, "HistoricalYear"),
+            Self::ScaledSyntheticYear => write!(f, "ScaledSyntheticYear"),
+        }
+    }
+}
+
+#[derive(FromForm, Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct CreditWindowConfiguration {
+    pub enabled: bool,
+    pub placed_in_service: String,
+    #[field(default_with = Some(MissingYearSource::HistoricalYear))]
+    pub missing_year_source: MissingYearSource,
+    pub historical_year: Option<i32>,
+    #[field(default_with = Some(0.0))]
+    pub price_escalation_rate: f64,
+}
+
+// The slice of a calendar year simulated as part of the credit window and the
+// grid data year it is simulated with
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct CreditWindowYear {
+    pub year: i32,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub source_year: i32,
+    pub price_scale: f64,
+}
+
+impl std::fmt::Display for CreditWindowYear {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.source_year == self.year {
+            write!(f, "Recorded")
+        } else if self.price_scale == 1.0 {
+            write!(f, "Replayed {}", self.source_year)
+        } else {
+            write!(
+                f,
+                "Replayed {} at {:.2}x prices",
+                self.source_year, self.price_scale
+            )
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct CreditYearSummary {
+    pub year: i32,
+    pub data_source: String,
+    pub hours_operated: f64,
+    pub kg_hydrogen: f64,
+    pub energy_usd: f64,
+    pub tax_credit_usd: f64,
+    pub max_credit_usd_per_kg: f64,
+}
+
+// The window emissions and hydrogen production are summed over before the
+// emissions intensity is compared against the 45V thresholds
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum AccountingMode {
+    #[default]
+    Interval,
+    Hourly,
+    Annual,
+}
+
+impl AccountingMode {
+    pub fn all() -> Vec<AccountingMode> {
+        vec![Self::Interval, Self::Hourly, Self::Annual]
+    }
+}
+
+impl std::fmt::Display for AccountingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interval => write!(f, "Interval"),
+            Self::Hourly => write!(f, "Hourly"),
+            Self::Annual => write!(f, "Annual"),
+        }
+    }
+}
+
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum DispatchObjective {
+    #[default]
+    MinimizeCost,
+    MinimizeNetCost,
+}
+
+impl std::fmt::Display for DispatchObjective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MinimizeCost => write!(f, "MinimizeCost"),
+            Self::MinimizeNetCost => write!(f, "MinimizeNetCost"),
+        }
+    }
+}
+
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum DispatchStrategyKind {
+    #[default]
+    AlwaysOn,
+    PriceCeiling,
+    CarbonIntensityCeiling,
+    CheapestHours,
+}
+
+impl DispatchStrategyKind {
+    pub fn all() -> Vec<DispatchStrategyKind> {
+        vec![
+            Self::AlwaysOn,
+            Self::PriceCeiling,
+            Self::CarbonIntensityCeiling,
+            Self::CheapestHours,
+        ]
+    }
+}
+
+impl std::fmt::Display for DispatchStrategyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlwaysOn => write!(f, "AlwaysOn"),
+            Self::PriceCeiling => write!(f, "PriceCeiling"),
+            Self::CarbonIntensityCeiling => write!(f, "CarbonIntensityCeiling"),
+            Self::CheapestHours => write!(f, "CheapestHours"),
+        }
+    }
+}
+
+#[derive(FromForm, Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct DispatchStrategyConfiguration {
+    #[field(default_with = Some(DispatchStrategyKind::AlwaysOn))]
+    pub kind: DispatchStrategyKind,
+    #[field(default_with = Some(DispatchObjective::MinimizeCost))]
+    pub objective: DispatchObjective,
+    pub price_ceiling_usd_per_mwh: Option<f64>,
+    pub carbon_intensity_ceiling: Option<f64>,
+    pub hours_per_day: Option<u32>,
+}
+
+impl std::fmt::Display for DispatchStrategyConfiguration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            DispatchStrategyKind::AlwaysOn => write!(f, "Always on")?,
+            DispatchStrategyKind::PriceCeiling => write!(
+                f,
+                "Price ceiling of ${} / MWh",
+                self.price_ceiling_usd_per_mwh.unwrap_or_default()
+            )?,
+            DispatchStrategyKind::CarbonIntensityCeiling => write!(
+                f,
+                "Carbon intensity ceiling of {} kg CO2 / kg H2",
+                self.carbon_intensity_ceiling.unwrap_or_default()
+            )?,
+            DispatchStrategyKind::CheapestHours => write!(
+                f,
+                "Cheapest {} hours per day",
+                self.hours_per_day.unwrap_or_default()
+            )?,
+        }
+
+        write!(f, " ranked by {}", self.objective)
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum DispatchReason {
+    #[default]
+    Dispatched,
+    SupplyLimited,
+    PartiallyCurtailed,
+    AbovePriceCeiling,
+    AboveCarbonIntensityCeiling,
+    OutsideCheapestHours,
+    BelowMinimumStableLoad,
+    NoCapacity,
+}
+
+impl DispatchReason {
+    pub fn all() -> Vec<DispatchReason> {
+        vec![
+            Self::Dispatched,
+            Self::SupplyLimited,
+            Self::PartiallyCurtailed,
+            Self::AbovePriceCeiling,
+            Self::AboveCarbonIntensityCeiling,
+            Self::OutsideCheapestHours,
+            Self::BelowMinimumStableLoad,
+            Self::NoCapacity,
+        ]
+    }
+}
+
+impl std::fmt::Display for DispatchReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dispatched => write!(f, "Dispatched"),
+            Self::SupplyLimited => write!(f, "Supply Limited"),
+            Self::PartiallyCurtailed => write!(f, "Partially Curtailed"),
+            Self::AbovePriceCeiling => write!(f, "Above Price Ceiling"),
+            Self::AboveCarbonIntensityCeiling => write!(f, "Above Carbon Ceiling"),
+            Self::OutsideCheapestHours => write!(f, "Outside Cheapest Hours"),
+            Self::BelowMinimumStableLoad => write!(f, "Below Minimum Stable Load"),
+            Self::NoCapacity => write!(f, "No Capacity"),
         }
     }
 }
 
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct DispatchDecision {
+    pub simulation_id: SimulationId,
+    pub electrolyzer_id: ElectrolyzerId,
+    pub timestamp: Timestamp,
+    pub demand_mwh: f64,
+    pub purchased_mwh: f64,
+    pub reason: DispatchReason,
+    #[serde(default)]
+    pub gap_filled: bool,
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub enum SimulationStatus {
+    Queued,
+    Running {
+        progress: f64,
+    },
     #[default]
     Complete,
+    Failed,
+    Cancelled,
+}
+
+impl SimulationStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Complete | Self::Failed | Self::Cancelled)
+    }
 }
 
 impl std::fmt::Display for SimulationStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::Queued => write!(f, "Queued"),
+            Self::Running { progress } => write!(f, "Running ({:.0}%)", progress * 100.0),
             Self::Complete => write!(f, "Complete"),
+            Self::Failed => write!(f, "Failed"),
+            Self::Cancelled => write!(f, "Cancelled"),
         }
     }
 }
 
+#[derive(FromForm, Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct SimulationJobRequest {
+    pub simulation_id: SimulationId,
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub struct TaxCreditSummary {
     pub credit_hours_full: f64,
@@ -52,13 +512,35 @@ pub struct TaxCreditSummary {
     pub credit_hours_none: f64,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct SimulationResult {
     pub hourly_histogram: HistogramResponse,
+    pub dispatch_histogram: HistogramResponse,
+    pub dispatch_strategy: String,
+    pub accounting_mode: String,
+    pub procurement_mode: String,
+    pub gap_fill_policy: String,
+    pub gap_filled_intervals: usize,
+    pub real_time_fallback_intervals: usize,
+    pub real_time_fallback_reasons: Vec<String>,
+    pub grid_region: String,
+    pub marginal_emissions_source: String,
+    pub average_kg_co2e_per_kg_hydrogen: f64,
+    pub marginal_kg_co2e_per_kg_hydrogen: f64,
+    pub certificate_matching: String,
+    pub retired_certificate_mwh: f64,
+    pub emission_factor_set: String,
+    pub tax_credit_ruleset: String,
     pub tax_credit_summary: TaxCreditSummary,
     pub emissions: TimeSeriesChartResponse,
     pub hydrogen_productions: TimeSeriesChartResponse,
     pub energy_costs: TimeSeriesChartResponse,
+    pub stack_efficiency: TimeSeriesChartResponse,
+    pub stack_replacement_count: usize,
+    pub replacement_cost_usd: f64,
+    pub financial_report: FinancialReportPanel,
+    pub credit_years: CreditWindowPanel,
+    pub plant_purchases: PlantPurchasePanel,
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
@@ -77,6 +559,24 @@ pub struct HydrogenProductionEvent {
     pub kg_hydrogen: f64,
 }
 
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct StackEfficiencyEvent {
+    pub simulation_id: SimulationId,
+    pub electrolyzer_id: ElectrolyzerId,
+    pub timestamp: Timestamp,
+    pub efficiency: f64,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
+pub struct StackReplacementEvent {
+    pub simulation_id: SimulationId,
+    pub electrolyzer_id: ElectrolyzerId,
+    pub replacement_timestamp: Timestamp,
+    pub operating_hours: f64,
+    pub efficiency_at_replacement: f64,
+    pub cost_usd: f64,
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone, Copy)]
 pub struct EnergySourcePortfolio {
     pub total_electricity_mwh: f64,
@@ -114,6 +614,23 @@ impl EnergySourcePortfolio {
         }
     }
 
+    pub fn energy_mwh(&self, source: &EnergySource) -> f64 {
+        match source {
+            EnergySource::Coal => self.coal_mwh,
+            EnergySource::NaturalGas => self.natural_gas_mwh,
+            EnergySource::Solar => self.solar_mwh,
+            EnergySource::Petroleum => self.petroleum_mwh,
+            EnergySource::Hydropower => self.hydropower_mwh,
+            EnergySource::Hydrocarbons => self.hydrocarbons_mwh,
+            EnergySource::Nuclear => self.nuclear_mwh,
+            EnergySource::Geothermal => self.geothermal_mwh,
+            EnergySource::Wind => self.wind_mwh,
+            EnergySource::Biomass => self.biomass_mwh,
+            EnergySource::WholesaleStorageLoad => self.wholesale_storage_load,
+            EnergySource::Unknown => self.unknown_mwh,
+        }
+    }
+
     pub fn merge(
         portfolio_a: &EnergySourcePortfolio,
         portfolio_b: &EnergySourcePortfolio,
@@ -145,10 +662,15 @@ impl EnergySourcePortfolio {
             return Err(Error::invalid_argument("Total electricity exceeded"));
         }
 
-        let scale_factor = amount_mwh / portfolio.total_electricity_mwh;
-
         Ok(EnergySourcePortfolio {
             total_electricity_mwh: amount_mwh,
+            ..EnergySourcePortfolio::scale(portfolio, amount_mwh / portfolio.total_electricity_mwh)
+        })
+    }
+
+    pub fn scale(portfolio: &EnergySourcePortfolio, scale_factor: f64) -> EnergySourcePortfolio {
+        EnergySourcePortfolio {
+            total_electricity_mwh: portfolio.total_electricity_mwh * scale_factor,
             coal_mwh: portfolio.coal_mwh * scale_factor,
             natural_gas_mwh: portfolio.natural_gas_mwh * scale_factor,
             solar_mwh: portfolio.solar_mwh * scale_factor,
@@ -161,19 +683,97 @@ impl EnergySourcePortfolio {
             biomass_mwh: portfolio.biomass_mwh * scale_factor,
             wholesale_storage_load: portfolio.wholesale_storage_load * scale_factor,
             unknown_mwh: portfolio.unknown_mwh * scale_factor,
-        })
+        }
     }
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub struct PowerGrid {
+    #[serde(default)]
+    pub region: GridRegion,
     pub power_plants: Vec<PowerPlant>,
 }
 
 impl PowerGrid {
+    pub fn new(region: GridRegion) -> Self {
+        Self {
+            region,
+            power_plants: vec![],
+        }
+    }
+
+    // Sets the sale price of the generations covering the time range to the
+    // price at the settlement point in the market energy is procured in so
+    // dispatch buys energy at the electrolyzer's own location, or at a
+    // registered plant's location for energy bought from it. A real-time
+    // true-up also keeps the real-time price to settle deviations from the
+    // day-ahead schedule. Intervals without a day-ahead price are bought
+    // real-time instead and returned with the reason
+    pub fn priced_at(
+        &self,
+        settlement_point: SettlementPointLocation,
+        procurement_mode: ProcurementMode,
+        time_range: &TimeRange,
+        real_time_fallbacks: &mut BTreeMap<Timestamp, String>,
+    ) -> Result<PowerGrid> {
+        let mut power_grid = PowerGrid::new(self.region.clone());
+
+        for power_plant in &self.power_plants {
+            let settlement_point = power_plant
+                .details
+                .as_ref()
+                .map_or(settlement_point, |details| details.location);
+            let generations = power_plant
+                .generations_covering(time_range)
+                .iter()
+                .map(|generation| {
+                    let real_time_price = generation.price_at(settlement_point)?;
+                    let mut day_ahead_price = || match generation
+                        .day_ahead_price_at(settlement_point)
+                    {
+                        Ok(price) => price,
+                        Err(err) => {
+                            real_time_fallbacks.insert(generation.time_generated, err.to_string());
+                            real_time_price
+                        }
+                    };
+
+                    Ok(match procurement_mode {
+                        ProcurementMode::RealTime => GenerationMetric {
+                            sale_price_usd_per_mwh: real_time_price,
+                            real_time_price_usd_per_mwh: None,
+                            ..generation.clone()
+                        },
+                        ProcurementMode::DayAhead => GenerationMetric {
+                            sale_price_usd_per_mwh: day_ahead_price(),
+                            real_time_price_usd_per_mwh: None,
+                            ..generation.clone()
+                        },
+                        ProcurementMode::DayAheadWithRealTimeTrueUp => GenerationMetric {
+                            sale_price_usd_per_mwh: day_ahead_price(),
+                            real_time_price_usd_per_mwh: Some(real_time_price),
+                            ..generation.clone()
+                        },
+                    })
+                })
+                .collect::<Result<Vec<GenerationMetric>>>()?;
+
+            power_grid.add_power_plant(power_plant.with_generations(generations));
+        }
+
+        Ok(power_grid)
+    }
+
     pub fn add_power_plant(&mut self, power_plant: PowerPlant) {
         self.power_plants.push(power_plant);
     }
+
+    pub fn plant_details(&self, plant_id: PowerPlantId) -> Option<&PowerPlantDetails> {
+        self.power_plants
+            .iter()
+            .find(|power_plant| power_plant.plant_id == plant_id)
+            .and_then(|power_plant| power_plant.details.as_ref())
+    }
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Clone)]
@@ -193,6 +793,25 @@ pub enum EnergySource {
     Unknown,
 }
 
+impl std::fmt::Display for EnergySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Petroleum => write!(f, "Petroleum"),
+            Self::Hydrocarbons => write!(f, "Hydrocarbons"),
+            Self::NaturalGas => write!(f, "Natural Gas"),
+            Self::Coal => write!(f, "Coal"),
+            Self::Nuclear => write!(f, "Nuclear"),
+            Self::Solar => write!(f, "Solar"),
+            Self::Geothermal => write!(f, "Geothermal"),
+            Self::Wind => write!(f, "Wind"),
+            Self::Biomass => write!(f, "Biomass"),
+            Self::Hydropower => write!(f, "Hydropower"),
+            Self::WholesaleStorageLoad => write!(f, "Wholesale Storage Load"),
+            Self::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
 impl FromStr for EnergySource {
     type Err = Error;
 
@@ -219,6 +838,20 @@ pub struct GenerationMetric {
     pub time_generated: Timestamp,
     pub sale_price_usd_per_mwh: f64,
     pub portfolio: EnergySourcePortfolio,
+    #[serde(default)]
+    pub settlement_point_prices: HashMap<SettlementPointLocation, f64>,
+    #[serde(default)]
+    pub settlement: Settlement,
+    #[serde(default)]
+    pub day_ahead_settlement_point_prices: HashMap<SettlementPointLocation, f64>,
+    #[serde(default)]
+    pub real_time_price_usd_per_mwh: Option<f64>,
+    #[serde(default)]
+    pub filled: bool,
+    #[serde(default)]
+    pub region: GridRegion,
+    #[serde(default)]
+    pub marginal_emission_rate_kg_co2e_per_mwh: Option<f64>,
 }
 
 impl GenerationMetric {
@@ -233,23 +866,219 @@ impl GenerationMetric {
             time_generated: Timestamp::new(time_generated.seconds, time_generated.nanos),
             sale_price_usd_per_mwh,
             portfolio,
+            settlement_point_prices: HashMap::new(),
+            settlement: Settlement::default(),
+            day_ahead_settlement_point_prices: HashMap::new(),
+            real_time_price_usd_per_mwh: None,
+            filled: false,
+            region: GridRegion::default(),
+            marginal_emission_rate_kg_co2e_per_mwh: None,
+        }
+    }
+
+    // Generations without prices by settlement point carry a single system
+    // price that every location pays
+    pub fn price_at(&self, settlement_point: SettlementPointLocation) -> Result<f64> {
+        if self.settlement_point_prices.is_empty() {
+            return Ok(self.sale_price_usd_per_mwh);
+        }
+
+        self.settlement_point_prices
+            .get(&settlement_point)
+            .copied()
+            .ok_or_else(|| {
+                Error::not_found(&format!(
+                    "No {} price at {}",
+                    settlement_point, self.time_generated.seconds
+                ))
+            })
+    }
+
+    // Linearly weights each price and source between the generations either
+    // side of the timestamp, flagging the result as filled
+    pub fn interpolate(
+        before: &GenerationMetric,
+        after: &GenerationMetric,
+        timestamp: &Timestamp,
+    ) -> GenerationMetric {
+        let span = (after.time_generated.seconds - before.time_generated.seconds) as f64;
+        let weight = if span > 0.0 {
+            (timestamp.seconds - before.time_generated.seconds) as f64 / span
+        } else {
+            0.0
+        };
+        let interpolate_prices =
+            |before: &HashMap<SettlementPointLocation, f64>,
+             after: &HashMap<SettlementPointLocation, f64>| {
+                before
+                    .iter()
+                    .filter_map(|(location, price)| {
+                        after
+                            .get(location)
+                            .map(|next| (*location, price + (next - price) * weight))
+                    })
+                    .collect::<HashMap<SettlementPointLocation, f64>>()
+            };
+
+        GenerationMetric {
+            plant_id: before.plant_id,
+            time_generated: *timestamp,
+            sale_price_usd_per_mwh: before.sale_price_usd_per_mwh
+                + (after.sale_price_usd_per_mwh - before.sale_price_usd_per_mwh) * weight,
+            portfolio: EnergySourcePortfolio::merge(
+                &EnergySourcePortfolio::scale(&before.portfolio, 1.0 - weight),
+                &EnergySourcePortfolio::scale(&after.portfolio, weight),
+            ),
+            settlement_point_prices: interpolate_prices(
+                &before.settlement_point_prices,
+                &after.settlement_point_prices,
+            ),
+            settlement: before.settlement,
+            day_ahead_settlement_point_prices: interpolate_prices(
+                &before.day_ahead_settlement_point_prices,
+                &after.day_ahead_settlement_point_prices,
+            ),
+            real_time_price_usd_per_mwh: before
+                .real_time_price_usd_per_mwh
+                .zip(after.real_time_price_usd_per_mwh)
+                .map(|(before, after)| before + (after - before) * weight),
+            filled: true,
+            region: before.region.clone(),
+            marginal_emission_rate_kg_co2e_per_mwh: before
+                .marginal_emission_rate_kg_co2e_per_mwh
+                .zip(after.marginal_emission_rate_kg_co2e_per_mwh)
+                .map(|(before, after)| before + (after - before) * weight),
+        }
+    }
+
+    // Like real-time prices, generations without any prices by settlement
+    // point are bought day-ahead at their single system price. Real-time prices
+    // without day-ahead ones mean the month's DAM file was never ingested
+    pub fn day_ahead_price_at(&self, settlement_point: SettlementPointLocation) -> Result<f64> {
+        if self.day_ahead_settlement_point_prices.is_empty()
+            && self.settlement_point_prices.is_empty()
+        {
+            return Ok(self.sale_price_usd_per_mwh);
         }
+
+        if self.day_ahead_settlement_point_prices.is_empty() {
+            let time_zone = match self.region {
+                GridRegion::Ercot => DisplayTimeZone::CentralPrevailingTime,
+                _ => DisplayTimeZone::Utc,
+            };
+            let month =
+                time_zone.format(&Timestamp::new(self.time_generated.seconds - 1, 0), "%B %Y")?;
+
+            return Err(Error::not_found(&format!(
+                "No day-ahead prices were ingested for {} {}, add its DAM settlement point prices or procure real-time",
+                self.region, month
+            )));
+        }
+
+        self.day_ahead_settlement_point_prices
+            .get(&settlement_point)
+            .copied()
+            .ok_or_else(|| {
+                Error::not_found(&format!(
+                    "No day-ahead {} price at {}",
+                    settlement_point, self.time_generated.seconds
+                ))
+            })
     }
 }
 
+// Generations are kept sorted by the time they were generated so lookups are a
+// binary search instead of a scan over the whole year. Plants in the registry
+// carry their details, the grid mix does not
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub struct PowerPlant {
     pub plant_id: PowerPlantId,
-    pub generations: Vec<GenerationMetric>,
+    #[serde(default)]
+    pub details: Option<PowerPlantDetails>,
+    generations: Vec<GenerationMetric>,
 }
 
 impl PowerPlant {
+    pub fn new(plant_id: PowerPlantId, generations: Vec<GenerationMetric>) -> Self {
+        let mut generations = generations;
+        generations.sort_by_key(|generation| generation.time_generated);
+
+        Self {
+            plant_id,
+            details: None,
+            generations,
+        }
+    }
+
+    // The same plant with its generations replaced
+    pub fn with_generations(&self, generations: Vec<GenerationMetric>) -> Self {
+        Self {
+            details: self.details.clone(),
+            ..PowerPlant::new(self.plant_id, generations)
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.details
+            .as_ref()
+            .map_or(GRID_MIX_PLANT_NAME, |details| &details.name)
+    }
+
     pub fn add_generation(&mut self, generation: GenerationMetric) {
-        self.generations.push(generation);
+        let is_latest = self
+            .generations
+            .last()
+            .is_none_or(|latest| latest.time_generated <= generation.time_generated);
+
+        if is_latest {
+            self.generations.push(generation);
+            return;
+        }
+
+        let index = self
+            .generations
+            .partition_point(|existing| existing.time_generated <= generation.time_generated);
+        self.generations.insert(index, generation);
+    }
+
+    pub fn generations(&self) -> &[GenerationMetric] {
+        &self.generations
+    }
+
+    pub fn generation_at(&self, timestamp: &Timestamp) -> Option<&GenerationMetric> {
+        self.generations
+            .binary_search_by_key(timestamp, |generation| generation.time_generated)
+            .ok()
+            .map(|index| &self.generations[index])
+    }
+
+    // Generations stamped at the end of an interval that overlaps the range,
+    // the last one covering the range's end
+    pub fn generations_covering(&self, time_range: &TimeRange) -> &[GenerationMetric] {
+        let first = self
+            .generations
+            .partition_point(|generation| generation.time_generated <= time_range.start);
+        let last = self
+            .generations
+            .partition_point(|generation| generation.time_generated < time_range.end);
+
+        &self.generations[first..(last + 1).min(self.generations.len()).max(first)]
+    }
+
+    // Generations from the start of the range up to but excluding its end
+    pub fn generations_between(&self, start: &Timestamp, end: &Timestamp) -> &[GenerationMetric] {
+        let first = self
+            .generations
+            .partition_point(|generation| generation.time_generated < *start);
+        let last = self
+            .generations
+            .partition_point(|generation| generation.time_generated < *end);
+
+        &self.generations[first..last.max(first)]
     }
 }
 
-#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum TaxCredit45VTier {
     Max,
     Tier1,
@@ -259,18 +1088,6 @@ pub enum TaxCredit45VTier {
     None,
 }
 
-impl TaxCredit45VTier {
-    pub fn value(&self) -> f64 {
-        match self {
-            Self::Max => 3.0,
-            Self::Tier1 => 3.0 * 0.334,
-            Self::Tier2 => 3.0 * 0.25,
-            Self::Tier3 => 3.0 * 0.2,
-            Self::None => 0.0,
-        }
-    }
-}
-
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub struct TaxCredit45V {
     pub tier: TaxCredit45VTier,
@@ -286,3 +1103,276 @@ pub struct EnergyTransaction {
     pub price_usd: f64,
     pub portfolio: EnergySourcePortfolio,
 }
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::{EnergySourcePortfolio, GenerationMetric, PowerGrid, PowerPlant, ProcurementMode};
+    use crate::schema::{
+        ercot::SettlementPointLocation,
+        plant::PowerPlantDetails,
+        time::{TimeRange, Timestamp},
+    };
+
+    fn create_power_plant(seconds: Vec<i64>) -> PowerPlant {
+        let mut power_plant = PowerPlant::default();
+
+        for seconds in seconds {
+            power_plant.add_generation(GenerationMetric::new(
+                0,
+                &Timestamp::new(seconds, 0),
+                seconds as f64,
+                EnergySourcePortfolio::default(),
+            ));
+        }
+
+        power_plant
+    }
+
+    fn to_seconds(generations: &[GenerationMetric]) -> Vec<i64> {
+        generations
+            .iter()
+            .map(|generation| generation.time_generated.seconds)
+            .collect()
+    }
+
+    #[test]
+    fn should_keep_generations_sorted() {
+        let power_plant = create_power_plant(vec![900, 0, 2700, 1800]);
+
+        assert_eq!(
+            to_seconds(power_plant.generations()),
+            vec![0, 900, 1800, 2700]
+        );
+    }
+
+    #[test]
+    fn should_find_generation_at_exact_interval() {
+        let power_plant = create_power_plant(vec![0, 900, 1800]);
+
+        assert_eq!(
+            power_plant
+                .generation_at(&Timestamp::new(900, 0))
+                .map(|generation| generation.sale_price_usd_per_mwh),
+            Some(900.0)
+        );
+        assert_eq!(power_plant.generation_at(&Timestamp::new(901, 0)), None);
+    }
+
+    #[test]
+    fn should_find_generations_in_range() {
+        let power_plant = create_power_plant(vec![0, 900, 1800, 2700, 3600]);
+
+        assert_eq!(
+            to_seconds(
+                power_plant.generations_between(&Timestamp::new(900, 0), &Timestamp::new(3600, 0))
+            ),
+            vec![900, 1800, 2700]
+        );
+        assert!(power_plant
+            .generations_between(&Timestamp::new(3600, 0), &Timestamp::new(0, 0))
+            .is_empty());
+    }
+
+    #[test]
+    fn should_price_generations_at_settlement_point() {
+        let mut generation =
+            GenerationMetric::new(0, &Timestamp::new(0, 0), 20.0, Default::default());
+        generation.settlement_point_prices = HashMap::from([
+            (SettlementPointLocation::HubAverage, 20.0),
+            (SettlementPointLocation::WestLoadingZone, -5.0),
+        ]);
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(PowerPlant::new(0, vec![generation]));
+
+        let priced_grid = power_grid
+            .priced_at(
+                SettlementPointLocation::WestLoadingZone,
+                ProcurementMode::RealTime,
+                &TimeRange::all(),
+                &mut BTreeMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            priced_grid.power_plants[0].generations()[0].sale_price_usd_per_mwh,
+            -5.0
+        );
+        assert!(power_grid
+            .priced_at(
+                SettlementPointLocation::NorthHub,
+                ProcurementMode::RealTime,
+                &TimeRange::all(),
+                &mut BTreeMap::new()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn should_price_registered_plant_at_its_location() {
+        let mut generation =
+            GenerationMetric::new(7, &Timestamp::new(0, 0), 20.0, Default::default());
+        generation.settlement_point_prices = HashMap::from([
+            (SettlementPointLocation::HubAverage, 20.0),
+            (SettlementPointLocation::WestLoadingZone, -5.0),
+        ]);
+        let mut power_plant = PowerPlant::new(7, vec![generation]);
+        power_plant.details = Some(PowerPlantDetails {
+            plant_id: 7,
+            location: SettlementPointLocation::WestLoadingZone,
+            ..Default::default()
+        });
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(power_plant);
+
+        let priced_grid = power_grid
+            .priced_at(
+                SettlementPointLocation::HubAverage,
+                ProcurementMode::RealTime,
+                &TimeRange::all(),
+                &mut BTreeMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            priced_grid.power_plants[0].generations()[0].sale_price_usd_per_mwh,
+            -5.0
+        );
+    }
+
+    #[test]
+    fn should_price_generations_by_procurement_mode() {
+        let mut generation =
+            GenerationMetric::new(0, &Timestamp::new(0, 0), 20.0, Default::default());
+        generation.settlement_point_prices =
+            HashMap::from([(SettlementPointLocation::HubAverage, 20.0)]);
+        generation.day_ahead_settlement_point_prices =
+            HashMap::from([(SettlementPointLocation::HubAverage, 30.0)]);
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(PowerPlant::new(0, vec![generation]));
+
+        let day_ahead_grid = power_grid
+            .priced_at(
+                SettlementPointLocation::HubAverage,
+                ProcurementMode::DayAhead,
+                &TimeRange::all(),
+                &mut BTreeMap::new(),
+            )
+            .unwrap();
+        let true_up_grid = power_grid
+            .priced_at(
+                SettlementPointLocation::HubAverage,
+                ProcurementMode::DayAheadWithRealTimeTrueUp,
+                &TimeRange::all(),
+                &mut BTreeMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            day_ahead_grid.power_plants[0].generations()[0].sale_price_usd_per_mwh,
+            30.0
+        );
+        assert_eq!(
+            day_ahead_grid.power_plants[0].generations()[0].real_time_price_usd_per_mwh,
+            None
+        );
+        assert_eq!(
+            true_up_grid.power_plants[0].generations()[0].sale_price_usd_per_mwh,
+            30.0
+        );
+        assert_eq!(
+            true_up_grid.power_plants[0].generations()[0].real_time_price_usd_per_mwh,
+            Some(20.0)
+        );
+        assert!(power_grid
+            .priced_at(
+                SettlementPointLocation::NorthHub,
+                ProcurementMode::DayAhead,
+                &TimeRange::all(),
+                &mut BTreeMap::new()
+            )
+            .is_err());
+    }
+
+    fn create_priced_generation(seconds: i64, day_ahead_price: Option<f64>) -> GenerationMetric {
+        let mut generation =
+            GenerationMetric::new(0, &Timestamp::new(seconds, 0), 20.0, Default::default());
+        generation.settlement_point_prices =
+            HashMap::from([(SettlementPointLocation::HubAverage, 20.0)]);
+        generation.day_ahead_settlement_point_prices = day_ahead_price
+            .map(|price| HashMap::from([(SettlementPointLocation::HubAverage, price)]))
+            .unwrap_or_default();
+
+        generation
+    }
+
+    #[test]
+    fn should_buy_real_time_in_month_missing_day_ahead_prices() {
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(PowerPlant::new(
+            0,
+            vec![create_priced_generation(1686830400, None)],
+        ));
+        let mut real_time_fallbacks = BTreeMap::new();
+
+        let priced_grid = power_grid
+            .priced_at(
+                SettlementPointLocation::HubAverage,
+                ProcurementMode::DayAheadWithRealTimeTrueUp,
+                &TimeRange::all(),
+                &mut real_time_fallbacks,
+            )
+            .unwrap();
+
+        assert_eq!(
+            priced_grid.power_plants[0].generations()[0].sale_price_usd_per_mwh,
+            20.0
+        );
+        assert_eq!(
+            real_time_fallbacks,
+            BTreeMap::from([(
+                Timestamp::new(1686830400, 0),
+                String::from("NotFound: No day-ahead prices were ingested for ERCOT June 2023, add its DAM settlement point prices or procure real-time")
+            )])
+        );
+    }
+
+    #[test]
+    fn should_only_price_generations_covering_time_range() {
+        let mut power_grid = PowerGrid::default();
+        power_grid.add_power_plant(PowerPlant::new(
+            0,
+            vec![
+                create_priced_generation(1684238400, None),
+                create_priced_generation(1685578500, Some(30.0)),
+                create_priced_generation(1685579400, Some(35.0)),
+            ],
+        ));
+        let mut real_time_fallbacks = BTreeMap::new();
+
+        let priced_grid = power_grid
+            .priced_at(
+                SettlementPointLocation::HubAverage,
+                ProcurementMode::DayAhead,
+                &TimeRange {
+                    start: Timestamp::new(1685577600, 0),
+                    end: Timestamp::new(1685578500, 0),
+                },
+                &mut real_time_fallbacks,
+            )
+            .unwrap();
+
+        assert_eq!(
+            to_seconds(priced_grid.power_plants[0].generations()),
+            vec![1685578500]
+        );
+        assert_eq!(
+            priced_grid.power_plants[0].generations()[0].sale_price_usd_per_mwh,
+            30.0
+        );
+        assert!(real_time_fallbacks.is_empty());
+    }
+}
@@ -1,11 +1,19 @@
 use std::cmp::Ordering;
 
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
-use rocket::FromForm;
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+use rocket::{FromForm, FromFormField};
 use serde::{Deserialize, Serialize};
 
 use super::errors::{Error, Result};
 
+// The format of datetime-local inputs, which simulation ranges are submitted in
+pub const DATE_TIME_LOCAL_FORMAT: &str = "%Y-%m-%dT%H:%M";
+const CHART_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const CENTRAL_STANDARD_OFFSET_HOURS: i64 = 6;
+const CENTRAL_DAYLIGHT_OFFSET_HOURS: i64 = 5;
+
 #[derive(FromForm, Deserialize, Serialize, Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Timestamp {
     pub seconds: i64,
@@ -70,10 +78,22 @@ pub struct TimeRange {
 }
 
 impl TimeRange {
-    pub fn to_datetime(&self) -> Result<DateTimeRange> {
+    // Every timestamp, for reading a data source in full
+    pub fn all() -> TimeRange {
+        TimeRange {
+            start: Timestamp::new(i64::MIN, 0),
+            end: Timestamp::new(i64::MAX, 0),
+        }
+    }
+
+    pub fn contains(&self, timestamp: &Timestamp) -> bool {
+        self.start <= *timestamp && *timestamp < self.end
+    }
+
+    pub fn to_datetime(&self, time_zone: DisplayTimeZone) -> Result<DateTimeRange> {
         Ok(DateTimeRange {
-            start: self.start.to_utc_date_time()?.to_rfc3339(),
-            end: self.end.to_utc_date_time()?.to_rfc3339(),
+            start: time_zone.format(&self.start, DATE_TIME_LOCAL_FORMAT)?,
+            end: time_zone.format(&self.end, DATE_TIME_LOCAL_FORMAT)?,
         })
     }
 }
@@ -85,15 +105,191 @@ pub struct DateTimeRange {
 }
 
 impl DateTimeRange {
-    pub fn parse(&self, format: &str) -> Result<TimeRange> {
+    pub fn parse(&self, format: &str, time_zone: DisplayTimeZone) -> Result<TimeRange> {
         let start = NaiveDateTime::parse_from_str(&self.start, format)
             .map_err(|err| Error::invalid_argument(&err.to_string()))?;
         let end = NaiveDateTime::parse_from_str(&self.end, format)
             .map_err(|err| Error::invalid_argument(&err.to_string()))?;
 
         Ok(TimeRange {
-            start: Timestamp::new(start.timestamp(), start.timestamp_subsec_nanos()),
-            end: Timestamp::new(end.timestamp(), end.timestamp_subsec_nanos()),
+            start: time_zone.from_local(&start)?,
+            end: time_zone.from_local(&end)?,
         })
     }
 }
+
+// The zone times are entered and shown in. Timestamps themselves are always UTC
+#[derive(FromFormField, Deserialize, Serialize, Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DisplayTimeZone {
+    #[default]
+    CentralPrevailingTime,
+    Utc,
+}
+
+impl DisplayTimeZone {
+    pub fn all() -> Vec<DisplayTimeZone> {
+        vec![Self::CentralPrevailingTime, Self::Utc]
+    }
+
+    pub fn to_local(&self, timestamp: &Timestamp) -> Result<NaiveDateTime> {
+        let date_time = timestamp.to_utc_date_time()?;
+
+        match self {
+            Self::CentralPrevailingTime => CentralPrevailingTime::from_utc(&date_time),
+            Self::Utc => Ok(date_time.naive_utc()),
+        }
+    }
+
+    // An entered time in the repeated fall back hour is read as its first
+    // occurrence
+    pub fn from_local(&self, local: &NaiveDateTime) -> Result<Timestamp> {
+        match self {
+            Self::CentralPrevailingTime => Ok(Timestamp::from(CentralPrevailingTime::to_utc(
+                local, false,
+            )?)),
+            Self::Utc => Ok(Timestamp::from(Utc.from_utc_datetime(local))),
+        }
+    }
+
+    pub fn format(&self, timestamp: &Timestamp, format: &str) -> Result<String> {
+        Ok(self.to_local(timestamp)?.format(format).to_string())
+    }
+
+    // Charts are given the local wall clock time without an offset so they plot
+    // it as is rather than in the browser's zone
+    pub fn format_chart_date(&self, timestamp: &Timestamp) -> Result<String> {
+        self.format(timestamp, CHART_DATE_FORMAT)
+    }
+}
+
+impl std::fmt::Display for DisplayTimeZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CentralPrevailingTime => write!(f, "CentralPrevailingTime"),
+            Self::Utc => write!(f, "Utc"),
+        }
+    }
+}
+
+// ERCOT settles in Central Prevailing Time, which is six hours behind UTC
+// except from 2:00 on the second Sunday of March to 2:00 on the first Sunday of
+// November when it is five. The spring forward hour never happens and the fall
+// back hour happens twice
+pub struct CentralPrevailingTime {}
+
+impl CentralPrevailingTime {
+    // The repeated hour flag picks the second, standard time, occurrence of a
+    // fall back hour
+    pub fn to_utc(local: &NaiveDateTime, repeated_hour: bool) -> Result<DateTime<Utc>> {
+        let daylight =
+            Utc.from_utc_datetime(local) + Duration::hours(CENTRAL_DAYLIGHT_OFFSET_HOURS);
+        let standard =
+            Utc.from_utc_datetime(local) + Duration::hours(CENTRAL_STANDARD_OFFSET_HOURS);
+        let is_daylight = CentralPrevailingTime::is_daylight_saving(&daylight)?;
+        let is_standard = !CentralPrevailingTime::is_daylight_saving(&standard)?;
+
+        match (is_daylight, is_standard) {
+            (true, true) if repeated_hour => Ok(standard),
+            (true, _) => Ok(daylight),
+            (false, true) => Ok(standard),
+            (false, false) => Err(Error::invalid_argument(&format!(
+                "{} does not exist in Central Prevailing Time",
+                local
+            ))),
+        }
+    }
+
+    pub fn from_utc(date_time: &DateTime<Utc>) -> Result<NaiveDateTime> {
+        let offset_hours = if CentralPrevailingTime::is_daylight_saving(date_time)? {
+            CENTRAL_DAYLIGHT_OFFSET_HOURS
+        } else {
+            CENTRAL_STANDARD_OFFSET_HOURS
+        };
+
+        Ok(date_time.naive_utc() - Duration::hours(offset_hours))
+    }
+
+    pub fn is_daylight_saving(date_time: &DateTime<Utc>) -> Result<bool> {
+        let year = date_time.year();
+        let start = CentralPrevailingTime::transition(year, 3, 2, CENTRAL_STANDARD_OFFSET_HOURS)?;
+        let end = CentralPrevailingTime::transition(year, 11, 1, CENTRAL_DAYLIGHT_OFFSET_HOURS)?;
+
+        Ok(start <= *date_time && *date_time < end)
+    }
+
+    // Clocks change at 2:00 local time on the nth Sunday of the month
+    fn transition(year: i32, month: u32, sunday: u8, offset_hours: i64) -> Result<DateTime<Utc>> {
+        let date = NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Sun, sunday)
+            .ok_or_else(|| Error::invalid_argument("Invalid daylight saving transition"))?;
+        let local = date.and_time(
+            NaiveTime::from_hms_opt(2, 0, 0)
+                .ok_or_else(|| Error::invalid_argument("Invalid transition time"))?,
+        );
+
+        Ok(Utc.from_utc_datetime(&local) + Duration::hours(offset_hours))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use pretty_assertions::assert_eq;
+
+    use super::CentralPrevailingTime;
+
+    #[test]
+    fn should_convert_standard_and_daylight_time() {
+        let winter = NaiveDate::from_ymd_opt(2023, 1, 15)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let summer = NaiveDate::from_ymd_opt(2023, 7, 15)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+
+        assert_eq!(
+            CentralPrevailingTime::to_utc(&winter, false).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 15, 18, 0, 0).unwrap()
+        );
+        assert_eq!(
+            CentralPrevailingTime::to_utc(&summer, false).unwrap(),
+            Utc.with_ymd_and_hms(2023, 7, 15, 17, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_separate_repeated_fall_back_hour() {
+        let repeated_hour = NaiveDate::from_ymd_opt(2023, 11, 5)
+            .unwrap()
+            .and_hms_opt(1, 15, 0)
+            .unwrap();
+        let first = CentralPrevailingTime::to_utc(&repeated_hour, false).unwrap();
+        let second = CentralPrevailingTime::to_utc(&repeated_hour, true).unwrap();
+
+        assert_eq!(first, Utc.with_ymd_and_hms(2023, 11, 5, 6, 15, 0).unwrap());
+        assert_eq!(second, Utc.with_ymd_and_hms(2023, 11, 5, 7, 15, 0).unwrap());
+        assert_eq!(
+            CentralPrevailingTime::from_utc(&second).unwrap(),
+            repeated_hour
+        );
+    }
+
+    #[test]
+    fn should_reject_skipped_spring_forward_hour() {
+        let skipped_hour = NaiveDate::from_ymd_opt(2023, 3, 12)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        let after_skip = NaiveDate::from_ymd_opt(2023, 3, 12)
+            .unwrap()
+            .and_hms_opt(3, 0, 0)
+            .unwrap();
+
+        assert!(CentralPrevailingTime::to_utc(&skipped_hour, false).is_err());
+        assert_eq!(
+            CentralPrevailingTime::to_utc(&after_skip, false).unwrap(),
+            Utc.with_ymd_and_hms(2023, 3, 12, 8, 0, 0).unwrap()
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use super::{errors::Result, histogram::Labels, time::Timestamp};
+use super::{
+    errors::Result,
+    histogram::Labels,
+    time::{DisplayTimeZone, Timestamp},
+};
 
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Clone)]
 pub enum ChartColor {
@@ -41,7 +45,10 @@ impl TimeSeries {
         Ok(Self {
             label: String::from(label),
             color,
-            data_points: data.iter().map(|element| operation(element)).collect::<Result<Vec<TimeSeriesEntry>>>()?
+            data_points: data
+                .iter()
+                .map(|element| operation(element))
+                .collect::<Result<Vec<TimeSeriesEntry>>>()?,
         })
     }
 }
@@ -54,10 +61,15 @@ pub struct TimeSeriesEntry {
 }
 
 impl TimeSeriesEntry {
-    pub fn render(value: f64, date: &Timestamp, color: ChartColor) -> Result<Self> {
+    pub fn render(
+        value: f64,
+        date: &Timestamp,
+        color: ChartColor,
+        time_zone: DisplayTimeZone,
+    ) -> Result<Self> {
         Ok(Self {
             value,
-            date: date.to_utc_date_time()?.to_rfc3339(),
+            date: time_zone.format_chart_date(date)?,
             color,
         })
     }
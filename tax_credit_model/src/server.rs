@@ -3,9 +3,11 @@ use rocket::{catchers, fs::FileServer, routes, Build, Rocket};
 use crate::{
     catchers::{not_found_catcher::not_found_catcher, unauthorized_catcher::unauthorized_catcher},
     handlers::{
+        cancel_simulation_handler::cancel_simulation_handler,
         close_error_handler::close_error_handler,
         create_electrolyzer_form_handler::create_electrolyzer_form_handler,
         create_electrolyzer_handler::create_electrolyzer_handler,
+        data_quality_handler::data_quality_handler,
         electrolyzer_selector_handler::electrolyzer_selector_handler,
         execute_simulation_handler::execute_simulation,
         get_electrolyzer_handler::get_electrolyzer_handler,
@@ -18,12 +20,17 @@ use crate::{
         select_electrolyzer_handler::select_electrolyzer_handler,
         select_simulation_handler::select_simulation_handler,
         simulation_handler::simulation_handler,
+        simulation_progress_handler::simulation_progress_handler,
     },
+    jobs::simulation_runner::SimulationJobRunner,
     persistance::{
-        electrolyzer::ElectrolyzerClient, generation::GenerationClient, grid::GridClient,
+        certificates::CertificateClient, data_quality::DataQualityReportClient,
+        electrolyzer::ElectrolyzerClient, emission_factors::EmissionFactorClient,
+        generation::GenerationClient, grid::GridClient, plant_registry::PlantRegistryClient,
         simulation::SimulationClient, simulation_selection::SimulationSelectionClient,
         user::UserClient,
     },
+    schema::data_quality::DataQualityPolicy,
 };
 
 #[derive(Debug, Clone)]
@@ -34,6 +41,7 @@ pub struct ServingConfiguration {
 #[derive(Debug, Clone)]
 pub struct ServerConfiguration {
     pub data_directory: String,
+    pub data_quality_policy: DataQualityPolicy,
     pub serving: ServingConfiguration,
 }
 
@@ -41,6 +49,7 @@ impl ServerConfiguration {
     pub fn new(data_directory: &str, assets_directory: &str) -> Self {
         Self {
             data_directory: String::from(data_directory),
+            data_quality_policy: DataQualityPolicy::default(),
             serving: ServingConfiguration {
                 assets_directory: String::from(assets_directory),
             },
@@ -55,6 +64,11 @@ pub struct Dependencies {
     pub generation_client: Box<dyn GenerationClient>,
     pub user_client: Box<dyn UserClient>,
     pub simulation_selection_client: Box<dyn SimulationSelectionClient>,
+    pub emission_factor_client: Box<dyn EmissionFactorClient>,
+    pub data_quality_report_client: Box<dyn DataQualityReportClient>,
+    pub plant_registry_client: Box<dyn PlantRegistryClient>,
+    pub certificate_client: Box<dyn CertificateClient>,
+    pub simulation_runner: SimulationJobRunner,
 }
 
 pub fn init_service(
@@ -71,6 +85,11 @@ pub fn init_service(
         .manage(dependencies.generation_client)
         .manage(dependencies.user_client)
         .manage(dependencies.simulation_selection_client)
+        .manage(dependencies.emission_factor_client)
+        .manage(dependencies.data_quality_report_client)
+        .manage(dependencies.plant_registry_client)
+        .manage(dependencies.certificate_client)
+        .manage(dependencies.simulation_runner)
         .register("/", catchers![unauthorized_catcher, not_found_catcher])
         .mount("/assets", static_files)
         .mount(
@@ -91,7 +110,10 @@ pub fn init_service(
                 select_simulation_handler,
                 initialize_simulation_handler,
                 get_selected_simulation_handler,
-                get_selected_electrolyzer_handler
+                get_selected_electrolyzer_handler,
+                simulation_progress_handler,
+                cancel_simulation_handler,
+                data_quality_handler
             ],
         )
 }